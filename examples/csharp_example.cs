@@ -92,6 +92,10 @@ namespace PluginDepot
             public int library_count;
             public int preference_count;
             public IntPtr icon_url;
+            public int enabled;
+            public int architecture;
+            public int scope;
+            public IntPtr vendor_name;
         }
 
         public enum PluginFormat
@@ -99,7 +103,8 @@ namespace PluginDepot
             VST2 = 0,
             VST3 = 1,
             AU = 2,
-            AAX = 3
+            AAX = 3,
+            LV2 = 4
         }
 
         public class Plugin
@@ -114,6 +119,8 @@ namespace PluginDepot
             public int LibraryCount { get; set; }
             public int PreferenceCount { get; set; }
             public string IconUrl { get; set; }  // URL to plugin icon
+            public string Vendor { get; set; }
+            public bool Enabled { get; set; }
 
             public string FormatDisplayName => Format switch
             {
@@ -121,6 +128,7 @@ namespace PluginDepot
                 PluginFormat.VST3 => "VST3",
                 PluginFormat.AU => "Audio Unit",
                 PluginFormat.AAX => "AAX",
+                PluginFormat.LV2 => "LV2",
                 _ => "Unknown"
             };
         }
@@ -168,6 +176,10 @@ namespace PluginDepot
                             PreferenceCount = cPlugin.preference_count,
                             IconUrl = cPlugin.icon_url != IntPtr.Zero
                                 ? Marshal.PtrToStringAnsi(cPlugin.icon_url)
+                                : null,
+                            Enabled = cPlugin.enabled != 0,
+                            Vendor = cPlugin.vendor_name != IntPtr.Zero
+                                ? Marshal.PtrToStringAnsi(cPlugin.vendor_name)
                                 : null
                         });
                     }
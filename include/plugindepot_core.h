@@ -32,11 +32,16 @@ typedef struct {
     char* version;
     char* description;      /* May be NULL */
     char* install_path;
-    int32_t format;         /* 0=VST2, 1=VST3, 2=AU, 3=AAX */
+    int32_t format;         /* 0=VST2, 1=VST3, 2=AU, 3=AAX, 4=LV2 */
     int32_t preset_count;
     int32_t library_count;
     int32_t preference_count;
     char* icon_url;         /* URL to plugin icon. May be NULL. */
+    int32_t enabled;        /* 1 if enabled, 0 if disabled */
+    int32_t architecture;   /* 0=unknown, 1=x86, 2=x86_64, 3=arm64; several: 4=x86_64+arm64 (universal),
+                               5=x86+x86_64, 6=x86+x86_64+arm64, 7=x86+arm64 */
+    int32_t scope;          /* 0=system-wide (changes need admin rights), 1=current user */
+    char* vendor_name;      /* Vendor from plugin metadata. May be NULL. */
 } CPlugin;
 
 typedef enum {
@@ -50,10 +55,30 @@ typedef enum {
 
 /**
  * Scan the system for installed plugins.
+ * If plugindepot_cancel_scan() is called from another thread, returns the plugins found so far.
  * @return Opaque handle to plugin list. Caller must call plugindepot_free_plugin_list().
  */
 CPluginList* plugindepot_scan_plugins(void);
 
+/**
+ * Cancel a scan running on another thread.
+ * The scan stops at the next directory or entry boundary and returns a truncated list.
+ */
+void plugindepot_cancel_scan(void);
+
+/**
+ * Add a folder that plugindepot_scan_plugins() also searches, for all later scans.
+ * @param path UTF-8 directory path
+ * @param format Plugin format expected in the folder (0=VST2, 1=VST3, 2=AU, 3=AAX, 4=LV2)
+ * @return 0 on success, 1 on invalid path or format (see plugindepot_last_error())
+ */
+int32_t plugindepot_add_scan_dir(const char* path, int32_t format);
+
+/**
+ * Remove every folder added with plugindepot_add_scan_dir().
+ */
+void plugindepot_clear_scan_dirs(void);
+
 /**
  * Get the number of plugins in a list.
  * @param list Plugin list handle
@@ -69,6 +94,37 @@ int32_t plugindepot_plugin_list_count(const CPluginList* list);
  */
 CPlugin* plugindepot_plugin_list_get(const CPluginList* list, int32_t index);
 
+/**
+ * Get a plugin's tags (inferred from metadata, e.g., "eq", plus user tags).
+ * Read the tags with plugindepot_path_list_count() and plugindepot_path_list_get().
+ * @param list Plugin list handle
+ * @param index Zero-based index
+ * @return String list, or NULL on invalid input. Caller must call plugindepot_free_path_list().
+ */
+CPathList* plugindepot_plugin_tags(const CPluginList* list, int32_t index);
+
+/**
+ * Re-read a single plugin from disk (metadata and related paths), e.g., after an update.
+ * The list entry is replaced with the new data.
+ * @param list Plugin list handle
+ * @param index Zero-based index
+ * @return Updated plugin structure, or NULL if the plugin is no longer installed.
+ *         Caller must call plugindepot_free_plugin().
+ */
+CPlugin* plugindepot_rescan_plugin(CPluginList* list, int32_t index);
+
+/**
+ * Fill a caller-provided array with a range of plugins (for paged/virtualized lists).
+ * @param list Plugin list handle
+ * @param start Zero-based index of the first plugin
+ * @param count Maximum number of plugins to write
+ * @param out_array Array with room for at least `count` CPlugin structures
+ * @return Number of entries written (less than count at the end of the list, 0 if start is out of range).
+ *         The array is owned by the caller; the strings in each entry must be freed with
+ *         plugindepot_free_plugin_range() using the returned count.
+ */
+int32_t plugindepot_plugin_list_get_range(const CPluginList* list, int32_t start, int32_t count, CPlugin* out_array);
+
 /**
  * Free a plugin list.
  * @param list Plugin list handle (may be NULL)
@@ -81,6 +137,14 @@ void plugindepot_free_plugin_list(CPluginList* list);
  */
 void plugindepot_free_plugin(CPlugin* plugin);
 
+/**
+ * Free the strings inside entries filled by plugindepot_plugin_list_get_range().
+ * Does not free the array itself. String pointers are set to NULL afterwards.
+ * @param array Array passed to plugindepot_plugin_list_get_range() (may be NULL)
+ * @param count Number of entries that call returned
+ */
+void plugindepot_free_plugin_range(CPlugin* array, int32_t count);
+
 /* ============================================================================
  * Orphaned File Detection
  * ============================================================================ */
@@ -125,15 +189,46 @@ void plugindepot_free_path_list(CPathList* list);
  */
 char* plugindepot_backup_plugin(const CPluginList* list, int32_t index, const char* backup_dir);
 
+/**
+ * Progress callback: files and bytes processed so far and in total, and the
+ * user pointer passed to the operation.
+ */
+typedef void (*CProgressCallback)(uint64_t files_done, uint64_t files_total,
+                                  uint64_t bytes_done, uint64_t bytes_total, void* user);
+
+/**
+ * Backup a plugin, reporting progress after each file is copied.
+ * The callback runs on the calling thread during the backup and must return
+ * quickly; post the values to the UI thread instead of redrawing in it.
+ * @param list Plugin list handle
+ * @param index Plugin index
+ * @param backup_dir Target directory path (null-terminated string)
+ * @param callback Called after each file; may be NULL
+ * @param user Passed unchanged to the callback
+ * @return Backup path on success, or NULL on error. Caller must call plugindepot_free_string().
+ */
+char* plugindepot_backup_plugin_with_progress(const CPluginList* list, int32_t index, const char* backup_dir,
+                                              CProgressCallback callback, void* user);
+
 /**
  * Uninstall a plugin.
  * @param list Plugin list handle
  * @param index Plugin index
  * @param dry_run If non-zero, only returns what would be deleted without actually deleting
  * @return Path list of deleted (or would-be deleted) files. Caller must call plugindepot_free_path_list().
+ *         NULL on error; if some files need administrator privileges, nothing is deleted.
  */
 CPathList* plugindepot_uninstall_plugin(const CPluginList* list, int32_t index, int32_t dry_run);
 
+/**
+ * List a plugin's paths that can't be deleted without administrator privileges.
+ * @param list Plugin list handle
+ * @param index Plugin index
+ * @return Path list, empty if the current user can uninstall the plugin. NULL on invalid input.
+ *         Caller must call plugindepot_free_path_list().
+ */
+CPathList* plugindepot_paths_needing_elevation(const CPluginList* list, int32_t index);
+
 /**
  * Export a plugin for migration to another machine.
  * @param list Plugin list handle
@@ -143,6 +238,55 @@ CPathList* plugindepot_uninstall_plugin(const CPluginList* list, int32_t index,
  */
 char* plugindepot_export_plugin(const CPluginList* list, int32_t index, const char* export_dir);
 
+/**
+ * Enable or disable a plugin without uninstalling it.
+ * Bundles are moved into a "(Disabled)" folder next to their plugin directory;
+ * file-based plugins are renamed with a ".disabled" extension.
+ * The plugin's recorded path in the list is updated on success.
+ * @param list Plugin list handle
+ * @param index Plugin index
+ * @param enabled Non-zero to enable, zero to disable
+ * @return 0 on success, 1 on error
+ */
+int32_t plugindepot_set_plugin_enabled(CPluginList* list, int32_t index, int32_t enabled);
+
+/**
+ * Compute the disk space used by a plugin and its related files.
+ * Walks every related folder, so this may be slow for sample-heavy plugins.
+ * Call it off the UI thread.
+ * @param list Plugin list handle
+ * @param index Plugin index
+ * @return Total size in bytes, or 0 on error (see plugindepot_last_error())
+ */
+uint64_t plugindepot_plugin_disk_usage(const CPluginList* list, int32_t index);
+
+/**
+ * Compute a plugin's disk usage broken down by category, as JSON:
+ * {"binary":..,"presets":..,"libraries":..,"support":..,"preferences":..,"total":..}
+ * May be slow; call off the UI thread.
+ * @param list Plugin list handle
+ * @param index Plugin index
+ * @return JSON string, or NULL on error. Caller must call plugindepot_free_string().
+ */
+char* plugindepot_plugin_disk_usage_json(const CPluginList* list, int32_t index);
+
+/**
+ * Describe every related path discovered for a plugin, for diagnostics, as JSON:
+ * {"plugin_id":..,"install_path":..,"categories":[{"category":"Presets","paths":[{"path":..,"confidence":"High","exists":true}]},..]}
+ * @param list Plugin list handle
+ * @param index Plugin index
+ * @return JSON string, or NULL on error. Caller must call plugindepot_free_string().
+ */
+char* plugindepot_related_paths_json(const CPluginList* list, int32_t index);
+
+/**
+ * Count the plugins in a list per vendor, most plugins first:
+ * [{"vendor":"Native Instruments","count":12},{"vendor":"FabFilter","count":5},{"vendor":"Unknown","count":3}]
+ * @param list Plugin list handle
+ * @return JSON string, or NULL on error. Caller must call plugindepot_free_string().
+ */
+char* plugindepot_vendor_summary_json(const CPluginList* list);
+
 /**
  * Enumerate all files associated with a plugin.
  * @param list Plugin list handle
@@ -151,6 +295,32 @@ char* plugindepot_export_plugin(const CPluginList* list, int32_t index, const ch
  */
 CPathList* plugindepot_enumerate_files(const CPluginList* list, int32_t index);
 
+/**
+ * Check whether a plugin carries the macOS quarantine attribute.
+ * Hosts refuse to load quarantined plugins until the attribute is cleared.
+ * @param list Plugin list handle
+ * @param index Plugin index
+ * @return 1 if quarantined, 0 if not (always 0 on Windows), -1 on invalid input
+ */
+int32_t plugindepot_is_quarantined(const CPluginList* list, int32_t index);
+
+/**
+ * Remove the macOS quarantine attribute from a plugin bundle (recursively).
+ * @param list Plugin list handle
+ * @param index Plugin index
+ * @return 0 on success, 1 on error
+ */
+int32_t plugindepot_remove_quarantine(const CPluginList* list, int32_t index);
+
+/**
+ * Check whether a host can load a plugin natively (no Rosetta or bit bridge).
+ * Unknown architectures are treated as compatible.
+ * @param plugin_arch Plugin architecture code (as in CPlugin.architecture)
+ * @param host_arch Host architecture code (same codes)
+ * @return 1 if compatible, 0 if not, -1 on invalid codes
+ */
+int32_t plugindepot_is_compatible(int32_t plugin_arch, int32_t host_arch);
+
 /* ============================================================================
  * Icon Management
  * ============================================================================ */
@@ -161,7 +331,8 @@ CPathList* plugindepot_enumerate_files(const CPluginList* list, int32_t index);
  * @param icon_url Icon URL (null-terminated string)
  * @param data Raw icon data bytes
  * @param data_length Length of data in bytes
- * @return Cached file path on success, or NULL on error. Caller must call plugindepot_free_string().
+ * @return Cached file path on success, or NULL on error (including data larger than
+ *         the max icon size). Caller must call plugindepot_free_string().
  */
 char* plugindepot_cache_icon(const char* icon_url, const uint8_t* data, int32_t data_length);
 
@@ -172,12 +343,50 @@ char* plugindepot_cache_icon(const char* icon_url, const uint8_t* data, int32_t
  */
 char* plugindepot_get_cached_icon_path(const char* icon_url);
 
+/**
+ * List the remote icon URLs of a plugin list that aren't cached yet, each once,
+ * so the UI can download them in one batch and call plugindepot_cache_icon() for each.
+ * @param list Plugin list from plugindepot_scan_plugins()
+ * @return URL list (read with plugindepot_path_list_get()), or NULL if list is NULL.
+ *         Caller must call plugindepot_free_path_list().
+ */
+CPathList* plugindepot_icons_needing_download(const CPluginList* list);
+
 /**
  * Clear all cached icons.
  * @return 0 on success, 1 on error
  */
 int32_t plugindepot_clear_icon_cache(void);
 
+/**
+ * Set the largest icon plugindepot_cache_icon() will accept. Defaults to 4 MB.
+ * @param max_bytes Maximum icon size in bytes
+ */
+void plugindepot_set_max_icon_size(uint64_t max_bytes);
+
+/* ============================================================================
+ * User State
+ * ============================================================================ */
+
+/**
+ * Set a custom display name for a plugin, persisted across scans.
+ * Takes effect on the next scan (the CPlugin name field shows the label).
+ * @param plugin_id Plugin ID (null-terminated string)
+ * @param name Display name, or NULL/empty to remove the custom label
+ * @return 0 on success, 1 on error
+ */
+int32_t plugindepot_set_display_name(const char* plugin_id, const char* name);
+
+/* ============================================================================
+ * Error Reporting
+ * ============================================================================ */
+
+/**
+ * Get the message for the most recent error on the calling thread.
+ * @return Error message, or NULL if no error has occurred. Caller must call plugindepot_free_string().
+ */
+char* plugindepot_last_error(void);
+
 /* ============================================================================
  * Memory Management
  * ============================================================================ */
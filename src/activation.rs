@@ -0,0 +1,131 @@
+//! AAX plugin activation status (PACE/iLok).
+//!
+//! Most AAX plugins are protected by PACE and won't load in Pro Tools until they are
+//! activated on an iLok. There is no public API for querying licenses, so this is a
+//! best-effort check against Pro Tools' cache of plugins it has successfully loaded:
+//! an unactivated PACE-protected plugin fails to load and never appears there.
+//!
+//! The record source is pluggable through `ActivationLookup`, so other sources (or
+//! fixtures) can be used. Only macOS is supported for now; elsewhere, and whenever
+//! Pro Tools or the iLok tooling isn't installed, the status is `Unknown`.
+
+use crate::{InstalledPlugin, PluginFormat};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Whether an AAX plugin is activated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivationStatus {
+    /// Listed in the activation record
+    Activated,
+    /// Activation record exists but doesn't list the plugin
+    NotActivated,
+    /// No activation record available (tooling not installed, unsupported platform)
+    Unknown,
+}
+
+/// A source of AAX activation records.
+pub trait ActivationLookup {
+    /// Returns the activation record as text, or None if the tooling that writes
+    /// it isn't installed.
+    fn read_record(&self) -> Result<Option<String>>;
+}
+
+/// Pro Tools' list of AAX plugins it has loaded.
+#[derive(Debug, Clone)]
+pub struct ProToolsPluginCache {
+    pub path: PathBuf,
+}
+
+impl Default for ProToolsPluginCache {
+    fn default() -> Self {
+        let home = std::env::var("HOME").unwrap_or_default();
+        ProToolsPluginCache {
+            path: PathBuf::from(format!("{}/Library/Preferences/Avid/Pro Tools/InstalledAAXPlugins", home)),
+        }
+    }
+}
+
+impl ActivationLookup for ProToolsPluginCache {
+    fn read_record(&self) -> Result<Option<String>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        // The cache may contain binary data around the plugin names
+        let data = fs::read(&self.path)
+            .context(format!("Failed to read Pro Tools plugin cache: {:?}", self.path))?;
+        Ok(Some(String::from_utf8_lossy(&data).into_owned()))
+    }
+}
+
+/// Reports whether an AAX plugin is activated, using Pro Tools' plugin cache.
+/// Always `Unknown` on platforms other than macOS.
+pub fn aax_activation_status(plugin: &InstalledPlugin) -> Result<ActivationStatus> {
+    if !cfg!(target_os = "macos") {
+        ensure_aax(plugin)?;
+        return Ok(ActivationStatus::Unknown);
+    }
+    aax_activation_status_with(plugin, &ProToolsPluginCache::default())
+}
+
+/// Like `aax_activation_status()`, reading the activation record from `lookup`.
+pub fn aax_activation_status_with(plugin: &InstalledPlugin, lookup: &dyn ActivationLookup) -> Result<ActivationStatus> {
+    ensure_aax(plugin)?;
+
+    let record = match lookup.read_record()? {
+        Some(record) => record,
+        None => return Ok(ActivationStatus::Unknown),
+    };
+
+    // Records identify plugins by bundle file name (e.g., "Pro-Q 3.aaxplugin")
+    let bundle_name = plugin.install_path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .context(format!("Invalid plugin path: {:?}", plugin.install_path))?;
+
+    // Entries are separated by binary data, newlines, or path separators; only a
+    // whole entry matches, so "EQ.aaxplugin" doesn't match "Pro-EQ.aaxplugin"
+    let listed = record.split(|c: char| c.is_control() || matches!(c, '/' | '\\' | char::REPLACEMENT_CHARACTER))
+        .any(|entry| entry.trim().to_lowercase() == bundle_name);
+    if listed {
+        Ok(ActivationStatus::Activated)
+    } else {
+        Ok(ActivationStatus::NotActivated)
+    }
+}
+
+fn ensure_aax(plugin: &InstalledPlugin) -> Result<()> {
+    if !matches!(plugin.format, PluginFormat::AAX) {
+        anyhow::bail!("Activation status is only available for AAX plugins: {}", plugin.plugin.name);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::installed_plugin;
+    use std::path::Path;
+
+    struct Record(&'static str);
+
+    impl ActivationLookup for Record {
+        fn read_record(&self) -> Result<Option<String>> {
+            Ok(Some(self.0.to_string()))
+        }
+    }
+
+    #[test]
+    fn matches_whole_bundle_names() {
+        let record = Record("\0\u{2}/Library/Application Support/Avid/Audio/Plug-Ins/Pro-EQ.aaxplugin\0\u{5}Pro-Q 3.aaxplugin\n");
+        let status = |name: &str| {
+            let plugin = installed_plugin(name, &Path::new("/Plug-Ins").join(format!("{}.aaxplugin", name)), PluginFormat::AAX);
+            aax_activation_status_with(&plugin, &record).unwrap()
+        };
+
+        assert_eq!(status("Pro-EQ"), ActivationStatus::Activated);
+        assert_eq!(status("pro-q 3"), ActivationStatus::Activated);
+        assert_eq!(status("EQ"), ActivationStatus::NotActivated);
+    }
+}
@@ -0,0 +1,182 @@
+//! Plugin binary architecture detection.
+//!
+//! Reads the executable headers of a plugin without loading it:
+//! - Mach-O (thin and universal/fat) for macOS bundles
+//! - PE for Windows DLLs, and the per-architecture folders of VST3/AAX bundles
+//!
+//! Used to warn about plugins a host can't load natively (Intel-only plugins on
+//! Apple Silicon, 32-bit plugins in a 64-bit host).
+
+use crate::pe::{machine, MACHINE_AMD64, MACHINE_ARM64, MACHINE_ARM64EC, MACHINE_I386};
+use crate::{Architecture, Architectures};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Mach-O magic numbers, as read little-endian from the start of the file
+pub(crate) const MH_MAGIC: u32 = 0xfeedface;
+pub(crate) const MH_MAGIC_64: u32 = 0xfeedfacf;
+/// Universal binaries use a big-endian header
+pub(crate) const FAT_MAGIC: u32 = 0xcafebabe;
+
+/// Mach-O CPU types
+const CPU_TYPE_X86: u32 = 7;
+const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+
+/// Per-architecture binary folders inside Windows VST3/AAX bundles
+const WINDOWS_BUNDLE_ARCH_DIRS: &[(&str, Architecture)] = &[
+    ("x86_64-win", Architecture::X86_64),
+    ("x86-win", Architecture::X86),
+    ("arm64-win", Architecture::Arm64),
+    ("arm64ec-win", Architecture::Arm64),
+    ("x64", Architecture::X86_64),
+    ("Win32", Architecture::X86),
+];
+
+/// Detects the architecture of a plugin bundle or binary.
+/// Returns `Unknown` if no executable could be found or parsed.
+pub fn detect_architecture(path: &Path) -> Architecture {
+    if !path.is_dir() {
+        return binary_architecture(path);
+    }
+
+    // macOS bundle: Contents/MacOS/<executable>
+    if let Some(executable) = bundle_executable(path) {
+        return binary_architecture(&executable);
+    }
+
+    // Windows bundle: one folder per architecture under Contents/
+    let found: Vec<Architecture> = WINDOWS_BUNDLE_ARCH_DIRS.iter()
+        .filter(|(dir, _)| path.join("Contents").join(dir).is_dir())
+        .map(|(_, arch)| *arch)
+        .collect();
+    combine(&found)
+}
+
+/// Returns the architecture of a single executable file (Mach-O or PE).
+pub fn binary_architecture(path: &Path) -> Architecture {
+    let mut header = Vec::with_capacity(4096);
+    let read = fs::File::open(path).and_then(|file| file.take(4096).read_to_end(&mut header));
+    if read.is_err() || header.len() < 8 {
+        return Architecture::Unknown;
+    }
+
+    if header.starts_with(b"MZ") {
+        return machine(&header).map_or(Architecture::Unknown, pe_machine_architecture);
+    }
+
+    macho_architecture(&header)
+}
+
+/// Maps a PE machine type to an architecture.
+pub fn pe_machine_architecture(machine: u16) -> Architecture {
+    match machine {
+        MACHINE_I386 => Architecture::X86,
+        MACHINE_AMD64 => Architecture::X86_64,
+        MACHINE_ARM64 | MACHINE_ARM64EC => Architecture::Arm64,
+        _ => Architecture::Unknown,
+    }
+}
+
+/// Parses a Mach-O or universal binary header.
+fn macho_architecture(header: &[u8]) -> Architecture {
+    let magic_le = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    if magic_le == MH_MAGIC || magic_le == MH_MAGIC_64 {
+        let cpu_type = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        return macho_cpu_architecture(cpu_type);
+    }
+
+    let magic_be = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    if magic_be != FAT_MAGIC {
+        return Architecture::Unknown;
+    }
+
+    // fat_header is followed by 20-byte fat_arch entries starting with cputype
+    let count = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let found: Vec<Architecture> = (0..count)
+        .filter_map(|i| header.get(8 + i * 20..12 + i * 20))
+        .map(|b| macho_cpu_architecture(u32::from_be_bytes([b[0], b[1], b[2], b[3]])))
+        .collect();
+    combine(&found)
+}
+
+pub(crate) fn macho_cpu_architecture(cpu_type: u32) -> Architecture {
+    match cpu_type {
+        CPU_TYPE_X86 => Architecture::X86,
+        CPU_TYPE_X86_64 => Architecture::X86_64,
+        CPU_TYPE_ARM64 => Architecture::Arm64,
+        _ => Architecture::Unknown,
+    }
+}
+
+/// Combines the architectures found in a multi-architecture binary or bundle.
+/// Keeps which ones they are, so an i386/x86_64 binary isn't taken for one that
+/// also runs natively on Apple Silicon.
+fn combine(found: &[Architecture]) -> Architecture {
+    let set = found.iter().fold(Architectures::default(), |set, arch| set.with(*arch));
+    let mut members = set.iter();
+
+    match (members.next(), members.next()) {
+        (None, _) => Architecture::Unknown,
+        (Some(only), None) => only,
+        _ => Architecture::Universal(set),
+    }
+}
+
+/// Locates the main executable of a macOS bundle.
+pub(crate) fn bundle_executable(bundle: &Path) -> Option<PathBuf> {
+    let macos_dir = bundle.join("Contents/MacOS");
+    if !macos_dir.is_dir() {
+        return None;
+    }
+
+    // Prefer the executable named in Info.plist
+    let declared = plist::Value::from_file(bundle.join("Contents/Info.plist")).ok()
+        .and_then(|v| v.as_dictionary()?.get("CFBundleExecutable")?.as_string().map(String::from))
+        .map(|name| macos_dir.join(name))
+        .filter(|p| p.is_file());
+    if declared.is_some() {
+        return declared;
+    }
+
+    fs::read_dir(&macos_dir).ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|p| p.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A universal binary header with one slice per CPU type
+    fn fat_header(cpu_types: &[u32]) -> Vec<u8> {
+        let mut header = FAT_MAGIC.to_be_bytes().to_vec();
+        header.extend((cpu_types.len() as u32).to_be_bytes());
+        for cpu_type in cpu_types {
+            header.extend(cpu_type.to_be_bytes());
+            header.extend([0; 16]);
+        }
+        header
+    }
+
+    #[test]
+    fn intel_only_universal_binaries_need_an_intel_host() {
+        let intel = macho_architecture(&fat_header(&[CPU_TYPE_X86, CPU_TYPE_X86_64]));
+        assert!(!intel.is_compatible_with(Architecture::Arm64));
+        assert!(intel.is_compatible_with(Architecture::X86));
+        assert!(intel.is_compatible_with(Architecture::X86_64));
+        assert_eq!(intel.to_string(), "universal (x86, x86_64)");
+
+        let universal = macho_architecture(&fat_header(&[CPU_TYPE_X86_64, CPU_TYPE_ARM64]));
+        assert!(universal.is_compatible_with(Architecture::Arm64));
+        assert!(!universal.is_compatible_with(Architecture::X86));
+    }
+
+    #[test]
+    fn single_architecture_slices_combine_to_that_architecture() {
+        assert_eq!(macho_architecture(&fat_header(&[CPU_TYPE_ARM64, CPU_TYPE_ARM64])), Architecture::Arm64);
+        assert_eq!(combine(&[Architecture::Unknown]), Architecture::Unknown);
+    }
+}
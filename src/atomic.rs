@@ -0,0 +1,90 @@
+//! Crash-safe file writes.
+//!
+//! Manifests, the user state store, and the icon cache manifest are rewritten in
+//! place. A crash or power loss during a plain `fs::write` can leave them truncated,
+//! losing every stored setting. `write_atomic()` writes to a temporary file next to
+//! the target and renames it over the target, so readers see either the old or the
+//! new contents, never a mix.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Distinguishes temporary files of concurrent writes within this process
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes `bytes` to `path` atomically: the data goes to a temporary file in the
+/// same directory, is flushed to disk, and then replaces `path` with a rename.
+/// If anything fails, `path` keeps its previous contents.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let temp_path = temp_path_for(path)?;
+
+    let result = write_and_sync(&temp_path, bytes)
+        .and_then(|_| {
+            fs::rename(&temp_path, path)
+                .context(format!("Failed to replace {:?}", path))
+        });
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Returns a unique temporary path in the same directory as `path`
+/// (a rename is only atomic within one filesystem).
+fn temp_path_for(path: &Path) -> Result<PathBuf> {
+    let file_name = path.file_name()
+        .context(format!("Invalid file path: {:?}", path))?;
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    Ok(path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        counter
+    )))
+}
+
+fn write_and_sync(path: &Path, bytes: &[u8]) -> Result<()> {
+    let mut file = fs::File::create(path)
+        .context(format!("Failed to create temporary file: {:?}", path))?;
+    file.write_all(bytes)
+        .context(format!("Failed to write temporary file: {:?}", path))?;
+    file.sync_all()
+        .context(format!("Failed to flush temporary file: {:?}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interrupted_writes_leave_the_previous_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        write_atomic(&path, b"previous").unwrap();
+
+        // Interrupted before the rename: the new data only reached the temporary file
+        write_and_sync(&temp_path_for(&path).unwrap(), b"new").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"previous");
+
+        write_atomic(&path, b"new").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+    }
+
+    #[test]
+    fn failed_writes_remove_the_temporary_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Folder");
+        fs::create_dir_all(path.join("Child")).unwrap();
+
+        assert!(write_atomic(&path, b"data").is_err());
+
+        let names: Vec<_> = fs::read_dir(dir.path()).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(names, ["Folder"]);
+    }
+}
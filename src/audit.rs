@@ -0,0 +1,152 @@
+//! Append-only audit log of operations performed on the system.
+//!
+//! Every backup, uninstall, export, and import appends one JSON line to
+//! `audit.jsonl` in the platform's log directory, recording when it ran, which
+//! plugin it touched, the affected paths, and whether it succeeded. Studios can
+//! use it as a record of what PluginDepot changed on a machine.
+//!
+//! Logging is best-effort: a log that can't be written produces a warning and
+//! never fails or delays the operation itself.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// File name of the audit log inside the log directory
+pub const AUDIT_LOG_FILE: &str = "audit.jsonl";
+
+/// Serializes appends so lines from concurrent operations don't interleave
+static AUDIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// The kind of operation an audit entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    Backup,
+    Uninstall,
+    Export,
+    Import,
+}
+
+/// One recorded operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// RFC 3339 timestamp of when the operation finished
+    pub timestamp: String,
+    pub action: AuditAction,
+    pub plugin_id: String,
+    /// Paths created, deleted, or written (in a dry run, those that would have been)
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    /// True if nothing was changed on disk
+    #[serde(default)]
+    pub dry_run: bool,
+    pub success: bool,
+    /// Why the operation failed
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Directory holding PluginDepot's logs.
+/// Falls back to the system temp directory if the user's environment variables are unset.
+pub fn log_dir() -> PathBuf {
+    #[cfg(target_os = "macos")]
+    {
+        if let Ok(home) = std::env::var("HOME") {
+            return PathBuf::from(format!("{}/Library/Logs/PluginDepot", home));
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(appdata) = std::env::var("LOCALAPPDATA") {
+            return PathBuf::from(format!("{}\\PluginDepot\\Logs", appdata));
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        if let Ok(home) = std::env::var("HOME") {
+            return PathBuf::from(format!("{}/.local/state/plugindepot", home));
+        }
+    }
+
+    std::env::temp_dir().join("PluginDepot").join("Logs")
+}
+
+/// Path of the audit log.
+pub fn audit_log_path() -> PathBuf {
+    log_dir().join(AUDIT_LOG_FILE)
+}
+
+/// Appends an entry for a finished operation. `outcome` holds the affected paths on
+/// success. Failures to write the log are reported as warnings only.
+pub(crate) fn record(action: AuditAction, plugin_id: &str, dry_run: bool, outcome: Result<Vec<PathBuf>, &anyhow::Error>) {
+    let (paths, error) = match outcome {
+        Ok(paths) => (paths, None),
+        Err(e) => (Vec::new(), Some(format!("{:#}", e))),
+    };
+    let entry = AuditEntry {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        action,
+        plugin_id: plugin_id.to_string(),
+        paths,
+        dry_run,
+        success: error.is_none(),
+        error,
+    };
+
+    if let Err(e) = append_entry(&audit_log_path(), &entry) {
+        eprintln!("Warning: Failed to write audit log: {:#}", e);
+    }
+}
+
+fn append_entry(path: &Path, entry: &AuditEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .context(format!("Failed to create log directory: {:?}", parent))?;
+    }
+
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    let _guard = AUDIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .context(format!("Failed to open audit log: {:?}", path))?;
+    // One write per line, so a crash can at worst leave the last line incomplete
+    file.write_all(line.as_bytes())
+        .context(format!("Failed to append to audit log: {:?}", path))?;
+    Ok(())
+}
+
+/// Reads the audit log, oldest entry first. A missing log is empty.
+pub fn read_audit_log() -> Result<Vec<AuditEntry>> {
+    read_audit_log_from(&audit_log_path())
+}
+
+/// Like `read_audit_log()`, reading the log at `path`.
+/// Unreadable lines (e.g., one cut short by a crash) are skipped with a warning.
+pub fn read_audit_log_from(path: &Path) -> Result<Vec<AuditEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)
+        .context(format!("Failed to read audit log: {:?}", path))?;
+
+    let mut entries = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => eprintln!("Warning: Skipping invalid audit log line {} in {:?}: {}", index + 1, path, e),
+        }
+    }
+    Ok(entries)
+}
@@ -1,67 +1,272 @@
-use plugindepot_core::registry::{scan_installed, detect_orphaned_files};
+use anyhow::{bail, Context};
+use plugindepot_core::health::audit_plugins;
+use plugindepot_core::operations::{backup_plugin, export_plugin, uninstall_plugins};
+use plugindepot_core::registry::{scan, scan_iter_with, scan_with_progress, share_icons_between_formats, detect_orphaned_files, filter_plugins, ScanConfig, ScanProgress};
+use plugindepot_core::state::config_dir;
+use plugindepot_core::{Architecture, InstalledPlugin, PluginFormat};
+use serde::Serialize;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+/// Scan settings file in the config directory, used when present
+const SCAN_CONFIG_FILE: &str = "config.json";
+
+const USAGE: &str = "\
+Usage:
+  plugindepot [list]                                  List installed plugins
+  plugindepot scan [--json]                           Scan with progress, printing the plugins found
+  plugindepot audit                                   Check every installed plugin for problems
+  plugindepot backup <pattern> <dir>                  Back up matching plugins into <dir>
+  plugindepot export <pattern> <dir>                  Export matching plugins into <dir>
+  plugindepot uninstall <pattern> [--dry-run] [--yes] Uninstall matching plugins
+
+<pattern> is a case-insensitive glob on plugin names, e.g., 'Waves *'.
+  --json     Print the scan result as JSON (no progress output)
+  --dry-run  List the files that would be deleted without deleting them
+  --yes      Don't ask for confirmation";
 
 fn main() -> anyhow::Result<()> {
-    let plugins = scan_installed();
-
-    match plugins {
-        Ok(list) => {
-            println!("Installed plugins: {}\n", list.len());
-            
-            if !list.is_empty() {
-                for plugin in &list {
-                    println!("  [{:?}] {}", plugin.format, plugin.plugin.name);
-                    println!("    Path: {}", plugin.install_path.display());
-                    if let Some(desc) = &plugin.plugin.description {
-                        println!("    {}", desc);
-                    }
-                    
-                    // Show icon if available
-                    if let Some(icon_url) = &plugin.plugin.icon_url {
-                        println!("    Icon: {}", icon_url);
-                    } else {
-                        println!("    Icon: Not found");
-                    }
-                    
-                    // Show related paths if found
-                    if !plugin.related_paths.preset_locations.is_empty() {
-                        println!("    Presets: {} location(s)", plugin.related_paths.preset_locations.len());
-                    }
-                    if !plugin.related_paths.library_locations.is_empty() {
-                        println!("    Libraries: {} location(s)", plugin.related_paths.library_locations.len());
-                    }
-                    if !plugin.related_paths.preference_files.is_empty() {
-                        println!("    Preferences: {} file(s)", plugin.related_paths.preference_files.len());
-                    }
-                    
-                    println!();
-                }
-                
-                // Demonstrate orphaned file detection
-                println!("\n--- Checking for orphaned files ---");
-                match detect_orphaned_files() {
-                    Ok(orphaned) => {
-                        if orphaned.is_empty() {
-                            println!("No orphaned files detected.");
-                        } else {
-                            println!("Found {} potentially orphaned file(s):", orphaned.len());
-                            for path in orphaned.iter().take(10) {
-                                println!("  - {}", path.display());
-                            }
-                            if orphaned.len() > 10 {
-                                println!("  ... and {} more", orphaned.len() - 10);
-                            }
-                        }
+    let mut positional = Vec::new();
+    let mut yes = false;
+    let mut dry_run = false;
+    let mut json = false;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--yes" | "-y" => yes = true,
+            "--dry-run" => dry_run = true,
+            "--json" => json = true,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ if arg.starts_with('-') => bail!("Unknown option: {}\n\n{}", arg, USAGE),
+            _ => positional.push(arg),
+        }
+    }
+
+    let config = load_scan_config();
+    let args: Vec<&str> = positional.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [] | ["list"] => list(config),
+        ["scan"] => scan_command(config, json),
+        ["audit"] => audit(config),
+        ["backup", pattern, dir] => copy_matching(config, pattern, &absolute(dir)?, "Backed up", backup_plugin),
+        ["export", pattern, dir] => copy_matching(config, pattern, Path::new(dir), "Exported", export_plugin),
+        ["uninstall", pattern] => uninstall_matching(config, pattern, dry_run, yes),
+        _ => bail!("Invalid arguments\n\n{}", USAGE),
+    }
+}
+
+fn load_scan_config() -> ScanConfig {
+    let config_path = config_dir().join(SCAN_CONFIG_FILE);
+    if config_path.exists() {
+        ScanConfig::from_file(&config_path).unwrap_or_else(|e| {
+            eprintln!("Warning: Ignoring scan config: {:#}", e);
+            ScanConfig::default()
+        })
+    } else {
+        ScanConfig::default()
+    }
+}
+
+fn list(config: ScanConfig) -> anyhow::Result<()> {
+    // Print plugins as they are found instead of waiting for the full scan
+    let mut list = Vec::new();
+    for result in scan_iter_with(config) {
+        match result {
+            Ok(plugin) => {
+                println!("{}", plugin);
+                list.push(plugin);
+            }
+            Err(e) => eprintln!("Warning: {:#}", e),
+        }
+    }
+    share_icons_between_formats(&mut list);
+
+    println!("Installed plugins: {}", list.len());
+
+    if !list.is_empty() {
+        // Demonstrate orphaned file detection
+        println!("\n--- Checking for orphaned files ---");
+        match detect_orphaned_files() {
+            Ok(orphaned) => {
+                if orphaned.is_empty() {
+                    println!("No orphaned files detected.");
+                } else {
+                    println!("Found {} potentially orphaned file(s):", orphaned.len());
+                    for path in orphaned.iter().take(10) {
+                        println!("  - {}", path.display());
                     }
-                    Err(e) => {
-                        eprintln!("Warning: Failed to detect orphaned files: {}", e);
+                    if orphaned.len() > 10 {
+                        println!("  ... and {} more", orphaned.len() - 10);
                     }
                 }
             }
+            Err(e) => {
+                eprintln!("Warning: Failed to detect orphaned files: {}", e);
+            }
         }
-        Err(e) => {
-            eprintln!("Error: {e}");
+    }
+
+    Ok(())
+}
+
+/// A plugin as printed by `plugindepot scan --json`.
+#[derive(Serialize)]
+struct ScanEntry<'a> {
+    id: &'a str,
+    name: &'a str,
+    version: &'a str,
+    format: PluginFormat,
+    vendor: Option<&'a str>,
+    install_path: &'a Path,
+    enabled: bool,
+}
+
+/// Scans, showing progress on stderr when it's a terminal, then prints the plugins found.
+fn scan_command(config: ScanConfig, json: bool) -> anyhow::Result<()> {
+    // Progress would corrupt JSON output and clutter logs when stderr is redirected
+    let show_progress = !json && io::stderr().is_terminal();
+    let plugins = scan_with_progress(&config, None, |progress| {
+        if show_progress {
+            render_progress(progress);
+        }
+    })?;
+
+    if json {
+        let entries: Vec<ScanEntry> = plugins.iter()
+            .map(|plugin| ScanEntry {
+                id: &plugin.plugin.id,
+                name: &plugin.plugin.name,
+                version: &plugin.plugin.version,
+                format: plugin.format,
+                vendor: plugin.plugin.author.as_deref(),
+                install_path: &plugin.install_path,
+                enabled: plugin.enabled,
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else {
+        for plugin in &plugins {
+            println!("{}", plugin);
         }
+        println!("Installed plugins: {}", plugins.len());
     }
+    Ok(())
+}
+
+/// Redraws the progress line, e.g., "[3/8] 37% 12 plugin(s) found, scanning /Library/Audio/Plug-Ins/VST3".
+/// The final report clears it.
+fn render_progress(progress: &ScanProgress) {
+    let mut stderr = io::stderr().lock();
+    // Return to the start of the line and clear it
+    let _ = write!(stderr, "\r\x1b[2K");
+    if let Some(dir) = &progress.current_dir {
+        let percent = progress.directories_done * 100 / progress.directories_total.max(1);
+        let _ = write!(stderr, "[{}/{}] {}% {} plugin(s) found, scanning {}",
+            progress.directories_done, progress.directories_total, percent, progress.plugins_found, dir.display());
+    }
+    let _ = stderr.flush();
+}
 
+/// Prints a table of every plugin with its health issues.
+fn audit(config: ScanConfig) -> anyhow::Result<()> {
+    let plugins = scan(&config, None)?;
+    let reports = audit_plugins(&plugins, Architecture::host());
+    
+    let name_width = reports.iter().map(|r| r.plugin_name.chars().count()).chain([4]).max().unwrap_or(4);
+    println!("{:<name_width$}  {:<6}  ISSUES", "NAME", "FORMAT");
+    for report in &reports {
+        let format = format!("{:?}", report.format);
+        let mut issues = report.issues.iter().map(|issue| format!("{:?}: {}", issue.severity, issue.message));
+        println!("{:<name_width$}  {:<6}  {}", report.plugin_name, format, issues.next().unwrap_or_else(|| "OK".to_string()));
+        for issue in issues {
+            println!("{:<name_width$}  {:<6}  {}", "", "", issue);
+        }
+    }
+    
+    let unhealthy = reports.iter().filter(|r| !r.is_healthy()).count();
+    println!("\n{} of {} plugin(s) have issues", unhealthy, reports.len());
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Backs up or exports every plugin matching `pattern` into `dir` with `operation`.
+fn copy_matching<F>(config: ScanConfig, pattern: &str, dir: &Path, done: &str, operation: F) -> anyhow::Result<()>
+where
+    F: Fn(&InstalledPlugin, &Path) -> anyhow::Result<PathBuf>,
+{
+    let plugins = scan(&config, None)?;
+    let matches = filter_plugins(&plugins, pattern)?;
+    if matches.is_empty() {
+        println!("No plugins match {:?}", pattern);
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for plugin in &matches {
+        match operation(plugin, dir) {
+            Ok(path) => println!("{} {} to {}", done, label(plugin), path.display()),
+            Err(e) => {
+                eprintln!("Warning: Failed to process {}: {:#}", label(plugin), e);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{} of {} plugin(s) failed", failed, matches.len());
+    }
+    Ok(())
+}
+
+fn uninstall_matching(config: ScanConfig, pattern: &str, dry_run: bool, yes: bool) -> anyhow::Result<()> {
+    let plugins = scan(&config, None)?;
+    let matches = filter_plugins(&plugins, pattern)?;
+    if matches.is_empty() {
+        println!("No plugins match {:?}", pattern);
+        return Ok(());
+    }
+
+    println!("Matching plugins:");
+    for plugin in &matches {
+        println!("  - {}", label(plugin));
+    }
+
+    // Nothing is deleted in a dry run, so there's nothing to confirm
+    if !dry_run && !yes && !confirm(&format!("Uninstall {} plugin(s)?", matches.len()))? {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    let heading = if dry_run { "Would delete" } else { "Deleted" };
+    for (id, paths) in uninstall_plugins(&matches, dry_run)? {
+        println!("{} for {}:", heading, id);
+        for path in paths {
+            println!("  - {}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves a directory given on the command line against the working directory;
+/// the library only accepts absolute backup destinations.
+fn absolute(dir: &str) -> anyhow::Result<PathBuf> {
+    std::path::absolute(dir).context(format!("Invalid directory: {:?}", dir))
+}
+
+/// One-line description of a plugin, e.g., "Pro-Q 3 [VST3]"
+fn label(plugin: &InstalledPlugin) -> String {
+    format!("{} [{:?}]", plugin.plugin.name, plugin.format)
+}
+
+/// Asks a yes/no question on the terminal; anything but "y"/"yes" is a no.
+fn confirm(question: &str) -> anyhow::Result<bool> {
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)
+        .context("Failed to read confirmation")?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,196 @@
+//! Detection of installed DAWs (plugin hosts).
+//!
+//! Knowing which hosts are present lets other features read the right host-specific
+//! files, such as a host's list of plugins it failed to load. A DAW counts as
+//! installed if its application or its preferences are found in the usual places
+//! for the current platform. Versioned names ("Ableton Live 12 Suite.app",
+//! "Cubase 13") are matched by prefix.
+//!
+//! Filesystem access goes through the `FileSystem` trait, so detection can be run
+//! against a fixture instead of the real disk.
+
+use crate::inventory::compare_versions;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A DAW that PluginDepot knows how to detect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Daw {
+    Ableton,
+    Logic,
+    Reaper,
+    ProTools,
+    Cubase,
+    FlStudio,
+}
+
+impl Daw {
+    pub const ALL: [Daw; 6] = [Daw::Ableton, Daw::Logic, Daw::Reaper, Daw::ProTools, Daw::Cubase, Daw::FlStudio];
+
+    /// Display name, e.g., "Pro Tools"
+    pub fn name(self) -> &'static str {
+        match self {
+            Daw::Ableton => "Ableton Live",
+            Daw::Logic => "Logic Pro",
+            Daw::Reaper => "REAPER",
+            Daw::ProTools => "Pro Tools",
+            Daw::Cubase => "Cubase",
+            Daw::FlStudio => "FL Studio",
+        }
+    }
+}
+
+/// A DAW found on this machine.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DawHost {
+    pub daw: Daw,
+    /// The application bundle or install folder, if found
+    pub app_path: Option<PathBuf>,
+    /// The preferences file or folder, if found
+    pub preferences_path: Option<PathBuf>,
+}
+
+/// Read-only filesystem access used by detection.
+pub trait FileSystem {
+    /// Returns the entries of a directory, or an empty list if it can't be read.
+    fn list_dir(&self, dir: &Path) -> Vec<PathBuf>;
+}
+
+/// The real filesystem.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealFileSystem;
+
+impl FileSystem for RealFileSystem {
+    fn list_dir(&self, dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// A place to look: an entry of `dir` whose name starts with `prefix` (case-insensitive).
+struct Candidate {
+    dir: PathBuf,
+    prefix: &'static str,
+}
+
+fn candidate(dir: impl Into<PathBuf>, prefix: &'static str) -> Candidate {
+    Candidate { dir: dir.into(), prefix }
+}
+
+/// Detects the DAWs installed on this machine.
+pub fn detect_installed_daws() -> Vec<DawHost> {
+    detect_installed_daws_with(&RealFileSystem)
+}
+
+/// Like `detect_installed_daws()`, reading the filesystem through `fs`.
+pub fn detect_installed_daws_with(fs: &dyn FileSystem) -> Vec<DawHost> {
+    Daw::ALL.into_iter()
+        .filter_map(|daw| {
+            let (apps, preferences) = daw_locations(daw);
+            let host = DawHost {
+                daw,
+                app_path: find_first(fs, &apps),
+                preferences_path: find_first(fs, &preferences),
+            };
+            (host.app_path.is_some() || host.preferences_path.is_some()).then_some(host)
+        })
+        .collect()
+}
+
+fn find_first(fs: &dyn FileSystem, candidates: &[Candidate]) -> Option<PathBuf> {
+    candidates.iter().find_map(|candidate| {
+        let prefix = candidate.prefix.to_lowercase();
+        let mut matches: Vec<PathBuf> = fs.list_dir(&candidate.dir).into_iter()
+            .filter(|path| path.file_name()
+                .is_some_and(|name| name.to_string_lossy().to_lowercase().starts_with(&prefix)))
+            .collect();
+        // With several versions installed, prefer the newest ("Cubase 13" over "Cubase 12")
+        matches.sort_by(|a, b| compare_versions(&a.to_string_lossy(), &b.to_string_lossy()));
+        matches.pop()
+    })
+}
+
+/// Application and preference locations of a DAW on macOS.
+#[cfg(target_os = "macos")]
+fn daw_locations(daw: Daw) -> (Vec<Candidate>, Vec<Candidate>) {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+    let preferences = home.join("Library/Preferences");
+    let support = home.join("Library/Application Support");
+
+    match daw {
+        Daw::Ableton => (
+            vec![candidate("/Applications", "Ableton Live")],
+            vec![candidate(&preferences, "Ableton")],
+        ),
+        Daw::Logic => (
+            vec![candidate("/Applications", "Logic Pro")],
+            vec![candidate(&preferences, "com.apple.logic10.plist")],
+        ),
+        Daw::Reaper => (
+            vec![candidate("/Applications", "REAPER")],
+            vec![candidate(support.join("REAPER"), "reaper.ini")],
+        ),
+        Daw::ProTools => (
+            vec![candidate("/Applications", "Pro Tools")],
+            vec![candidate(preferences.join("Avid"), "Pro Tools")],
+        ),
+        Daw::Cubase => (
+            vec![candidate("/Applications", "Cubase")],
+            vec![candidate(&preferences, "Cubase")],
+        ),
+        Daw::FlStudio => (
+            vec![candidate("/Applications", "FL Studio")],
+            vec![candidate(support, "Image-Line")],
+        ),
+    }
+}
+
+/// Application and preference locations of a DAW on Windows.
+#[cfg(target_os = "windows")]
+fn daw_locations(daw: Daw) -> (Vec<Candidate>, Vec<Candidate>) {
+    let env_path = |var: &str, default: &str| PathBuf::from(std::env::var(var).unwrap_or_else(|_| default.to_string()));
+    let program_files = env_path("ProgramFiles", r"C:\Program Files");
+    let program_data = env_path("ProgramData", r"C:\ProgramData");
+    let appdata = PathBuf::from(std::env::var("APPDATA").unwrap_or_default());
+
+    match daw {
+        Daw::Ableton => (
+            vec![candidate(program_data.join("Ableton"), "Live")],
+            vec![candidate(&appdata, "Ableton")],
+        ),
+        // Logic is macOS-only
+        Daw::Logic => (Vec::new(), Vec::new()),
+        Daw::Reaper => (
+            vec![candidate(&program_files, "REAPER")],
+            vec![candidate(appdata.join("REAPER"), "reaper.ini")],
+        ),
+        Daw::ProTools => (
+            vec![candidate(program_files.join("Avid"), "Pro Tools")],
+            vec![candidate(appdata.join("Avid"), "Pro Tools")],
+        ),
+        Daw::Cubase => (
+            vec![candidate(program_files.join("Steinberg"), "Cubase")],
+            vec![candidate(appdata.join("Steinberg"), "Cubase")],
+        ),
+        Daw::FlStudio => (
+            vec![candidate(program_files.join("Image-Line"), "FL Studio")],
+            vec![candidate(&appdata, "Image-Line")],
+        ),
+    }
+}
+
+/// Of the supported DAWs, only REAPER runs on Linux.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn daw_locations(daw: Daw) -> (Vec<Candidate>, Vec<Candidate>) {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+
+    match daw {
+        Daw::Reaper => (
+            vec![candidate(home.join("opt"), "REAPER")],
+            vec![candidate(home.join(".config/REAPER"), "reaper.ini")],
+        ),
+        _ => (Vec::new(), Vec::new()),
+    }
+}
@@ -0,0 +1,231 @@
+//! Shared library dependency checks (macOS).
+//!
+//! Reads the libraries a plugin's Mach-O executable links against (its
+//! `LC_LOAD_DYLIB` load commands) and checks that each one is present. Plugins
+//! often depend on a vendor framework installed separately (e.g., in
+//! `/Library/Frameworks`); removing that framework breaks every plugin using it,
+//! and hosts only report a generic load failure.
+//!
+//! `@rpath`, `@loader_path`, and `@executable_path` are resolved the way dyld
+//! does for a plugin. Libraries in `/usr/lib` and `/System/Library` live in the
+//! dyld shared cache rather than on disk on current macOS, so they are assumed
+//! present. Binaries that aren't Mach-O (Windows and Linux plugins) have no
+//! dependencies to report.
+
+use crate::arch::{bundle_executable, macho_cpu_architecture, FAT_MAGIC, MH_MAGIC, MH_MAGIC_64};
+use crate::{Architecture, InstalledPlugin};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Load commands naming a library
+const LC_LOAD_DYLIB: u32 = 0xc;
+const LC_LOAD_WEAK_DYLIB: u32 = 0x8000_0018;
+const LC_REEXPORT_DYLIB: u32 = 0x8000_001f;
+const LC_LOAD_UPWARD_DYLIB: u32 = 0x8000_0023;
+/// Load command adding a `@rpath` search path
+const LC_RPATH: u32 = 0x8000_001c;
+
+/// Prefixes of libraries provided by the OS through the dyld shared cache
+const SYSTEM_LIBRARY_PREFIXES: &[&str] = &["/usr/lib/", "/System/Library/"];
+
+/// Upper bound on the load command area read from a binary, against corrupt headers
+const MAX_LOAD_COMMANDS_SIZE: u32 = 4 * 1024 * 1024;
+
+/// A library the plugin's executable links against.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyStatus {
+    /// Install name as recorded in the binary, e.g., "@rpath/libVendorRuntime.dylib"
+    pub name: String,
+    /// Where the library was found, or where it was expected if it's missing.
+    /// None for an `@rpath` name that no search path resolves.
+    pub path: Option<PathBuf>,
+    /// True if the library exists (OS libraries always count as existing)
+    pub exists: bool,
+    /// Weakly linked: the plugin still loads without it
+    pub weak: bool,
+}
+
+impl DependencyStatus {
+    /// Returns true if the plugin can't load because this library is missing.
+    pub fn is_missing(&self) -> bool {
+        !self.exists && !self.weak
+    }
+}
+
+/// Checks the libraries linked by a plugin's executable.
+/// Fails if the executable can't be read; returns an empty list for non-Mach-O binaries.
+pub fn check_dependencies(plugin: &InstalledPlugin) -> Result<Vec<DependencyStatus>> {
+    let executable = if plugin.resolved_path.is_dir() {
+        match bundle_executable(&plugin.resolved_path) {
+            Some(executable) => executable,
+            None => return Ok(Vec::new()),
+        }
+    } else {
+        plugin.resolved_path.clone()
+    };
+
+    let load_commands = read_load_commands(&executable)?;
+    let loader_dir = executable.parent().unwrap_or(Path::new("/"));
+    let rpaths: Vec<PathBuf> = load_commands.rpaths.iter()
+        .map(|rpath| expand_loader_path(rpath, loader_dir))
+        .collect();
+
+    Ok(load_commands.dylibs.into_iter()
+        .map(|(name, weak)| {
+            let (path, exists) = resolve_dylib(&name, loader_dir, &rpaths);
+            DependencyStatus { name, path, exists, weak }
+        })
+        .collect())
+}
+
+/// Returns the libraries a plugin can't load without that are missing.
+pub fn missing_dependencies(plugin: &InstalledPlugin) -> Result<Vec<DependencyStatus>> {
+    Ok(check_dependencies(plugin)?.into_iter().filter(DependencyStatus::is_missing).collect())
+}
+
+/// Libraries (with whether each is weakly linked) and rpaths from a binary's load commands.
+#[derive(Debug, Default)]
+struct LoadCommands {
+    dylibs: Vec<(String, bool)>,
+    rpaths: Vec<String>,
+}
+
+/// Reads the load commands of a thin Mach-O binary, or of the slice of a universal
+/// binary matching this machine (the first slice if none does).
+fn read_load_commands(path: &Path) -> Result<LoadCommands> {
+    let mut file = fs::File::open(path)
+        .context(format!("Failed to open executable: {:?}", path))?;
+    let mut magic = [0u8; 4];
+    if file.read_exact(&mut magic).is_err() {
+        return Ok(LoadCommands::default());
+    }
+
+    let offset = if u32::from_be_bytes(magic) == FAT_MAGIC {
+        match fat_slice_offset(&mut file)? {
+            Some(offset) => offset,
+            None => return Ok(LoadCommands::default()),
+        }
+    } else {
+        0
+    };
+
+    // mach_header(_64): magic, cputype, cpusubtype, filetype, ncmds, sizeofcmds, flags[, reserved]
+    let mut header = [0u8; 32];
+    file.seek(SeekFrom::Start(offset))?;
+    if file.read_exact(&mut header).is_err() {
+        return Ok(LoadCommands::default());
+    }
+    let header_size = match u32_le(&header, 0) {
+        Some(MH_MAGIC) => 28,
+        Some(MH_MAGIC_64) => 32,
+        _ => return Ok(LoadCommands::default()),
+    };
+    let count = u32_le(&header, 16).unwrap_or(0);
+    let size = u32_le(&header, 20).unwrap_or(0).min(MAX_LOAD_COMMANDS_SIZE);
+
+    let mut commands = vec![0u8; size as usize];
+    file.seek(SeekFrom::Start(offset + header_size))?;
+    file.read_exact(&mut commands)
+        .context(format!("Truncated Mach-O load commands: {:?}", path))?;
+
+    Ok(parse_load_commands(&commands, count))
+}
+
+/// Finds the file offset of the universal binary slice to read. `file` is
+/// positioned just past the magic number.
+fn fat_slice_offset(file: &mut fs::File) -> Result<Option<u64>> {
+    let mut count = [0u8; 4];
+    file.read_exact(&mut count)?;
+    // Real universal binaries have a handful of slices
+    let count = u32::from_be_bytes(count).min(32) as usize;
+
+    let mut entries = vec![0u8; count * 20];
+    file.read_exact(&mut entries)?;
+
+    // fat_arch: cputype, cpusubtype, offset, size, align (big-endian)
+    let slices: Vec<(Architecture, u64)> = entries.chunks_exact(20)
+        .map(|entry| (
+            macho_cpu_architecture(u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]])),
+            u32::from_be_bytes([entry[8], entry[9], entry[10], entry[11]]) as u64,
+        ))
+        .collect();
+
+    let host = Architecture::host();
+    Ok(slices.iter()
+        .find(|(arch, _)| *arch == host)
+        .or(slices.first())
+        .map(|(_, offset)| *offset))
+}
+
+fn parse_load_commands(commands: &[u8], count: u32) -> LoadCommands {
+    let mut found = LoadCommands::default();
+    let mut position = 0;
+
+    for _ in 0..count {
+        let (Some(cmd), Some(cmd_size)) = (u32_le(commands, position), u32_le(commands, position + 4)) else {
+            break;
+        };
+        let Some(command) = commands.get(position..position + cmd_size as usize) else {
+            break;
+        };
+        if cmd_size < 8 {
+            break;
+        }
+
+        // dylib_command and rpath_command hold the offset of their string at byte 8
+        let string = || u32_le(command, 8).and_then(|offset| c_string(command, offset as usize));
+        match cmd {
+            LC_LOAD_DYLIB | LC_REEXPORT_DYLIB | LC_LOAD_UPWARD_DYLIB => found.dylibs.extend(string().map(|name| (name, false))),
+            LC_LOAD_WEAK_DYLIB => found.dylibs.extend(string().map(|name| (name, true))),
+            LC_RPATH => found.rpaths.extend(string()),
+            _ => {}
+        }
+
+        position += cmd_size as usize;
+    }
+
+    found
+}
+
+/// Resolves a library install name to a path and whether it exists.
+fn resolve_dylib(name: &str, loader_dir: &Path, rpaths: &[PathBuf]) -> (Option<PathBuf>, bool) {
+    if SYSTEM_LIBRARY_PREFIXES.iter().any(|prefix| name.starts_with(prefix)) {
+        return (Some(PathBuf::from(name)), true);
+    }
+
+    if let Some(relative) = name.strip_prefix("@rpath/") {
+        let candidates: Vec<PathBuf> = rpaths.iter().map(|rpath| rpath.join(relative)).collect();
+        return match candidates.iter().find(|candidate| candidate.exists()) {
+            Some(found) => (Some(found.clone()), true),
+            None => (candidates.into_iter().next(), false),
+        };
+    }
+
+    let path = expand_loader_path(name, loader_dir);
+    let exists = path.exists();
+    (Some(path), exists)
+}
+
+/// Expands `@loader_path` and `@executable_path`. A plugin is loaded by the host,
+/// but its own folder is what these point at for the libraries it ships with.
+fn expand_loader_path(path: &str, loader_dir: &Path) -> PathBuf {
+    ["@loader_path", "@executable_path"].iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
+        .map(|rest| loader_dir.join(rest.trim_start_matches('/')))
+        .unwrap_or_else(|| PathBuf::from(path))
+}
+
+fn u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+    let b = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Reads a NUL-terminated string starting at `offset`.
+fn c_string(bytes: &[u8], offset: usize) -> Option<String> {
+    let rest = bytes.get(offset..)?;
+    let end = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+    Some(String::from_utf8_lossy(&rest[..end]).to_string())
+}
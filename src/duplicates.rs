@@ -0,0 +1,325 @@
+//! Detection of the same plugin installed in more than one place.
+//!
+//! Plugins are grouped by normalized name and format (the same normalization used
+//! to share icons between VST2 and VST3). A group with several members means the
+//! host will see several copies and load one of them, which is rarely what the user
+//! expects.
+//!
+//! The most common case on macOS is a plugin present in both `/Library/...` and
+//! `~/Library/...`; `find_scope_conflicts()` reports those pairs. On Windows,
+//! `find_bitness_duplicates()` reports plugins installed in both `Program Files`
+//! and `Program Files (x86)`.
+//!
+//! `companion_formats()` uses the same normalization across formats, finding the
+//! VST3 of a VST2 and vice versa.
+//!
+//! `find_identical_plugins()` ignores names entirely and groups byte-identical
+//! copies, which catches renamed duplicates.
+
+use crate::registry::{normalize_plugin_name, path_size};
+use crate::{Architecture, InstallScope, InstalledPlugin, PluginFormat};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Groups plugins that are the same plugin in the same format, installed at different paths.
+/// Only groups with two or more members are returned, in scan order.
+pub fn find_duplicates(plugins: &[InstalledPlugin]) -> Vec<Vec<&InstalledPlugin>> {
+    let mut order: Vec<(String, PluginFormat)> = Vec::new();
+    let mut groups: HashMap<(String, PluginFormat), Vec<&InstalledPlugin>> = HashMap::new();
+
+    for plugin in plugins {
+        let key = (normalize_plugin_name(&plugin.plugin.name), plugin.format);
+        let group = groups.entry(key.clone()).or_default();
+        if group.is_empty() {
+            order.push(key);
+        }
+        group.push(plugin);
+    }
+
+    order.into_iter()
+        .filter_map(|key| groups.remove(&key))
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+/// Returns the installs in `all` of the same product as `plugin` in other formats,
+/// e.g., the VST3 of a VST2, in scan order. The UI uses them to point out that
+/// another format of a plugin is available.
+pub fn companion_formats<'a>(plugin: &InstalledPlugin, all: &'a [InstalledPlugin]) -> Vec<&'a InstalledPlugin> {
+    let name = normalize_plugin_name(&plugin.plugin.name);
+    all.iter()
+        .filter(|other| other.format != plugin.format && normalize_plugin_name(&other.plugin.name) == name)
+        .collect()
+}
+
+/// A plugin installed both system-wide and for the current user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScopeConflict {
+    pub name: String,
+    pub format: PluginFormat,
+    /// Copy in the machine-wide plugin folder
+    pub system_path: PathBuf,
+    /// Copy in the user's plugin folder
+    pub user_path: PathBuf,
+}
+
+/// Finds plugins present in both a system-wide and a user-specific plugin directory.
+pub fn find_scope_conflicts(plugins: &[InstalledPlugin]) -> Vec<ScopeConflict> {
+    find_scope_conflicts_with(plugins, &user_plugin_roots())
+}
+
+/// Like `find_scope_conflicts()`, treating paths under any of `user_roots` as user-scoped.
+pub fn find_scope_conflicts_with(plugins: &[InstalledPlugin], user_roots: &[PathBuf]) -> Vec<ScopeConflict> {
+    let mut conflicts = Vec::new();
+
+    for group in find_duplicates(plugins) {
+        let system = group.iter().find(|p| scope_of(&p.install_path, user_roots) == InstallScope::System);
+        let user = group.iter().find(|p| scope_of(&p.install_path, user_roots) == InstallScope::User);
+
+        if let (Some(system), Some(user)) = (system, user) {
+            conflicts.push(ScopeConflict {
+                name: system.plugin.name.clone(),
+                format: system.format,
+                system_path: system.install_path.clone(),
+                user_path: user.install_path.clone(),
+            });
+        }
+    }
+
+    conflicts
+}
+
+/// A plugin installed as both a 32-bit and a 64-bit copy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BitnessDuplicate {
+    pub name: String,
+    pub format: PluginFormat,
+    /// The 64-bit copy (by binary architecture, or `Program Files` location if unknown)
+    pub path_64: PathBuf,
+    pub architecture_64: Architecture,
+    /// The 32-bit copy (by binary architecture, or `Program Files (x86)` location if unknown)
+    pub path_32: PathBuf,
+    pub architecture_32: Architecture,
+    /// The copy the given host can't load, which the UI can suggest removing
+    pub incompatible_path: Option<PathBuf>,
+}
+
+/// Finds plugins present as both a 32-bit and a 64-bit copy, e.g., in both
+/// `Program Files` and `Program Files (x86)`.
+/// `host` is the architecture of the host that will load the plugins.
+pub fn find_bitness_duplicates(plugins: &[InstalledPlugin], host: Architecture) -> Vec<BitnessDuplicate> {
+    let mut duplicates = Vec::new();
+
+    for group in find_duplicates(plugins) {
+        let copy_32 = group.iter().find(|p| is_32bit(p));
+        let copy_64 = group.iter().find(|p| !is_32bit(p));
+
+        if let (Some(copy_32), Some(copy_64)) = (copy_32, copy_64) {
+            // A host loads only plugins matching its own bitness
+            let incompatible_path = match host {
+                Architecture::Unknown => None,
+                Architecture::X86 => Some(copy_64.install_path.clone()),
+                _ => Some(copy_32.install_path.clone()),
+            };
+
+            duplicates.push(BitnessDuplicate {
+                name: copy_64.plugin.name.clone(),
+                format: copy_64.format,
+                path_64: copy_64.install_path.clone(),
+                architecture_64: copy_64.architecture,
+                path_32: copy_32.install_path.clone(),
+                architecture_32: copy_32.architecture,
+                incompatible_path,
+            });
+        }
+    }
+
+    duplicates
+}
+
+/// Returns true if a plugin is a 32-bit build: by binary architecture when known,
+/// otherwise by being installed under `Program Files (x86)`.
+fn is_32bit(plugin: &InstalledPlugin) -> bool {
+    match plugin.architecture {
+        Architecture::X86 => true,
+        Architecture::X86_64 | Architecture::Arm64 | Architecture::Universal(_) => false,
+        Architecture::Unknown => is_32bit_location(&plugin.install_path),
+    }
+}
+
+/// Returns true if the path is inside a `Program Files (x86)` folder.
+pub fn is_32bit_location(path: &Path) -> bool {
+    path.components()
+        .any(|c| c.as_os_str().to_string_lossy().eq_ignore_ascii_case("Program Files (x86)"))
+}
+
+/// Returns the scope of a path given the roots that count as user-specific.
+pub fn scope_of(path: &Path, user_roots: &[PathBuf]) -> InstallScope {
+    if user_roots.iter().any(|root| path.starts_with(root)) {
+        InstallScope::User
+    } else {
+        InstallScope::System
+    }
+}
+
+/// Directories whose contents belong to the current user rather than the machine.
+pub(crate) fn user_plugin_roots() -> Vec<PathBuf> {
+    let vars: &[&str] = if cfg!(target_os = "windows") {
+        &["LOCALAPPDATA", "APPDATA"]
+    } else {
+        &["HOME"]
+    };
+
+    vars.iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Groups plugins whose binaries or bundles are byte-identical, whatever their names.
+/// Only groups with two or more members are returned, in scan order.
+/// Only plugins of equal total size are hashed, so unique plugins aren't read.
+/// Members of a shell plugin share its binary, so only the first entry for each
+/// install path is considered; they aren't copies of each other.
+pub fn find_identical_plugins(plugins: &[InstalledPlugin]) -> Vec<Vec<&InstalledPlugin>> {
+    let mut seen_paths = HashSet::new();
+    let plugins: Vec<&InstalledPlugin> = plugins.iter()
+        .filter(|plugin| seen_paths.insert(&plugin.install_path))
+        .collect();
+
+    let sizes: Vec<u64> = plugins.iter().map(|p| path_size(&p.install_path)).collect();
+    let mut size_counts: HashMap<u64, usize> = HashMap::new();
+    for size in &sizes {
+        *size_counts.entry(*size).or_default() += 1;
+    }
+
+    let mut order: Vec<String> = Vec::new();
+    let mut groups: HashMap<String, Vec<&InstalledPlugin>> = HashMap::new();
+
+    for (&plugin, size) in plugins.iter().zip(&sizes) {
+        if size_counts[size] < 2 {
+            continue;
+        }
+
+        let hash = match plugin_content_hash(plugin) {
+            Ok(hash) => hash,
+            Err(e) => {
+                eprintln!("Warning: Failed to hash {:?}: {}", plugin.install_path, e);
+                continue;
+            }
+        };
+
+        let group = groups.entry(hash.clone()).or_default();
+        if group.is_empty() {
+            order.push(hash);
+        }
+        group.push(plugin);
+    }
+
+    order.into_iter()
+        .filter_map(|hash| groups.remove(&hash))
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+/// SHA-256 (hex) of a plugin's binary or bundle contents.
+/// For bundles, every file's path relative to the bundle and its bytes are hashed
+/// in sorted order, so the result doesn't depend on the bundle's own name or location.
+pub fn plugin_content_hash(plugin: &InstalledPlugin) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hash_path(&plugin.install_path, Path::new(""), &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn hash_path(path: &Path, relative: &Path, hasher: &mut Sha256) -> Result<()> {
+    // Links inside a bundle are hashed by their target path, not followed: a link
+    // to a parent folder would never finish. A symlinked plugin itself is followed.
+    let is_link = fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink());
+    if is_link && !relative.as_os_str().is_empty() {
+        let target = fs::read_link(path)
+            .context(format!("Failed to read link: {:?}", path))?;
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([1]);
+        hasher.update(target.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        return Ok(());
+    }
+
+    if path.is_dir() {
+        let mut entries: Vec<_> = fs::read_dir(path)
+            .context(format!("Failed to read directory: {:?}", path))?
+            .collect::<std::io::Result<_>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            hash_path(&entry.path(), &relative.join(entry.file_name()), hasher)?;
+        }
+        return Ok(());
+    }
+
+    // Separate the name from the contents so moving bytes between files changes the hash
+    hasher.update(relative.to_string_lossy().as_bytes());
+    hasher.update([0]);
+
+    let mut file = fs::File::open(path)
+        .context(format!("Failed to open file: {:?}", path))?;
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    hasher.update([0]);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::installed_plugin;
+
+    #[test]
+    fn shell_members_are_not_identical_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let shell = dir.path().join("WaveShell.vst3");
+        let copy = dir.path().join("Copy.vst3");
+        fs::write(&shell, "shell").unwrap();
+        fs::write(&copy, "shell").unwrap();
+        let members: Vec<InstalledPlugin> = ["Member A", "Member B"].iter()
+            .map(|name| installed_plugin(name, &shell, PluginFormat::VST3))
+            .collect();
+
+        assert!(find_identical_plugins(&members).is_empty());
+
+        let mut plugins = members;
+        plugins.push(installed_plugin("Copy", &copy, PluginFormat::VST3));
+        let groups = find_identical_plugins(&plugins);
+        assert_eq!(groups.len(), 1);
+        let paths: Vec<&Path> = groups[0].iter().map(|p| p.install_path.as_path()).collect();
+        assert_eq!(paths, [shell.as_path(), copy.as_path()]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hashes_links_without_following_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Synth.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "").unwrap();
+        std::os::unix::fs::symlink("..", bundle.join("parent")).unwrap();
+        let plugin = installed_plugin("Synth", &bundle, PluginFormat::LV2);
+
+        let with_link = plugin_content_hash(&plugin).unwrap();
+        fs::remove_file(bundle.join("parent")).unwrap();
+        std::os::unix::fs::symlink(".", bundle.join("parent")).unwrap();
+        assert_ne!(plugin_content_hash(&plugin).unwrap(), with_link);
+    }
+}
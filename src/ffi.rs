@@ -9,6 +9,12 @@
 //! - The calling code MUST call the corresponding `_free` functions to prevent leaks
 //! - Strings are null-terminated UTF-8
 //!
+//! # Error Reporting
+//!
+//! Functions signal failure through their return value (null, non-zero, etc.).
+//! The message for the most recent failure on the calling thread is available
+//! from `plugindepot_last_error()`.
+//!
 //! # Usage from Swift (macOS)
 //!
 //! ```swift
@@ -32,15 +38,20 @@
 //! private static extern void plugindepot_free_plugin_list(IntPtr list);
 //! ```
 
-use crate::registry::{scan_installed, detect_orphaned_files, enumerate_plugin_files};
-use crate::operations::{backup_plugin, uninstall_plugin, export_plugin};
-use crate::icons::{cache_icon_data, get_cached_icon_path, clear_icon_cache};
-use crate::{InstalledPlugin, PluginFormat};
+use crate::registry::{scan, refresh_plugin, add_global_scan_dir, clear_global_scan_dirs, detect_orphaned_files, enumerate_plugin_files, plugin_disk_usage, related_paths_report, vendor_summary_of, CancellationToken, ScanConfig};
+use crate::operations::{backup_plugin, backup_plugin_with_progress, uninstall_plugin, paths_needing_elevation, export_plugin, set_plugin_enabled};
+use crate::icons::{cache_icon_data, get_cached_icon_path, clear_icon_cache, icons_needing_download, set_max_icon_size};
+use crate::quarantine::{is_quarantined, remove_quarantine};
+use crate::state::set_display_name;
+use crate::operations::OpProgress;
+use crate::{Architecture, Architectures, InstallScope, InstalledPlugin, PluginFormat};
+use std::cell::RefCell;
 use std::ffi::{CString, CStr};
-use std::os::raw::{c_char, c_int};
+use std::os::raw::{c_char, c_int, c_void};
 use std::path::PathBuf;
 use std::ptr;
 use std::slice;
+use std::sync::OnceLock;
 
 // ============================================================================
 // C-Compatible Types
@@ -60,12 +71,20 @@ pub struct CPlugin {
     pub version: *mut c_char,
     pub description: *mut c_char,
     pub install_path: *mut c_char,
-    pub format: c_int, // 0=VST2, 1=VST3, 2=AU, 3=AAX
+    pub format: c_int, // 0=VST2, 1=VST3, 2=AU, 3=AAX, 4=LV2
     pub preset_count: c_int,
     pub library_count: c_int,
     pub preference_count: c_int,
     /// URL to the plugin's icon (null if not available)
     pub icon_url: *mut c_char,
+    /// 1 if enabled, 0 if disabled
+    pub enabled: c_int,
+    /// 0=unknown, 1=x86, 2=x86_64, 3=arm64; several architectures: 4=x86_64+arm64
+    /// (universal), 5=x86+x86_64, 6=x86+x86_64+arm64, 7=x86+arm64
+    pub architecture: c_int,
+    pub scope: c_int, // 0=system-wide, 1=current user
+    /// Vendor from the plugin's metadata (null if unknown)
+    pub vendor_name: *mut c_char,
 }
 
 /// C-compatible path list
@@ -85,26 +104,94 @@ pub enum CResultCode {
 // Plugin Scanning
 // ============================================================================
 
+/// Cancellation flag shared by FFI scans; set by plugindepot_cancel_scan().
+fn scan_cancel_token() -> &'static CancellationToken {
+    static TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+    TOKEN.get_or_init(CancellationToken::new)
+}
+
 /// Scan the system for installed plugins.
 /// Returns an opaque handle to the plugin list.
+/// If plugindepot_cancel_scan() is called from another thread, returns the plugins found so far.
 /// Caller MUST call plugindepot_free_plugin_list() when done.
 #[no_mangle]
 pub extern "C" fn plugindepot_scan_plugins() -> *mut CPluginList {
-    match scan_installed() {
+    let cancel = scan_cancel_token();
+    cancel.reset();
+    
+    match scan(&ScanConfig::default(), Some(cancel)) {
         Ok(plugins) => {
             let list = Box::new(CPluginList { plugins });
             Box::into_raw(list)
         }
         Err(e) => {
-            eprintln!("Error scanning plugins: {}", e);
+            report_error("Error scanning plugins", &e);
             ptr::null_mut()
         }
     }
 }
 
+/// Cancel a scan running on another thread.
+/// The scan stops at the next directory or entry and returns a truncated list.
+#[no_mangle]
+pub extern "C" fn plugindepot_cancel_scan() {
+    scan_cancel_token().cancel();
+}
+
+/// Add a folder that plugindepot_scan_plugins() will also search, for plugins of the
+/// given format (0=VST2, 1=VST3, 2=AU, 3=AAX, 4=LV2). Applies to all later scans in this process.
+/// Returns 0 on success, 1 on invalid input.
+///
+/// # Safety
+///
+/// `path` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_add_scan_dir(path: *const c_char, format: c_int) -> c_int {
+    if path.is_null() {
+        set_last_error("Scan directory path is null");
+        return 1;
+    }
+    
+    let format = match int_to_format(format) {
+        Some(format) => format,
+        None => {
+            set_last_error(&format!("Invalid plugin format code: {}", format));
+            return 1;
+        }
+    };
+    
+    unsafe {
+        let path = match CStr::from_ptr(path).to_str() {
+            Ok(s) if !s.is_empty() => s,
+            Ok(_) => {
+                set_last_error("Scan directory path is empty");
+                return 1;
+            }
+            Err(_) => {
+                set_last_error("Scan directory path is not valid UTF-8");
+                return 1;
+            }
+        };
+        
+        add_global_scan_dir(PathBuf::from(path), format);
+    }
+    
+    0
+}
+
+/// Remove every folder added with plugindepot_add_scan_dir().
+#[no_mangle]
+pub extern "C" fn plugindepot_clear_scan_dirs() {
+    clear_global_scan_dirs();
+}
+
 /// Get the number of plugins in a list.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
 #[no_mangle]
-pub extern "C" fn plugindepot_plugin_list_count(list: *const CPluginList) -> c_int {
+pub unsafe extern "C" fn plugindepot_plugin_list_count(list: *const CPluginList) -> c_int {
     if list.is_null() {
         return 0;
     }
@@ -115,8 +202,12 @@ pub extern "C" fn plugindepot_plugin_list_count(list: *const CPluginList) -> c_i
 
 /// Get plugin information at a specific index.
 /// Returns a CPlugin struct. Caller MUST call plugindepot_free_plugin() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
 #[no_mangle]
-pub extern "C" fn plugindepot_plugin_list_get(list: *const CPluginList, index: c_int) -> *mut CPlugin {
+pub unsafe extern "C" fn plugindepot_plugin_list_get(list: *const CPluginList, index: c_int) -> *mut CPlugin {
     if list.is_null() {
         return ptr::null_mut();
     }
@@ -129,33 +220,155 @@ pub extern "C" fn plugindepot_plugin_list_get(list: *const CPluginList, index: c
             return ptr::null_mut();
         }
         
-        let plugin = &plugins[idx];
+        Box::into_raw(Box::new(to_c_plugin(&plugins[idx])))
+    }
+}
+
+/// Get the tags of the plugin at `index` (inferred from metadata plus user tags),
+/// as a string list read with plugindepot_path_list_count()/plugindepot_path_list_get().
+/// Returns null on invalid input.
+/// Caller MUST call plugindepot_free_path_list() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_plugin_tags(list: *const CPluginList, index: c_int) -> *mut CPathList {
+    if list.is_null() {
+        set_last_error("Plugin list is null");
+        return ptr::null_mut();
+    }
+    
+    unsafe {
+        let plugins = &(*list).plugins;
+        let idx = index as usize;
         
-        // Convert to C-compatible struct
-        let c_plugin = Box::new(CPlugin {
-            id: string_to_c_char(&plugin.plugin.id),
-            name: string_to_c_char(&plugin.plugin.name),
-            version: string_to_c_char(&plugin.plugin.version),
-            description: plugin.plugin.description.as_ref()
-                .map(|s| string_to_c_char(s))
-                .unwrap_or(ptr::null_mut()),
-            install_path: string_to_c_char(&plugin.install_path.to_string_lossy()),
-            format: format_to_int(&plugin.format),
-            preset_count: plugin.related_paths.preset_locations.len() as c_int,
-            library_count: plugin.related_paths.library_locations.len() as c_int,
-            preference_count: plugin.related_paths.preference_files.len() as c_int,
-            icon_url: plugin.plugin.icon_url.as_ref()
-                .map(|s| string_to_c_char(s))
-                .unwrap_or(ptr::null_mut()),
-        });
+        if idx >= plugins.len() {
+            set_last_error(&format!("Plugin index out of range: {}", index));
+            return ptr::null_mut();
+        }
         
-        Box::into_raw(c_plugin)
+        let paths = plugins[idx].plugin.tags.iter().map(PathBuf::from).collect();
+        Box::into_raw(Box::new(CPathList { paths }))
+    }
+}
+
+/// Re-read the plugin at `index` from disk (metadata and related paths), e.g., after
+/// the user installed an update, without rescanning everything. The list entry is
+/// replaced, so later calls with the same index see the new data.
+/// Returns null if the plugin is no longer installed or on invalid input.
+/// Caller MUST call plugindepot_free_plugin() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_rescan_plugin(list: *mut CPluginList, index: c_int) -> *mut CPlugin {
+    if list.is_null() {
+        set_last_error("Plugin list is null");
+        return ptr::null_mut();
+    }
+    
+    unsafe {
+        let plugins = &mut (*list).plugins;
+        let idx = index as usize;
+        
+        if idx >= plugins.len() {
+            set_last_error(&format!("Plugin index out of range: {}", index));
+            return ptr::null_mut();
+        }
+        
+        match refresh_plugin(&plugins[idx]) {
+            Some(plugin) => {
+                plugins[idx] = plugin;
+                Box::into_raw(Box::new(to_c_plugin(&plugins[idx])))
+            }
+            None => {
+                set_last_error(&format!("Plugin is no longer installed: {:?}", plugins[idx].install_path));
+                ptr::null_mut()
+            }
+        }
+    }
+}
+
+/// Fill a caller-provided array with up to `count` plugins starting at `start`.
+/// Returns the number of entries written (fewer than `count` at the end of the list,
+/// 0 if `start` is out of range). `out_array` must have room for `count` CPlugin structs.
+/// The array belongs to the caller, but the strings inside each entry are allocated by
+/// this library: call plugindepot_free_plugin_range() with the returned count when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+/// `out_array` must be null or have room for `count` `CPlugin` structs.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_plugin_list_get_range(
+    list: *const CPluginList,
+    start: c_int,
+    count: c_int,
+    out_array: *mut CPlugin,
+) -> c_int {
+    if list.is_null() || out_array.is_null() {
+        set_last_error("Invalid plugin list or output array");
+        return 0;
+    }
+    if start < 0 || count <= 0 {
+        return 0;
+    }
+    
+    unsafe {
+        let plugins = &(*list).plugins;
+        let start = start as usize;
+        if start >= plugins.len() {
+            return 0;
+        }
+        let end = plugins.len().min(start + count as usize);
+        
+        for (offset, plugin) in plugins[start..end].iter().enumerate() {
+            // The buffer is uninitialized from our point of view, so write without dropping
+            ptr::write(out_array.add(offset), to_c_plugin(plugin));
+        }
+        
+        (end - start) as c_int
+    }
+}
+
+/// Convert a plugin to its C representation. Strings are owned by the result.
+fn to_c_plugin(plugin: &InstalledPlugin) -> CPlugin {
+    CPlugin {
+        id: string_to_c_char(&plugin.plugin.id),
+        name: string_to_c_char(&plugin.plugin.name),
+        version: string_to_c_char(&plugin.plugin.version),
+        description: plugin.plugin.description.as_ref()
+            .map(|s| string_to_c_char(s))
+            .unwrap_or(ptr::null_mut()),
+        install_path: string_to_c_char(&plugin.install_path.to_string_lossy()),
+        format: format_to_int(&plugin.format),
+        preset_count: plugin.related_paths.preset_locations.len() as c_int,
+        library_count: plugin.related_paths.library_locations.len() as c_int,
+        preference_count: plugin.related_paths.preference_files.len() as c_int,
+        icon_url: plugin.plugin.icon_url.as_ref()
+            .map(|s| string_to_c_char(s))
+            .unwrap_or(ptr::null_mut()),
+        enabled: plugin.enabled as c_int,
+        architecture: architecture_to_int(plugin.architecture),
+        scope: match plugin.scope {
+            InstallScope::System => 0,
+            InstallScope::User => 1,
+        },
+        vendor_name: plugin.plugin.author.as_ref()
+            .map(|s| string_to_c_char(s))
+            .unwrap_or(ptr::null_mut()),
     }
 }
 
 /// Free a plugin list returned by plugindepot_scan_plugins().
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
 #[no_mangle]
-pub extern "C" fn plugindepot_free_plugin_list(list: *mut CPluginList) {
+pub unsafe extern "C" fn plugindepot_free_plugin_list(list: *mut CPluginList) {
     if !list.is_null() {
         unsafe {
             let _ = Box::from_raw(list);
@@ -164,21 +377,58 @@ pub extern "C" fn plugindepot_free_plugin_list(list: *mut CPluginList) {
 }
 
 /// Free a CPlugin struct returned by plugindepot_plugin_list_get().
+///
+/// # Safety
+///
+/// `plugin` must be null or a plugin returned by this library that hasn't been freed.
 #[no_mangle]
-pub extern "C" fn plugindepot_free_plugin(plugin: *mut CPlugin) {
+pub unsafe extern "C" fn plugindepot_free_plugin(plugin: *mut CPlugin) {
     if !plugin.is_null() {
         unsafe {
             let p = Box::from_raw(plugin);
-            free_c_char(p.id);
-            free_c_char(p.name);
-            free_c_char(p.version);
-            free_c_char(p.description);
-            free_c_char(p.install_path);
-            free_c_char(p.icon_url);
+            free_c_plugin_strings(&p);
         }
     }
 }
 
+/// Free the strings inside entries filled by plugindepot_plugin_list_get_range().
+/// `count` is the value that call returned. The array itself is not freed.
+///
+/// # Safety
+///
+/// `array` must be null or an array filled by `plugindepot_plugin_list_get_range()`, and `count` at most the number of entries it wrote.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_free_plugin_range(array: *mut CPlugin, count: c_int) {
+    if array.is_null() || count <= 0 {
+        return;
+    }
+    unsafe {
+        for i in 0..count as usize {
+            let p = &mut *array.add(i);
+            free_c_plugin_strings(p);
+            // Guard against a double free if the caller frees the range twice
+            p.id = ptr::null_mut();
+            p.name = ptr::null_mut();
+            p.version = ptr::null_mut();
+            p.description = ptr::null_mut();
+            p.install_path = ptr::null_mut();
+            p.icon_url = ptr::null_mut();
+            p.vendor_name = ptr::null_mut();
+        }
+    }
+}
+
+/// Free every string owned by a CPlugin.
+fn free_c_plugin_strings(p: &CPlugin) {
+    free_c_char(p.id);
+    free_c_char(p.name);
+    free_c_char(p.version);
+    free_c_char(p.description);
+    free_c_char(p.install_path);
+    free_c_char(p.icon_url);
+    free_c_char(p.vendor_name);
+}
+
 // ============================================================================
 // Orphaned File Detection
 // ============================================================================
@@ -194,15 +444,19 @@ pub extern "C" fn plugindepot_detect_orphaned() -> *mut CPathList {
             Box::into_raw(list)
         }
         Err(e) => {
-            eprintln!("Error detecting orphaned files: {}", e);
+            report_error("Error detecting orphaned files", &e);
             ptr::null_mut()
         }
     }
 }
 
 /// Get the number of paths in a path list.
+///
+/// # Safety
+///
+/// `list` must be null or a path list returned by this library that hasn't been freed.
 #[no_mangle]
-pub extern "C" fn plugindepot_path_list_count(list: *const CPathList) -> c_int {
+pub unsafe extern "C" fn plugindepot_path_list_count(list: *const CPathList) -> c_int {
     if list.is_null() {
         return 0;
     }
@@ -213,8 +467,12 @@ pub extern "C" fn plugindepot_path_list_count(list: *const CPathList) -> c_int {
 
 /// Get a path at a specific index.
 /// Returns a null-terminated string. Caller MUST call plugindepot_free_string() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a path list returned by this library that hasn't been freed.
 #[no_mangle]
-pub extern "C" fn plugindepot_path_list_get(list: *const CPathList, index: c_int) -> *mut c_char {
+pub unsafe extern "C" fn plugindepot_path_list_get(list: *const CPathList, index: c_int) -> *mut c_char {
     if list.is_null() {
         return ptr::null_mut();
     }
@@ -232,8 +490,12 @@ pub extern "C" fn plugindepot_path_list_get(list: *const CPathList, index: c_int
 }
 
 /// Free a path list returned by plugindepot_detect_orphaned().
+///
+/// # Safety
+///
+/// `list` must be null or a path list returned by this library that hasn't been freed.
 #[no_mangle]
-pub extern "C" fn plugindepot_free_path_list(list: *mut CPathList) {
+pub unsafe extern "C" fn plugindepot_free_path_list(list: *mut CPathList) {
     if !list.is_null() {
         unsafe {
             let _ = Box::from_raw(list);
@@ -248,8 +510,13 @@ pub extern "C" fn plugindepot_free_path_list(list: *mut CPathList) {
 /// Backup a plugin to the specified directory.
 /// Returns the backup path on success, or null on error.
 /// Caller MUST call plugindepot_free_string() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+/// `backup_dir` must be null or a NUL-terminated string.
 #[no_mangle]
-pub extern "C" fn plugindepot_backup_plugin(
+pub unsafe extern "C" fn plugindepot_backup_plugin(
     list: *const CPluginList,
     index: c_int,
     backup_dir: *const c_char,
@@ -275,7 +542,74 @@ pub extern "C" fn plugindepot_backup_plugin(
         match backup_plugin(plugin, &PathBuf::from(backup_dir_str)) {
             Ok(path) => string_to_c_char(&path.to_string_lossy()),
             Err(e) => {
-                eprintln!("Error backing up plugin: {}", e);
+                report_error("Error backing up plugin", &e);
+                ptr::null_mut()
+            }
+        }
+    }
+}
+
+/// Progress callback for long operations: files and bytes processed so far and in
+/// total, plus the `user` pointer passed to the operation.
+pub type CProgressCallback = extern "C" fn(files_done: u64, files_total: u64, bytes_done: u64, bytes_total: u64, user: *mut c_void);
+
+/// Like `plugindepot_backup_plugin()`, calling `callback` after each file is copied.
+/// The callback runs on the calling thread, in the middle of the backup, so it must
+/// return quickly (e.g., post the values to the UI thread rather than redraw).
+/// A null callback is allowed and reports nothing.
+/// Returns the backup path on success, or null on error.
+/// Caller MUST call plugindepot_free_string() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+/// `backup_dir` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_backup_plugin_with_progress(
+    list: *const CPluginList,
+    index: c_int,
+    backup_dir: *const c_char,
+    callback: Option<CProgressCallback>,
+    user: *mut c_void,
+) -> *mut c_char {
+    if list.is_null() || backup_dir.is_null() {
+        set_last_error("Plugin list or backup directory is null");
+        return ptr::null_mut();
+    }
+    
+    unsafe {
+        let plugins = &(*list).plugins;
+        let idx = index as usize;
+        
+        if idx >= plugins.len() {
+            set_last_error(&format!("Plugin index out of range: {}", index));
+            return ptr::null_mut();
+        }
+        
+        let backup_dir_str = match CStr::from_ptr(backup_dir).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("Backup directory is not valid UTF-8");
+                return ptr::null_mut();
+            }
+        };
+        
+        let progress = |progress: &OpProgress| {
+            if let Some(callback) = callback {
+                callback(
+                    progress.files_done as u64,
+                    progress.files_total as u64,
+                    progress.bytes_done,
+                    progress.bytes_total,
+                    user,
+                );
+            }
+        };
+        
+        match backup_plugin_with_progress(&plugins[idx], &PathBuf::from(backup_dir_str), progress) {
+            Ok(path) => string_to_c_char(&path.to_string_lossy()),
+            Err(e) => {
+                report_error("Error backing up plugin", &e);
                 ptr::null_mut()
             }
         }
@@ -285,8 +619,12 @@ pub extern "C" fn plugindepot_backup_plugin(
 /// Uninstall a plugin. If dry_run is non-zero, only returns what would be deleted.
 /// Returns a path list of deleted files.
 /// Caller MUST call plugindepot_free_path_list() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
 #[no_mangle]
-pub extern "C" fn plugindepot_uninstall_plugin(
+pub unsafe extern "C" fn plugindepot_uninstall_plugin(
     list: *const CPluginList,
     index: c_int,
     dry_run: c_int,
@@ -310,18 +648,52 @@ pub extern "C" fn plugindepot_uninstall_plugin(
                 Box::into_raw(list)
             }
             Err(e) => {
-                eprintln!("Error uninstalling plugin: {}", e);
+                report_error("Error uninstalling plugin", &e);
                 ptr::null_mut()
             }
         }
     }
 }
 
+/// List the plugin's paths that can't be deleted without administrator privileges,
+/// so the UI can ask for elevation before uninstalling. An empty list means the
+/// current user can uninstall the plugin.
+/// Caller MUST call plugindepot_free_path_list() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_paths_needing_elevation(list: *const CPluginList, index: c_int) -> *mut CPathList {
+    if list.is_null() {
+        set_last_error("Plugin list is null");
+        return ptr::null_mut();
+    }
+    
+    unsafe {
+        let plugins = &(*list).plugins;
+        let idx = index as usize;
+        
+        if idx >= plugins.len() {
+            set_last_error(&format!("Plugin index out of range: {}", index));
+            return ptr::null_mut();
+        }
+        
+        let paths = paths_needing_elevation(&plugins[idx]);
+        Box::into_raw(Box::new(CPathList { paths }))
+    }
+}
+
 /// Export a plugin for migration to another machine.
 /// Returns the export path on success, or null on error.
 /// Caller MUST call plugindepot_free_string() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+/// `export_dir` must be null or a NUL-terminated string.
 #[no_mangle]
-pub extern "C" fn plugindepot_export_plugin(
+pub unsafe extern "C" fn plugindepot_export_plugin(
     list: *const CPluginList,
     index: c_int,
     export_dir: *const c_char,
@@ -347,18 +719,187 @@ pub extern "C" fn plugindepot_export_plugin(
         match export_plugin(plugin, &PathBuf::from(export_dir_str)) {
             Ok(path) => string_to_c_char(&path.to_string_lossy()),
             Err(e) => {
-                eprintln!("Error exporting plugin: {}", e);
+                report_error("Error exporting plugin", &e);
                 ptr::null_mut()
             }
         }
     }
 }
 
+/// Enable or disable a plugin without uninstalling it.
+/// On success the plugin's recorded path in the list is updated to its new location.
+/// Returns 0 on success, 1 on error.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_set_plugin_enabled(
+    list: *mut CPluginList,
+    index: c_int,
+    enabled: c_int,
+) -> c_int {
+    if list.is_null() {
+        return 1;
+    }
+    
+    unsafe {
+        let plugins = &mut (*list).plugins;
+        let idx = index as usize;
+        
+        if idx >= plugins.len() {
+            return 1;
+        }
+        
+        match set_plugin_enabled(&mut plugins[idx], enabled != 0) {
+            Ok(_) => 0,
+            Err(e) => {
+                report_error("Error changing plugin enabled state", &e);
+                1
+            }
+        }
+    }
+}
+
+/// Compute the total disk space used by a plugin and its related files, in bytes.
+/// Walks every related folder, so this may be slow for sample-heavy plugins;
+/// call it off the UI thread. Returns 0 on error (see plugindepot_last_error()).
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_plugin_disk_usage(list: *const CPluginList, index: c_int) -> u64 {
+    if list.is_null() {
+        set_last_error("Plugin list is null");
+        return 0;
+    }
+    
+    unsafe {
+        let plugins = &(*list).plugins;
+        let idx = index as usize;
+        
+        if idx >= plugins.len() {
+            set_last_error(&format!("Plugin index out of range: {}", index));
+            return 0;
+        }
+        
+        match plugin_disk_usage(&plugins[idx]) {
+            Ok(usage) => usage.total,
+            Err(e) => {
+                report_error("Error computing disk usage", &e);
+                0
+            }
+        }
+    }
+}
+
+/// Compute a plugin's disk usage broken down by binary, presets, libraries,
+/// support files, and preferences, as a JSON object of byte counts.
+/// May be slow; call off the UI thread.
+/// Returns null on error. Caller MUST call plugindepot_free_string() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_plugin_disk_usage_json(list: *const CPluginList, index: c_int) -> *mut c_char {
+    if list.is_null() {
+        set_last_error("Plugin list is null");
+        return ptr::null_mut();
+    }
+    
+    unsafe {
+        let plugins = &(*list).plugins;
+        let idx = index as usize;
+        
+        if idx >= plugins.len() {
+            set_last_error(&format!("Plugin index out of range: {}", index));
+            return ptr::null_mut();
+        }
+        
+        match plugin_disk_usage(&plugins[idx]) {
+            Ok(usage) => string_to_c_char(&serde_json::to_string(&usage).unwrap_or_default()),
+            Err(e) => {
+                report_error("Error computing disk usage", &e);
+                ptr::null_mut()
+            }
+        }
+    }
+}
+
+/// Describe every related path discovered for a plugin, with its category and
+/// match confidence, as JSON. Meant for diagnostics (e.g., attaching to a support
+/// request), not for display.
+/// Returns null on error. Caller MUST call plugindepot_free_string() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_related_paths_json(list: *const CPluginList, index: c_int) -> *mut c_char {
+    if list.is_null() {
+        set_last_error("Plugin list is null");
+        return ptr::null_mut();
+    }
+    
+    unsafe {
+        let plugins = &(*list).plugins;
+        let idx = index as usize;
+        
+        if idx >= plugins.len() {
+            set_last_error(&format!("Plugin index out of range: {}", index));
+            return ptr::null_mut();
+        }
+        
+        match serde_json::to_string_pretty(&related_paths_report(&plugins[idx])) {
+            Ok(json) => string_to_c_char(&json),
+            Err(e) => {
+                report_error("Error writing related paths report", &e.into());
+                ptr::null_mut()
+            }
+        }
+    }
+}
+
+/// Count the plugins in a list per vendor, as a JSON array of
+/// `{"vendor": .., "count": ..}` objects, most plugins first. Plugins without a
+/// vendor are counted under "Unknown".
+/// Returns null on error. Caller MUST call plugindepot_free_string() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_vendor_summary_json(list: *const CPluginList) -> *mut c_char {
+    if list.is_null() {
+        set_last_error("Plugin list is null");
+        return ptr::null_mut();
+    }
+    
+    let plugins = unsafe { &(*list).plugins };
+    let summary: Vec<serde_json::Value> = vendor_summary_of(plugins).into_iter()
+        .map(|(vendor, count)| serde_json::json!({ "vendor": vendor, "count": count }))
+        .collect();
+    
+    match serde_json::to_string_pretty(&summary) {
+        Ok(json) => string_to_c_char(&json),
+        Err(e) => {
+            report_error("Error writing vendor summary", &e.into());
+            ptr::null_mut()
+        }
+    }
+}
+
 /// Enumerate all files associated with a plugin.
 /// Returns a path list.
 /// Caller MUST call plugindepot_free_path_list() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
 #[no_mangle]
-pub extern "C" fn plugindepot_enumerate_files(
+pub unsafe extern "C" fn plugindepot_enumerate_files(
     list: *const CPluginList,
     index: c_int,
 ) -> *mut CPathList {
@@ -381,13 +922,82 @@ pub extern "C" fn plugindepot_enumerate_files(
                 Box::into_raw(list)
             }
             Err(e) => {
-                eprintln!("Error enumerating files: {}", e);
+                report_error("Error enumerating files", &e);
                 ptr::null_mut()
             }
         }
     }
 }
 
+/// Check whether a plugin carries the macOS quarantine attribute.
+/// Returns 1 if quarantined, 0 if not (always 0 on other platforms), -1 on invalid input.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_is_quarantined(list: *const CPluginList, index: c_int) -> c_int {
+    if list.is_null() {
+        return -1;
+    }
+    
+    unsafe {
+        let plugins = &(*list).plugins;
+        let idx = index as usize;
+        
+        if idx >= plugins.len() {
+            return -1;
+        }
+        
+        if is_quarantined(&plugins[idx].install_path) { 1 } else { 0 }
+    }
+}
+
+/// Remove the macOS quarantine attribute from a plugin bundle.
+/// Returns 0 on success, 1 on error.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_remove_quarantine(list: *const CPluginList, index: c_int) -> c_int {
+    if list.is_null() {
+        return 1;
+    }
+    
+    unsafe {
+        let plugins = &(*list).plugins;
+        let idx = index as usize;
+        
+        if idx >= plugins.len() {
+            return 1;
+        }
+        
+        match remove_quarantine(&plugins[idx].install_path) {
+            Ok(_) => 0,
+            Err(e) => {
+                report_error("Error removing quarantine", &e);
+                1
+            }
+        }
+    }
+}
+
+/// Check whether a host can load a plugin natively, e.g., to warn about plugins
+/// that need Rosetta or a bit bridge. Both arguments use the `CPlugin.architecture`
+/// codes; an unknown architecture on either side counts as compatible.
+/// Returns 1 if compatible, 0 if not, -1 on invalid input.
+#[no_mangle]
+pub extern "C" fn plugindepot_is_compatible(plugin_arch: c_int, host_arch: c_int) -> c_int {
+    match (int_to_architecture(plugin_arch), int_to_architecture(host_arch)) {
+        (Some(plugin), Some(host)) => plugin.is_compatible_with(host) as c_int,
+        _ => {
+            set_last_error("Invalid architecture code");
+            -1
+        }
+    }
+}
+
 // ============================================================================
 // Icon Management
 // ============================================================================
@@ -396,8 +1006,13 @@ pub extern "C" fn plugindepot_enumerate_files(
 /// This should be called by the native UI after downloading the icon.
 /// Returns the cached file path on success, or null on error.
 /// Caller MUST call plugindepot_free_string() when done.
+///
+/// # Safety
+///
+/// `icon_url` must be null or a NUL-terminated string.
+/// `data` must be null or point to `data_length` readable bytes.
 #[no_mangle]
-pub extern "C" fn plugindepot_cache_icon(
+pub unsafe extern "C" fn plugindepot_cache_icon(
     icon_url: *const c_char,
     data: *const u8,
     data_length: c_int,
@@ -417,7 +1032,7 @@ pub extern "C" fn plugindepot_cache_icon(
         match cache_icon_data(url, data_slice) {
             Ok(path) => string_to_c_char(&path.to_string_lossy()),
             Err(e) => {
-                eprintln!("Error caching icon: {}", e);
+                report_error("Error caching icon", &e);
                 ptr::null_mut()
             }
         }
@@ -427,8 +1042,12 @@ pub extern "C" fn plugindepot_cache_icon(
 /// Get the cached icon path for a URL, if it exists.
 /// Returns null if the icon is not cached.
 /// Caller MUST call plugindepot_free_string() when done.
+///
+/// # Safety
+///
+/// `icon_url` must be null or a NUL-terminated string.
 #[no_mangle]
-pub extern "C" fn plugindepot_get_cached_icon_path(icon_url: *const c_char) -> *mut c_char {
+pub unsafe extern "C" fn plugindepot_get_cached_icon_path(icon_url: *const c_char) -> *mut c_char {
     if icon_url.is_null() {
         return ptr::null_mut();
     }
@@ -446,6 +1065,28 @@ pub extern "C" fn plugindepot_get_cached_icon_path(icon_url: *const c_char) -> *
     }
 }
 
+/// List the remote icon URLs of the plugins in a list that aren't cached yet.
+/// Each URL appears once; read them with plugindepot_path_list_get().
+/// Returns null if `list` is null.
+/// Caller MUST call plugindepot_free_path_list() when done.
+///
+/// # Safety
+///
+/// `list` must be null or a list from `plugindepot_scan_plugins()` that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_icons_needing_download(list: *const CPluginList) -> *mut CPathList {
+    if list.is_null() {
+        set_last_error("Plugin list is null");
+        return ptr::null_mut();
+    }
+    
+    let list = unsafe { &*list };
+    let paths = icons_needing_download(&list.plugins).into_iter()
+        .map(PathBuf::from)
+        .collect();
+    Box::into_raw(Box::new(CPathList { paths }))
+}
+
 /// Clear all cached icons.
 /// Returns 0 on success, 1 on error.
 #[no_mangle]
@@ -453,19 +1094,108 @@ pub extern "C" fn plugindepot_clear_icon_cache() -> c_int {
     match clear_icon_cache() {
         Ok(_) => 0,
         Err(e) => {
-            eprintln!("Error clearing icon cache: {}", e);
+            report_error("Error clearing icon cache", &e);
             1
         }
     }
 }
 
+/// Set the largest icon, in bytes, that plugindepot_cache_icon() will accept.
+#[no_mangle]
+pub extern "C" fn plugindepot_set_max_icon_size(max_bytes: u64) {
+    set_max_icon_size(usize::try_from(max_bytes).unwrap_or(usize::MAX));
+}
+
+// ============================================================================
+// User State
+// ============================================================================
+
+/// Set a custom display name for a plugin, persisted across scans.
+/// Pass a null or empty name to remove the custom label.
+/// Takes effect on the next scan. Returns 0 on success, 1 on error.
+///
+/// # Safety
+///
+/// `plugin_id` must be null or a NUL-terminated string.
+/// `name` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn plugindepot_set_display_name(plugin_id: *const c_char, name: *const c_char) -> c_int {
+    if plugin_id.is_null() {
+        set_last_error("Plugin ID is null");
+        return 1;
+    }
+    
+    unsafe {
+        let id = match CStr::from_ptr(plugin_id).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("Plugin ID is not valid UTF-8");
+                return 1;
+            }
+        };
+        let name = if name.is_null() {
+            ""
+        } else {
+            match CStr::from_ptr(name).to_str() {
+                Ok(s) => s,
+                Err(_) => {
+                    set_last_error("Display name is not valid UTF-8");
+                    return 1;
+                }
+            }
+        };
+        
+        match set_display_name(id, name) {
+            Ok(_) => 0,
+            Err(e) => {
+                report_error("Error setting display name", &e);
+                1
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Error Reporting
+// ============================================================================
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Record an error message for plugindepot_last_error() on this thread.
+fn set_last_error(message: &str) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message.to_string()));
+}
+
+/// Log an error and record it for plugindepot_last_error().
+fn report_error(context: &str, e: &anyhow::Error) {
+    eprintln!("{}: {}", context, e);
+    set_last_error(&format!("{}: {:#}", context, e));
+}
+
+/// Get the message for the most recent error on the calling thread.
+/// Returns null if no error has occurred.
+/// Caller MUST call plugindepot_free_string() when done.
+#[no_mangle]
+pub extern "C" fn plugindepot_last_error() -> *mut c_char {
+    LAST_ERROR.with(|last| match last.borrow().as_deref() {
+        Some(message) => string_to_c_char(message),
+        None => ptr::null_mut(),
+    })
+}
+
 // ============================================================================
 // String Management
 // ============================================================================
 
 /// Free a string returned by FFI functions.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library that hasn't been freed.
 #[no_mangle]
-pub extern "C" fn plugindepot_free_string(s: *mut c_char) {
+pub unsafe extern "C" fn plugindepot_free_string(s: *mut c_char) {
     free_c_char(s);
 }
 
@@ -494,5 +1224,104 @@ fn format_to_int(format: &PluginFormat) -> c_int {
         PluginFormat::VST3 => 1,
         PluginFormat::AU => 2,
         PluginFormat::AAX => 3,
+        PluginFormat::LV2 => 4,
+    }
+}
+
+/// Codes of the multi-architecture combinations, members in `Architectures::iter()` order
+const UNIVERSAL_CODES: &[(c_int, &[Architecture])] = &[
+    (4, &[Architecture::X86_64, Architecture::Arm64]),
+    (5, &[Architecture::X86, Architecture::X86_64]),
+    (6, &[Architecture::X86, Architecture::X86_64, Architecture::Arm64]),
+    (7, &[Architecture::X86, Architecture::Arm64]),
+];
+
+fn architecture_to_int(architecture: Architecture) -> c_int {
+    match architecture {
+        Architecture::Unknown => 0,
+        Architecture::X86 => 1,
+        Architecture::X86_64 => 2,
+        Architecture::Arm64 => 3,
+        Architecture::Universal(set) => UNIVERSAL_CODES.iter()
+            .find(|(_, members)| set.iter().eq(members.iter().copied()))
+            .map_or(0, |(code, _)| *code),
+    }
+}
+
+fn int_to_architecture(code: c_int) -> Option<Architecture> {
+    match code {
+        0 => Some(Architecture::Unknown),
+        1 => Some(Architecture::X86),
+        2 => Some(Architecture::X86_64),
+        3 => Some(Architecture::Arm64),
+        code => UNIVERSAL_CODES.iter()
+            .find(|(universal, _)| *universal == code)
+            .map(|(_, members)| members.iter().fold(Architectures::default(), |set, arch| set.with(*arch)))
+            .map(Architecture::Universal),
+    }
+}
+
+fn int_to_format(code: c_int) -> Option<PluginFormat> {
+    match code {
+        0 => Some(PluginFormat::VST2),
+        1 => Some(PluginFormat::VST3),
+        2 => Some(PluginFormat::AU),
+        3 => Some(PluginFormat::AAX),
+        4 => Some(PluginFormat::LV2),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{installed_plugin, isolated_home};
+    use crate::{Confidence, RelatedPath};
+    use std::fs;
+
+    extern "C" fn record_progress(files_done: u64, files_total: u64, bytes_done: u64, bytes_total: u64, user: *mut c_void) {
+        let calls = unsafe { &mut *(user as *mut Vec<[u64; 4]>) };
+        calls.push([files_done, files_total, bytes_done, bytes_total]);
+    }
+
+    #[test]
+    fn backup_reports_progress_through_the_callback() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Plugins/Synth.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "bundle").unwrap();
+        fs::write(bundle.join("synth.so"), [0u8; 1000]).unwrap();
+        let presets = dir.path().join("Presets/Synth");
+        fs::create_dir_all(&presets).unwrap();
+        for name in ["Init.preset", "Pad.preset"] {
+            fs::write(presets.join(name), name).unwrap();
+        }
+        let mut plugin = installed_plugin("Synth", &bundle, PluginFormat::LV2);
+        plugin.related_paths.preset_locations = vec![RelatedPath::new(presets, Confidence::High)];
+        let list = CPluginList { plugins: vec![plugin] };
+        let backups = dir.path().join("Backups");
+        fs::create_dir(&backups).unwrap();
+        let backup_dir = CString::new(backups.to_string_lossy().as_bytes()).unwrap();
+
+        let mut calls: Vec<[u64; 4]> = Vec::new();
+        let backup = unsafe {
+            plugindepot_backup_plugin_with_progress(
+                &list,
+                0,
+                backup_dir.as_ptr(),
+                Some(record_progress),
+                &mut calls as *mut Vec<[u64; 4]> as *mut c_void,
+            )
+        };
+        assert!(!backup.is_null());
+        unsafe { plugindepot_free_string(backup) };
+
+        assert!(calls.len() > 1);
+        assert!(calls.windows(2).all(|pair| pair[0][0] < pair[1][0] && pair[0][2] <= pair[1][2]));
+        assert!(calls.iter().all(|call| call[1] == calls[0][1] && call[3] == calls[0][3]));
+        let [files_done, files_total, bytes_done, bytes_total] = *calls.last().unwrap();
+        assert_eq!((files_done, bytes_done), (files_total, bytes_total));
+        assert!(bytes_total >= 1000);
     }
 }
@@ -0,0 +1,222 @@
+//! Aggregate plugin health check.
+//!
+//! Runs the individual checks (architecture, linked libraries, code signature,
+//! bundle structure, quarantine, license, duplicates) against one plugin and collects every problem
+//! into a `PluginHealthReport`, so a dashboard needs a single call per plugin.
+//! `audit_all()` runs it on every installed plugin.
+//! Checks that don't apply on the current platform are skipped.
+
+use crate::arch::bundle_executable;
+use crate::dependencies::missing_dependencies;
+use crate::duplicates::find_duplicates;
+use crate::quarantine::is_quarantined;
+use crate::registry::scan_installed;
+use crate::{Architecture, InstalledPlugin, PluginFormat};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// How serious a health issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    /// Worth knowing, nothing is broken
+    Info,
+    /// May cause problems or confusion
+    Warning,
+    /// The plugin will likely fail to load
+    Error,
+}
+
+/// The check that found a health issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HealthIssueKind {
+    /// Built for an architecture the host can't load natively
+    IncompatibleArchitecture,
+    /// Not signed, or signed but not notarized
+    Unsigned,
+    /// Carries the macOS quarantine attribute
+    Quarantined,
+    /// Missing, or a bundle without its executable
+    BrokenBundle,
+    /// Links against a library that isn't installed
+    MissingDependency,
+    /// Trial, demo, NFR, or beta license detected
+    NonCommercialLicense,
+    /// Also installed at another location in the same format
+    Duplicate,
+}
+
+/// One problem found by `health_check()`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthIssue {
+    pub kind: HealthIssueKind,
+    pub severity: Severity,
+    /// Human-readable description for display
+    pub message: String,
+}
+
+/// Every problem found with a plugin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginHealthReport {
+    pub plugin_id: String,
+    pub plugin_name: String,
+    pub format: PluginFormat,
+    pub issues: Vec<HealthIssue>,
+}
+
+impl PluginHealthReport {
+    fn new(plugin: &InstalledPlugin) -> Self {
+        PluginHealthReport {
+            plugin_id: plugin.plugin.id.clone(),
+            plugin_name: plugin.plugin.name.clone(),
+            format: plugin.format,
+            issues: Vec::new(),
+        }
+    }
+
+    /// Returns true if no issues were found.
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns the most serious severity among the issues, if any.
+    pub fn worst_severity(&self) -> Option<Severity> {
+        self.issues.iter().map(|issue| issue.severity).max()
+    }
+
+    fn push(&mut self, kind: HealthIssueKind, severity: Severity, message: String) {
+        self.issues.push(HealthIssue { kind, severity, message });
+    }
+}
+
+/// Checks a plugin for problems a host of architecture `host_arch` would run into.
+/// Duplicates need the other installed plugins; use `health_check_with()` to include them.
+pub fn health_check(plugin: &InstalledPlugin, host_arch: Architecture) -> PluginHealthReport {
+    health_check_with(plugin, host_arch, &[])
+}
+
+/// Like `health_check()`, also reporting copies of the plugin among `installed`
+/// (e.g., the result of `scan_installed()`).
+pub fn health_check_with(plugin: &InstalledPlugin, host_arch: Architecture, installed: &[InstalledPlugin]) -> PluginHealthReport {
+    let mut report = PluginHealthReport::new(plugin);
+
+    if let Some(problem) = bundle_problem(plugin) {
+        report.push(HealthIssueKind::BrokenBundle, Severity::Error, problem);
+        // The remaining checks read the plugin's files
+        return report;
+    }
+
+    if !plugin.architecture.is_compatible_with(host_arch) {
+        report.push(
+            HealthIssueKind::IncompatibleArchitecture,
+            Severity::Error,
+            format!("Built for {}, but the host is {}", plugin.architecture, host_arch),
+        );
+    }
+
+    match missing_dependencies(plugin) {
+        Ok(missing) => for dependency in missing {
+            report.push(
+                HealthIssueKind::MissingDependency,
+                Severity::Error,
+                format!("Needs {}, which isn't installed", dependency.name),
+            );
+        },
+        Err(e) => eprintln!("Warning: Failed to check dependencies of {}: {:#}", plugin.plugin.name, e),
+    }
+
+    check_signature(plugin, &mut report);
+
+    if is_quarantined(&plugin.install_path) {
+        report.push(
+            HealthIssueKind::Quarantined,
+            Severity::Error,
+            "Quarantined by macOS; hosts may refuse to load it until the quarantine is removed".to_string(),
+        );
+    }
+
+    if let Some(license_type) = plugin.license.as_ref().and_then(|license| license.license_type.as_ref()) {
+        report.push(
+            HealthIssueKind::NonCommercialLicense,
+            Severity::Info,
+            format!("{} license detected", license_type),
+        );
+    }
+
+    let copies = find_duplicates(installed).into_iter()
+        .find(|group| group.iter().any(|p| p.install_path == plugin.install_path))
+        .unwrap_or_default();
+    for copy in copies.iter().filter(|p| p.install_path != plugin.install_path) {
+        report.push(
+            HealthIssueKind::Duplicate,
+            Severity::Warning,
+            format!("Also installed at {:?}", copy.install_path),
+        );
+    }
+
+    report
+}
+
+/// Scans the system and checks every installed plugin for problems a host of this
+/// library's architecture would run into, duplicates included. One report per
+/// plugin, healthy ones too, in scan order.
+pub fn audit_all() -> Result<Vec<PluginHealthReport>> {
+    Ok(audit_plugins(&scan_installed()?, Architecture::host()))
+}
+
+/// Like `audit_all()`, checking already-scanned `plugins` for a host of `host_arch`.
+pub fn audit_plugins(plugins: &[InstalledPlugin], host_arch: Architecture) -> Vec<PluginHealthReport> {
+    plugins.iter()
+        .map(|plugin| health_check_with(plugin, host_arch, plugins))
+        .collect()
+}
+
+/// Describes why a plugin's files are unusable, if they are.
+fn bundle_problem(plugin: &InstalledPlugin) -> Option<String> {
+    if !plugin.install_path.exists() {
+        return Some(format!("Plugin not found at {:?}", plugin.install_path));
+    }
+    if !plugin.install_path.is_dir() {
+        return None;
+    }
+
+    // LV2 bundles are flat folders described by their manifest
+    if plugin.format == PluginFormat::LV2 {
+        return (!plugin.install_path.join("manifest.ttl").is_file())
+            .then(|| "Bundle has no manifest.ttl".to_string());
+    }
+
+    let contents = plugin.install_path.join("Contents");
+    if !contents.is_dir() {
+        return Some("Bundle has no Contents folder".to_string());
+    }
+
+    // macOS bundles declare their executable in Info.plist under Contents/MacOS
+    let is_macos_bundle = contents.join("Info.plist").is_file() || contents.join("MacOS").is_dir();
+    if is_macos_bundle && bundle_executable(&plugin.install_path).is_none() {
+        return Some("Bundle has no executable in Contents/MacOS".to_string());
+    }
+
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn check_signature(plugin: &InstalledPlugin, report: &mut PluginHealthReport) {
+    match crate::signing::check_signature(plugin) {
+        Ok(status) if !status.signed => report.push(
+            HealthIssueKind::Unsigned,
+            Severity::Warning,
+            "Not code signed".to_string(),
+        ),
+        Ok(status) if status.notarized == Some(false) => report.push(
+            HealthIssueKind::Unsigned,
+            Severity::Warning,
+            "Signed but not notarized".to_string(),
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!("Warning: Failed to check signature of {:?}: {}", plugin.install_path, e),
+    }
+}
+
+/// Code signatures are only checked on macOS
+#[cfg(not(target_os = "macos"))]
+fn check_signature(_plugin: &InstalledPlugin, _report: &mut PluginHealthReport) {}
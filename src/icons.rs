@@ -8,59 +8,292 @@
 //! - Fetch icons from remote URLs (http/https)
 //! - Support local file:// URLs for icons in plugin bundles
 //! - Cache remote icons locally to reduce network requests
+//! - List the remote icons still to download in one batch (see `icons_needing_download()`)
 //! - Provide icon data as raw bytes for native UI consumption
 //! - Support common image formats (PNG, JPEG, ICNS, ICO)
+//! - Reject oversized icons (see `set_max_icon_size()`)
+//! - Detect and remove missing or truncated cache entries (see `verify_icon_cache()`)
+//! - Convert macOS `.icns` icons to a cached PNG of the largest size (see `cache_icns_as_png()`)
+//!
+//! # Cache Layout
+//!
+//! Icons are stored once per unique content. A cached file is named
+//! `<blake3>.<ext>`: the lowercase hex BLAKE3 hash of the icon's bytes, followed by
+//! an extension sniffed from the bytes (`png`, `jpg`, `gif`, `icns`, `ico`, `svg`),
+//! or no extension if the format isn't recognized. The name depends only on the
+//! content, never on the URL, so the same icon always maps to the same file.
+//! `manifest.json` in the cache directory maps each URL to its stored file, so
+//! identical icons shared by many plugins take up space only once.
+//!
+//! Caches written by older versions (files named by the MD5 of their URL, or by
+//! the SHA-256 of their bytes with the URL's extension) are migrated to this scheme
+//! on first use; see `migrate_icon_cache()`.
 
+use crate::atomic::write_atomic;
+use crate::InstalledPlugin;
 use anyhow::{Context, Result, anyhow};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Name of the url → cached file mapping inside the cache directory
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Marker file recording the filename scheme of the cache directory
+const SCHEME_FILE: &str = "scheme";
+
+/// Filename scheme written by this version (1 = MD5 of URL, 2 = SHA-256 of bytes, 3 = BLAKE3 of bytes)
+const CACHE_SCHEME: u32 = 3;
+
+/// Serializes manifest updates so concurrent cache writes don't drop entries
+static MANIFEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// Default upper bound on the size of a cached icon (4 MB)
+pub const DEFAULT_MAX_ICON_SIZE: usize = 4 * 1024 * 1024;
 
-/// Get the cache directory for plugin icons
+static MAX_ICON_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_ICON_SIZE);
+
+/// Returns the largest icon, in bytes, that will be written to the cache.
+pub fn max_icon_size() -> usize {
+    MAX_ICON_SIZE.load(Ordering::Relaxed)
+}
+
+/// Sets the largest icon, in bytes, that will be written to the cache.
+/// Icons above this size are rejected by `cache_icon_data()`.
+pub fn set_max_icon_size(bytes: usize) {
+    MAX_ICON_SIZE.store(bytes, Ordering::Relaxed);
+}
+
+/// Get the cache directory for plugin icons.
+/// Falls back to the system temp directory if the user's environment variables are unset.
 fn get_icon_cache_dir() -> Result<PathBuf> {
     #[cfg(target_os = "macos")]
     {
-        let home = std::env::var("HOME").context("HOME environment variable not set")?;
-        let cache_dir = PathBuf::from(format!("{}/Library/Caches/PluginDepot/icons", home));
-        Ok(cache_dir)
+        if let Ok(home) = std::env::var("HOME") {
+            return Ok(PathBuf::from(format!("{}/Library/Caches/PluginDepot/icons", home)));
+        }
+        eprintln!("Warning: HOME environment variable not set; using temp directory for icon cache");
     }
     
     #[cfg(target_os = "windows")]
     {
-        let appdata = std::env::var("LOCALAPPDATA")
-            .context("LOCALAPPDATA environment variable not set")?;
-        let cache_dir = PathBuf::from(format!("{}\\PluginDepot\\icons", appdata));
-        Ok(cache_dir)
+        if let Ok(appdata) = std::env::var("LOCALAPPDATA") {
+            return Ok(PathBuf::from(format!("{}\\PluginDepot\\icons", appdata)));
+        }
+        eprintln!("Warning: LOCALAPPDATA environment variable not set; using temp directory for icon cache");
     }
     
     #[cfg(not(any(target_os = "macos", target_os = "windows")))]
     {
-        let home = std::env::var("HOME").context("HOME environment variable not set")?;
-        let cache_dir = PathBuf::from(format!("{}/.cache/plugindepot/icons", home));
-        Ok(cache_dir)
+        if let Ok(home) = std::env::var("HOME") {
+            return Ok(PathBuf::from(format!("{}/.cache/plugindepot/icons", home)));
+        }
+        eprintln!("Warning: HOME environment variable not set; using temp directory for icon cache");
     }
+    
+    Ok(std::env::temp_dir().join("PluginDepot").join("icons"))
 }
 
-/// Ensures the icon cache directory exists
+/// Ensures the icon cache directory exists and uses the current filename scheme
 fn ensure_cache_dir_exists() -> Result<PathBuf> {
     let cache_dir = get_icon_cache_dir()?;
     fs::create_dir_all(&cache_dir)
         .context(format!("Failed to create icon cache directory: {:?}", cache_dir))?;
+    migrate_if_needed(&cache_dir);
     Ok(cache_dir)
 }
 
-/// Generate a cache filename from a URL
+/// Generate the cache filename used before content addressing, from a URL.
+/// Still checked so icons cached by older versions are found and migrated.
 fn url_to_cache_filename(url: &str) -> String {
     // Simple hash-based filename to avoid filesystem issues with URL characters
     let hash = format!("{:x}", md5::compute(url.as_bytes()));
     
-    // Try to preserve the extension
-    if let Some(extension) = url.rsplit('.').next() {
-        if extension.len() <= 4 && extension.chars().all(|c| c.is_alphanumeric()) {
-            return format!("{}.{}", hash, extension);
+    match url_extension(url) {
+        Some(extension) => format!("{}.{}", hash, extension),
+        None => hash,
+    }
+}
+
+/// Generate the cache filename of icon bytes: `<blake3 hex>.<sniffed extension>`.
+fn content_cache_filename(data: &[u8]) -> String {
+    let hash = blake3::hash(data).to_hex();
+    
+    match sniff_extension(data) {
+        Some(extension) => format!("{}.{}", hash, extension),
+        None => hash.to_string(),
+    }
+}
+
+/// Returns the file extension matching the image format of `data`, if recognized.
+fn sniff_extension(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(PNG_SIGNATURE) {
+        Some("png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("gif")
+    } else if data.starts_with(b"icns") {
+        Some("icns")
+    } else if data.starts_with(&[0x00, 0x00, 0x01, 0x00]) {
+        Some("ico")
+    } else {
+        let head = String::from_utf8_lossy(&data[..data.len().min(256)]).to_lowercase();
+        head.contains("<svg").then_some("svg")
+    }
+}
+
+/// Returns the URL's file extension if it looks like one (short and alphanumeric).
+fn url_extension(url: &str) -> Option<&str> {
+    url.rsplit('.').next()
+        .filter(|ext| ext.len() <= 4 && ext.chars().all(|c| c.is_alphanumeric()))
+}
+
+/// Reads the url → cached filename manifest. Missing or unreadable manifests are treated as empty.
+fn load_manifest(cache_dir: &Path) -> HashMap<String, String> {
+    let manifest_path = cache_dir.join(MANIFEST_FILE);
+    let content = match fs::read_to_string(&manifest_path) {
+        Ok(content) => content,
+        Err(_) => return HashMap::new(),
+    };
+    
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        eprintln!("Warning: Ignoring corrupt icon cache manifest {:?}: {}", manifest_path, e);
+        HashMap::new()
+    })
+}
+
+/// Writes the url → cached filename manifest.
+fn save_manifest(cache_dir: &Path, manifest: &HashMap<String, String>) -> Result<()> {
+    let manifest_path = cache_dir.join(MANIFEST_FILE);
+    let content = serde_json::to_string_pretty(manifest)?;
+    write_atomic(&manifest_path, content.as_bytes())
+        .context(format!("Failed to write icon cache manifest: {:?}", manifest_path))
+}
+
+/// Finds the cached file for a URL, via the manifest or the legacy filename scheme.
+/// An icon found under its legacy name is migrated to the current scheme.
+fn lookup_cached_icon(cache_dir: &Path, url: &str) -> Option<PathBuf> {
+    migrate_if_needed(cache_dir);
+    
+    if let Some(filename) = load_manifest(cache_dir).get(url) {
+        let path = cache_dir.join(filename);
+        if path.exists() {
+            return Some(path);
         }
     }
     
-    hash
+    // Files named by URL have no manifest entry, so they can only be
+    // migrated once their URL is asked for
+    let legacy_path = cache_dir.join(url_to_cache_filename(url));
+    if legacy_path.exists() {
+        let _guard = MANIFEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut manifest = load_manifest(cache_dir);
+        return match migrate_file(cache_dir, &legacy_path) {
+            Ok(filename) => {
+                manifest.insert(url.to_string(), filename.clone());
+                if let Err(e) = save_manifest(cache_dir, &manifest) {
+                    eprintln!("Warning: {}", e);
+                }
+                Some(cache_dir.join(filename))
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to migrate cached icon {:?}: {}", legacy_path, e);
+                Some(legacy_path)
+            }
+        };
+    }
+    
+    None
+}
+
+/// Renames a cached file to its name under the current scheme and returns the new name.
+/// If that name is already taken (the same icon cached under another URL), the
+/// file is removed instead.
+fn migrate_file(cache_dir: &Path, path: &Path) -> Result<String> {
+    let data = fs::read(path)
+        .context(format!("Failed to read icon file: {:?}", path))?;
+    let filename = content_cache_filename(&data);
+    let new_path = cache_dir.join(&filename);
+    
+    if new_path == path {
+        return Ok(filename);
+    }
+    if new_path.exists() {
+        fs::remove_file(path)
+            .context(format!("Failed to remove duplicate icon file: {:?}", path))?;
+    } else {
+        fs::rename(path, &new_path)
+            .context(format!("Failed to rename {:?} to {:?}", path, new_path))?;
+    }
+    Ok(filename)
+}
+
+/// Renames every icon listed in the cache manifest to the current filename scheme
+/// and rewrites the manifest to match. Returns the number of manifest entries updated.
+///
+/// This runs automatically, once, the first time the cache is used after an upgrade.
+/// Icons cached by the oldest versions (named by URL, without a manifest entry)
+/// are migrated individually when `get_cached_icon_path()` looks them up.
+pub fn migrate_icon_cache() -> Result<usize> {
+    let cache_dir = get_icon_cache_dir()?;
+    if !cache_dir.exists() {
+        return Ok(0);
+    }
+    migrate_cache_dir(&cache_dir)
+}
+
+fn migrate_cache_dir(cache_dir: &Path) -> Result<usize> {
+    let _guard = MANIFEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut manifest = load_manifest(cache_dir);
+    // Several URLs may share one file; it's only renamed once
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    let mut updated = 0;
+    
+    for filename in manifest.values_mut() {
+        let new_name = match renamed.get(filename.as_str()) {
+            Some(new_name) => new_name.clone(),
+            None => {
+                let path = cache_dir.join(filename.as_str());
+                if !path.exists() {
+                    continue;
+                }
+                match migrate_file(cache_dir, &path) {
+                    Ok(new_name) => {
+                        renamed.insert(filename.clone(), new_name.clone());
+                        new_name
+                    }
+                    Err(e) => {
+                        eprintln!("Warning: Failed to migrate cached icon {:?}: {}", path, e);
+                        continue;
+                    }
+                }
+            }
+        };
+        if *filename != new_name {
+            *filename = new_name;
+            updated += 1;
+        }
+    }
+    
+    save_manifest(cache_dir, &manifest)?;
+    write_atomic(&cache_dir.join(SCHEME_FILE), CACHE_SCHEME.to_string().as_bytes())
+        .context("Failed to record icon cache scheme")?;
+    Ok(updated)
+}
+
+/// Migrates the cache directory if it was written with an older filename scheme.
+fn migrate_if_needed(cache_dir: &Path) {
+    let scheme = fs::read_to_string(cache_dir.join(SCHEME_FILE)).ok()
+        .and_then(|content| content.trim().parse::<u32>().ok());
+    if scheme == Some(CACHE_SCHEME) || !cache_dir.exists() {
+        return;
+    }
+    if let Err(e) = migrate_cache_dir(cache_dir) {
+        eprintln!("Warning: Failed to migrate icon cache {:?}: {}", cache_dir, e);
+    }
 }
 
 /// Fetch an icon from a URL and cache it locally.
@@ -68,11 +301,9 @@ fn url_to_cache_filename(url: &str) -> String {
 /// If the icon is already cached, returns the cached path without downloading.
 pub fn fetch_icon(url: &str) -> Result<PathBuf> {
     let cache_dir = ensure_cache_dir_exists()?;
-    let cache_filename = url_to_cache_filename(url);
-    let cache_path = cache_dir.join(&cache_filename);
     
     // If already cached, return the cached path
-    if cache_path.exists() {
+    if let Some(cache_path) = lookup_cached_icon(&cache_dir, url) {
         return Ok(cache_path);
     }
     
@@ -95,13 +326,28 @@ pub fn load_icon_data(url: &str) -> Result<Vec<u8>> {
 
 /// Save icon data to the cache.
 /// This can be called from FFI after the native UI downloads the icon.
+/// Identical bytes cached under different URLs share a single file.
+/// Fails without writing anything if the data exceeds `max_icon_size()`.
 pub fn cache_icon_data(url: &str, data: &[u8]) -> Result<PathBuf> {
+    let limit = max_icon_size();
+    if data.len() > limit {
+        return Err(anyhow!("Icon for {} is {} bytes, exceeding the {} byte limit", url, data.len(), limit));
+    }
+    
     let cache_dir = ensure_cache_dir_exists()?;
-    let cache_filename = url_to_cache_filename(url);
+    let cache_filename = content_cache_filename(data);
     let cache_path = cache_dir.join(&cache_filename);
     
-    fs::write(&cache_path, data)
-        .context(format!("Failed to write icon cache file: {:?}", cache_path))?;
+    // Content is already stored if another URL cached the same bytes
+    if !cache_path.exists() {
+        fs::write(&cache_path, data)
+            .context(format!("Failed to write icon cache file: {:?}", cache_path))?;
+    }
+    
+    let _guard = MANIFEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut manifest = load_manifest(&cache_dir);
+    manifest.insert(url.to_string(), cache_filename);
+    save_manifest(&cache_dir, &manifest)?;
     
     Ok(cache_path)
 }
@@ -121,16 +367,203 @@ pub fn get_cached_icon_path(url: &str) -> Option<PathBuf> {
     }
     
     // For HTTP(S) URLs, check the cache
-    if let Ok(cache_dir) = get_icon_cache_dir() {
-        let cache_filename = url_to_cache_filename(url);
-        let cache_path = cache_dir.join(&cache_filename);
+    get_icon_cache_dir().ok()
+        .and_then(|cache_dir| lookup_cached_icon(&cache_dir, url))
+}
+
+/// Result of `verify_icon_cache()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheRepairReport {
+    /// Number of manifest entries checked
+    pub checked: usize,
+    /// URLs whose cached file no longer exists; their entries were removed
+    pub missing: Vec<String>,
+    /// URLs whose cached file isn't a complete image; the file and the entries were removed
+    pub invalid: Vec<String>,
+}
+
+impl CacheRepairReport {
+    /// Returns true if every entry was valid and nothing was removed.
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.invalid.is_empty()
+    }
+}
+
+/// Checks that every icon in the cache manifest exists and is a complete image of a
+/// known format, removing entries (and files) that aren't, so UIs don't display
+/// broken images. Removed URLs are reported as needing download again.
+pub fn verify_icon_cache() -> Result<CacheRepairReport> {
+    let cache_dir = get_icon_cache_dir()?;
+    if !cache_dir.exists() {
+        return Ok(CacheRepairReport::default());
+    }
+    verify_cache_dir(&cache_dir)
+}
+
+fn verify_cache_dir(cache_dir: &Path) -> Result<CacheRepairReport> {
+    let mut report = CacheRepairReport::default();
+    migrate_if_needed(cache_dir);
+    
+    let _guard = MANIFEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut manifest = load_manifest(cache_dir);
+    report.checked = manifest.len();
+    
+    // Several URLs may share one file; each file is read once
+    let mut validity: HashMap<String, bool> = HashMap::new();
+    manifest.retain(|url, filename| {
+        let path = cache_dir.join(filename.as_str());
+        if !path.is_file() {
+            report.missing.push(url.clone());
+            return false;
+        }
+        let valid = *validity.entry(filename.clone())
+            .or_insert_with(|| fs::read(&path).is_ok_and(|data| is_complete_image(&data)));
+        if !valid {
+            report.invalid.push(url.clone());
+        }
+        valid
+    });
+    
+    for filename in validity.iter().filter(|(_, valid)| !**valid).map(|(filename, _)| filename) {
+        let path = cache_dir.join(filename);
+        if let Err(e) = fs::remove_file(&path) {
+            eprintln!("Warning: Failed to remove invalid cached icon {:?}: {}", path, e);
+        }
+    }
+    
+    if !report.is_clean() {
+        save_manifest(cache_dir, &manifest)?;
+    }
+    report.missing.sort();
+    report.invalid.sort();
+    Ok(report)
+}
+
+/// Returns true if `data` is an image of a supported format that isn't cut short.
+/// Only headers and end markers are checked; the image isn't decoded.
+fn is_complete_image(data: &[u8]) -> bool {
+    match sniff_extension(data) {
+        // The last chunk is IEND: length (4), "IEND", CRC (4)
+        Some("png") => data.len() >= PNG_SIGNATURE.len() + 12 && &data[data.len() - 8..data.len() - 4] == b"IEND",
+        // End of image marker
+        Some("jpg") => data.ends_with(&[0xFF, 0xD9]),
+        // Trailer byte
+        Some("gif") => data.ends_with(&[0x3B]),
+        // The header declares the total file length
+        Some("icns") => data.get(4..8)
+            .is_some_and(|len| u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize == data.len()),
+        Some("ico") => ico_is_complete(data),
+        Some("svg") => String::from_utf8_lossy(data).contains("</svg>"),
+        _ => false,
+    }
+}
+
+/// Checks that every image of an `.ico` file lies within the file.
+fn ico_is_complete(data: &[u8]) -> bool {
+    let count = match data.get(4..6) {
+        Some(count) => u16::from_le_bytes([count[0], count[1]]) as usize,
+        None => return false,
+    };
+    // Each 16-byte directory entry ends with the image's size and offset
+    count > 0 && (0..count).all(|index| {
+        let entry = 6 + index * 16;
+        let field = |offset: usize| data.get(entry + offset..entry + offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize);
+        match (field(8), field(12)) {
+            (Some(size), Some(offset)) => offset.checked_add(size).is_some_and(|end| end <= data.len()),
+            _ => false,
+        }
+    })
+}
+
+/// Returns the remote icon URLs of `plugins` that aren't cached yet, each once, in
+/// plugin order. The native UI can download them in one batch and store each with
+/// `cache_icon_data()`. Local `file://` icons never need downloading.
+pub fn icons_needing_download(plugins: &[InstalledPlugin]) -> Vec<String> {
+    let cache_dir = get_icon_cache_dir().ok();
+    let mut urls: Vec<String> = Vec::new();
+    
+    for url in plugins.iter().filter_map(|p| p.plugin.icon_url.as_ref()) {
+        let is_remote = url.starts_with("http://") || url.starts_with("https://");
+        if !is_remote || urls.contains(url) {
+            continue;
+        }
+        let cached = cache_dir.as_ref().is_some_and(|dir| lookup_cached_icon(dir, url).is_some());
+        if !cached {
+            urls.push(url.clone());
+        }
+    }
+    
+    urls
+}
+
+/// PNG file signature
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Extracts the highest-resolution PNG image from an `.icns` file.
+/// Modern icns files store their larger sizes (`ic07`-`ic14`) as embedded PNGs;
+/// files that only contain legacy RLE bitmaps or JPEG 2000 images are rejected.
+pub fn extract_icns_png(icns_path: &Path) -> Result<Vec<u8>> {
+    let data = fs::read(icns_path)
+        .context(format!("Failed to read icon file: {:?}", icns_path))?;
+    
+    if data.get(0..4) != Some(b"icns".as_slice()) {
+        return Err(anyhow!("Not an icns file: {:?}", icns_path));
+    }
+    
+    // Entries follow the 8-byte header: 4-byte type, 4-byte big-endian length
+    // (including the entry header), then the image data
+    let mut best: Option<(u32, &[u8])> = None;
+    let mut offset = 8;
+    while let Some(header) = data.get(offset..offset + 8) {
+        let length = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if length < 8 {
+            break;
+        }
+        let Some(image) = data.get(offset + 8..offset + length) else {
+            break;
+        };
         
-        if cache_path.exists() {
-            return Some(cache_path);
+        if let Some(width) = png_width(image) {
+            if best.is_none_or(|(best_width, _)| width > best_width) {
+                best = Some((width, image));
+            }
         }
+        offset += length;
     }
     
-    None
+    best.map(|(_, image)| image.to_vec())
+        .ok_or_else(|| anyhow!("No PNG image in icns file: {:?}", icns_path))
+}
+
+/// Returns the width from a PNG's IHDR chunk, or None if the data isn't a PNG.
+fn png_width(data: &[u8]) -> Option<u32> {
+    if !data.starts_with(PNG_SIGNATURE) {
+        return None;
+    }
+    // Signature (8) + IHDR length (4) + "IHDR" (4), then width
+    let width = data.get(16..20)?;
+    Some(u32::from_be_bytes([width[0], width[1], width[2], width[3]]))
+}
+
+/// Caches the largest PNG inside an `.icns` file and returns the cached PNG's path.
+/// Already-converted icons are returned from the cache without re-reading the icns.
+pub fn cache_icns_as_png(icns_path: &Path) -> Result<PathBuf> {
+    // Cache key for the converted image. The modification time makes an updated icon
+    // convert again
+    let mtime = fs::metadata(icns_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    let key = format!("file://{}?mtime={}.png", icns_path.display(), mtime);
+    
+    if let Some(cached) = get_icon_cache_dir().ok().and_then(|dir| lookup_cached_icon(&dir, &key)) {
+        return Ok(cached);
+    }
+    
+    let png = extract_icns_png(icns_path)?;
+    cache_icon_data(&key, &png)
 }
 
 /// Clear the icon cache directory
@@ -145,3 +578,70 @@ pub fn clear_icon_cache() -> Result<()> {
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A complete PNG: signature, IHDR, and IEND chunks (CRCs aren't checked)
+    fn png(width: u8) -> Vec<u8> {
+        let mut data = PNG_SIGNATURE.to_vec();
+        data.extend([0, 0, 0, 13]);
+        data.extend(b"IHDR");
+        data.extend([0, 0, 0, width, 0, 0, 0, 1, 8, 6, 0, 0, 0]);
+        data.extend([0; 4]);
+        data.extend([0, 0, 0, 0]);
+        data.extend(b"IEND");
+        data.extend([0; 4]);
+        data
+    }
+
+    #[test]
+    fn removes_truncated_icons() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache_dir = cache_dir.path();
+        let complete = png(16);
+        let truncated = &png(32)[..20];
+        let mut manifest = HashMap::new();
+        for (url, data) in [("https://example.com/a.png", &complete[..]), ("https://example.com/b.png", truncated)] {
+            fs::write(cache_dir.join(content_cache_filename(data)), data).unwrap();
+            manifest.insert(url.to_string(), content_cache_filename(data));
+        }
+        manifest.insert(String::from("https://example.com/gone.png"), String::from("missing.png"));
+        save_manifest(cache_dir, &manifest).unwrap();
+
+        let report = verify_cache_dir(cache_dir).unwrap();
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.invalid, ["https://example.com/b.png"]);
+        assert_eq!(report.missing, ["https://example.com/gone.png"]);
+        assert!(!cache_dir.join(content_cache_filename(truncated)).exists());
+        assert_eq!(load_manifest(cache_dir).keys().collect::<Vec<_>>(), ["https://example.com/a.png"]);
+        assert!(verify_cache_dir(cache_dir).unwrap().is_clean());
+    }
+    #[test]
+    fn migrates_icons_cached_under_old_names() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache_dir = cache_dir.path();
+        let listed = png(16);
+        let unlisted = png(32);
+        // An older scheme: listed in the manifest under a name that isn't the content hash
+        fs::write(cache_dir.join("0123abcd.png"), &listed).unwrap();
+        let manifest = HashMap::from([(String::from("https://example.com/listed.png"), String::from("0123abcd.png"))]);
+        save_manifest(cache_dir, &manifest).unwrap();
+        // The oldest scheme: named by URL, with no manifest entry
+        let url = "https://example.com/unlisted.png";
+        fs::write(cache_dir.join(url_to_cache_filename(url)), &unlisted).unwrap();
+
+        let path = lookup_cached_icon(cache_dir, url).unwrap();
+        assert_eq!(path, cache_dir.join(content_cache_filename(&unlisted)));
+        assert_eq!(fs::read(&path).unwrap(), unlisted);
+        assert!(!cache_dir.join(url_to_cache_filename(url)).exists());
+        assert!(!cache_dir.join("0123abcd.png").exists());
+
+        let manifest = load_manifest(cache_dir);
+        assert_eq!(manifest["https://example.com/listed.png"], content_cache_filename(&listed));
+        assert_eq!(manifest[url], content_cache_filename(&unlisted));
+        assert_eq!(fs::read_to_string(cache_dir.join(SCHEME_FILE)).unwrap(), CACHE_SCHEME.to_string());
+        assert_eq!(migrate_cache_dir(cache_dir).unwrap(), 0);
+    }
+}
@@ -0,0 +1,419 @@
+//! Machine-wide plugin inventory export and reconciliation.
+//!
+//! An inventory is a portable list of everything installed on a machine (id, name,
+//! version, format, vendor). It is distinct from export packages: no binaries move.
+//! Importing an inventory on another machine produces a diff reporting which plugins
+//! are present, missing, or installed at a different version. A migration plan goes
+//! one step further and says what to install or update to bring a machine in line.
+//! `merge_scans()` combines the scans of several machines into one list recording
+//! which machines have each plugin.
+//!
+//! Plugins are matched across machines by `stable_identity()`, which doesn't depend
+//! on where or on which platform a plugin is installed.
+
+use crate::registry::{legacy_plugin_id, normalize_plugin_name, scan_installed};
+use crate::{InstalledPlugin, PluginFormat};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// A single plugin listed in an inventory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InventoryEntry {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub format: PluginFormat,
+    pub vendor: Option<String>,
+    /// See `stable_identity()`; absent in inventories from older versions, which
+    /// are matched by `id`
+    #[serde(default)]
+    pub identity: Option<String>,
+}
+
+impl From<&InstalledPlugin> for InventoryEntry {
+    fn from(plugin: &InstalledPlugin) -> Self {
+        InventoryEntry {
+            id: plugin.plugin.id.clone(),
+            name: plugin.plugin.name.clone(),
+            version: plugin.plugin.version.clone(),
+            format: plugin.format,
+            vendor: plugin.plugin.author.clone(),
+            identity: Some(stable_identity(plugin)),
+        }
+    }
+}
+
+/// Returns a key identifying a plugin independently of its install path and
+/// platform: `<format>:<vendor>:<uid>`, e.g., `vst3:fabfilter:72c4db717a4d459aa97e51a94f1dcd8b`.
+/// The UID is the identifier the plugin declares on every platform (VST3 class
+/// ID, LV2 URI), as recorded at scan time; plugins without one use their
+/// normalized name instead. Nothing is read from disk, so plugins scanned on
+/// another machine get the same identity they had there.
+pub fn stable_identity(plugin: &InstalledPlugin) -> String {
+    let uid = plugin.uid.as_deref()
+        .map(|uid| uid.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect::<String>())
+        .filter(|uid| !uid.is_empty());
+    // A custom display name shouldn't change the identity
+    let name = plugin.plugin.original_name.as_deref().unwrap_or(&plugin.plugin.name);
+
+    let vendor = plugin.plugin.author.as_deref()
+        .map(|vendor| vendor.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect::<String>())
+        .filter(|vendor| !vendor.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    format!(
+        "{}:{}:{}",
+        format!("{:?}", plugin.format).to_lowercase(),
+        vendor,
+        uid.unwrap_or_else(|| normalize_plugin_name(name)),
+    )
+}
+
+/// Finds the plugin an inventory entry refers to: by identity when the entry
+/// has one, otherwise or if nothing has that identity, by ID. Identities can
+/// differ for the same plugin when one platform's metadata lacks the vendor or
+/// UID the other recorded.
+fn find_installed<'a>(entry: &InventoryEntry, by_identity: &HashMap<String, &'a InstalledPlugin>, by_id: &HashMap<String, &'a InstalledPlugin>) -> Option<&'a InstalledPlugin> {
+    entry.identity.as_ref()
+        .and_then(|identity| by_identity.get(identity))
+        .or_else(|| by_id.get(entry.id.as_str()))
+        .copied()
+}
+
+/// Indexes plugins by `stable_identity()` and by ID for `find_installed()`.
+/// Inventories from older versions use legacy IDs (see `legacy_plugin_id()`),
+/// which are indexed too; a current ID wins over another plugin's legacy one.
+fn index_installed(plugins: &[InstalledPlugin]) -> (HashMap<String, &InstalledPlugin>, HashMap<String, &InstalledPlugin>) {
+    let by_identity = plugins.iter().map(|p| (stable_identity(p), p)).collect();
+    let by_id = plugins.iter().map(|p| (legacy_plugin_id(p), p))
+        .chain(plugins.iter().map(|p| (p.plugin.id.clone(), p)))
+        .collect();
+    (by_identity, by_id)
+}
+
+/// Everything installed on a machine at a point in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Inventory {
+    /// RFC 3339 timestamp of when the inventory was taken
+    pub created: String,
+    pub plugins: Vec<InventoryEntry>,
+}
+
+impl Inventory {
+    /// Builds an inventory from already-scanned plugins.
+    pub fn from_plugins(plugins: &[InstalledPlugin]) -> Self {
+        Inventory {
+            created: chrono::Local::now().to_rfc3339(),
+            plugins: plugins.iter().map(InventoryEntry::from).collect(),
+        }
+    }
+
+    /// Writes the inventory as pretty-printed JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)
+            .context(format!("Failed to write inventory: {:?}", path))?;
+        Ok(())
+    }
+
+    /// Reads an inventory previously written with `save()`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .context(format!("Failed to read inventory: {:?}", path))?;
+        serde_json::from_str(&content)
+            .context(format!("Failed to parse inventory: {:?}", path))
+    }
+}
+
+/// A plugin present on both sides but at different versions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionMismatch {
+    pub entry: InventoryEntry,
+    pub installed_version: String,
+}
+
+/// Reconciliation of an inventory against the plugins installed on this machine.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InventoryDiff {
+    /// Listed in the inventory and installed at the same version
+    pub present: Vec<InventoryEntry>,
+    /// Listed in the inventory but not installed
+    pub missing: Vec<InventoryEntry>,
+    /// Installed, but at a different version than the inventory lists
+    pub version_mismatch: Vec<VersionMismatch>,
+}
+
+/// Scans this machine and returns its inventory.
+pub fn export_inventory() -> Result<Inventory> {
+    let installed = scan_installed()?;
+    Ok(Inventory::from_plugins(&installed))
+}
+
+/// Compares an inventory against the plugins available on this machine.
+/// Nothing is installed or copied; this only reports differences.
+pub fn import_inventory(inventory: &Inventory, available: &[InstalledPlugin]) -> InventoryDiff {
+    let (by_identity, by_id) = index_installed(available);
+
+    let mut diff = InventoryDiff::default();
+
+    for entry in &inventory.plugins {
+        match find_installed(entry, &by_identity, &by_id) {
+            None => diff.missing.push(entry.clone()),
+            Some(plugin) if plugin.plugin.version != entry.version => {
+                diff.version_mismatch.push(VersionMismatch {
+                    entry: entry.clone(),
+                    installed_version: plugin.plugin.version.clone(),
+                });
+            }
+            Some(_) => diff.present.push(entry.clone()),
+        }
+    }
+
+    diff
+}
+
+/// What to do on a target machine to match a source machine's inventory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MigrationPlan {
+    /// Not installed on the target
+    pub install: Vec<InventoryEntry>,
+    /// Installed on the target at an older version
+    pub update: Vec<VersionMismatch>,
+    /// Installed on the target at the same or a newer version
+    pub skip: Vec<InventoryEntry>,
+}
+
+/// Plans the installs and updates needed for `target` to have everything in `source`.
+/// Plugins are matched by `stable_identity()`. Nothing is installed; this only classifies.
+pub fn migration_plan(source: &Inventory, target: &[InstalledPlugin]) -> MigrationPlan {
+    let (by_identity, by_id) = index_installed(target);
+
+    let mut plan = MigrationPlan::default();
+
+    for entry in &source.plugins {
+        match find_installed(entry, &by_identity, &by_id) {
+            None => plan.install.push(entry.clone()),
+            Some(plugin) if compare_versions(&plugin.plugin.version, &entry.version) == Ordering::Less => {
+                plan.update.push(VersionMismatch {
+                    entry: entry.clone(),
+                    installed_version: plugin.plugin.version.clone(),
+                });
+            }
+            Some(_) => plan.skip.push(entry.clone()),
+        }
+    }
+
+    plan
+}
+
+/// A plugin in a `MergedInventory`, with the machines it's installed on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergedEntry {
+    /// The plugin as found on the first machine that has it
+    pub entry: InventoryEntry,
+    /// Names of the machines that have the plugin, in input order
+    pub machines: Vec<String>,
+    /// Installed version on each of those machines
+    pub versions: BTreeMap<String, String>,
+}
+
+/// Plugins found across several machines, each listed once.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MergedInventory {
+    /// Names of the merged machines, in input order
+    pub machines: Vec<String>,
+    /// Every distinct plugin, in order of first appearance
+    pub plugins: Vec<MergedEntry>,
+}
+
+impl MergedInventory {
+    /// Returns the plugins missing from a machine that other machines have.
+    pub fn missing_on(&self, machine: &str) -> Vec<&MergedEntry> {
+        self.plugins.iter()
+            .filter(|merged| !merged.machines.iter().any(|m| m == machine))
+            .collect()
+    }
+}
+
+/// Combines the scans of several machines, given as (machine name, plugins) pairs,
+/// into one list where each plugin appears once with the machines that have it.
+/// Plugins are matched by `stable_identity()`, so installs at different paths or on
+/// different platforms count as the same plugin.
+pub fn merge_scans(named: &[(String, Vec<InstalledPlugin>)]) -> MergedInventory {
+    let mut merged = MergedInventory::default();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for (machine, plugins) in named {
+        if !merged.machines.contains(machine) {
+            merged.machines.push(machine.clone());
+        }
+
+        for plugin in plugins {
+            let entry = InventoryEntry::from(plugin);
+            let identity = entry.identity.clone().unwrap_or_else(|| entry.id.clone());
+            let position = *index.entry(identity).or_insert_with(|| {
+                merged.plugins.push(MergedEntry {
+                    entry,
+                    machines: Vec::new(),
+                    versions: BTreeMap::new(),
+                });
+                merged.plugins.len() - 1
+            });
+
+            // A plugin installed twice on one machine still lists the machine once
+            let merged_entry = &mut merged.plugins[position];
+            if !merged_entry.machines.contains(machine) {
+                merged_entry.machines.push(machine.clone());
+                merged_entry.versions.insert(machine.clone(), plugin.plugin.version.clone());
+            }
+        }
+    }
+
+    merged
+}
+
+/// Compares two version strings such as "1.10.2" and "1.9". Parts are compared in
+/// order, each by its leading number first ("10a" is newer than "9"), missing
+/// trailing parts count as 0 ("1.2" equals "1.2.0"), and a pre-release suffix
+/// ranks below the bare release ("1.0-beta" and "1.0rc1" are older than "1.0").
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (version_parts(a), version_parts(b));
+    let zero = vec![VersionToken::Number(String::new())];
+
+    for i in 0..a.len().max(b.len()) {
+        let order = compare_version_part(a.get(i).unwrap_or(&zero), b.get(i).unwrap_or(&zero));
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// A run of digits, or of other characters, within a version part
+#[derive(Debug, PartialEq, Eq)]
+enum VersionToken {
+    /// Digits without leading zeros ("" for 0), so numbers of any length compare
+    Number(String),
+    /// Lowercased letters, e.g., "beta"
+    Text(String),
+}
+
+/// Splits a version into its parts ("1.0-rc2" into "1", "0", "rc2") and each part
+/// into tokens ("rc", 2).
+fn version_parts(version: &str) -> Vec<Vec<VersionToken>> {
+    version.split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut tokens: Vec<VersionToken> = Vec::new();
+            for c in part.chars() {
+                match (tokens.last_mut(), c.is_ascii_digit()) {
+                    (Some(VersionToken::Number(digits)), true) => digits.push(c),
+                    (Some(VersionToken::Text(text)), false) => text.extend(c.to_lowercase()),
+                    (_, true) => tokens.push(VersionToken::Number(c.to_string())),
+                    (_, false) => tokens.push(VersionToken::Text(c.to_lowercase().collect())),
+                }
+            }
+            for token in &mut tokens {
+                if let VersionToken::Number(digits) = token {
+                    *digits = digits.trim_start_matches('0').to_string();
+                }
+            }
+            tokens
+        })
+        .collect()
+}
+
+/// Compares two parts of a version. Numbers outrank text, so a release part ("0")
+/// is newer than a pre-release one ("beta"), and text after a number marks a
+/// pre-release of it ("10a" is older than "10").
+fn compare_version_part(a: &[VersionToken], b: &[VersionToken]) -> Ordering {
+    for i in 0..a.len().max(b.len()) {
+        let order = match (a.get(i), b.get(i)) {
+            (Some(VersionToken::Number(left)), Some(VersionToken::Number(right))) => {
+                (left.len(), left).cmp(&(right.len(), right))
+            }
+            (Some(VersionToken::Text(left)), Some(VersionToken::Text(right))) => left.cmp(right),
+            (Some(VersionToken::Number(_)), Some(VersionToken::Text(_))) => Ordering::Greater,
+            (Some(VersionToken::Text(_)), Some(VersionToken::Number(_))) => Ordering::Less,
+            (Some(VersionToken::Number(number)), None) => if number.is_empty() { Ordering::Equal } else { Ordering::Greater },
+            (None, Some(VersionToken::Number(number))) => if number.is_empty() { Ordering::Equal } else { Ordering::Less },
+            (Some(VersionToken::Text(_)), None) => Ordering::Less,
+            (None, Some(VersionToken::Text(_))) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::installed_plugin;
+
+    #[test]
+    fn merges_plugins_by_recorded_uid() {
+        // Paths from other machines don't exist here, so the UID can't be read from disk
+        let mut mac = installed_plugin("Pro-Q 3", Path::new("/Library/Audio/Plug-Ins/VST3/Pro-Q 3.vst3"), PluginFormat::VST3);
+        mac.uid = Some(String::from("72C4DB71-7A4D459A-A97E51A9-4F1DCD8B"));
+        let mut windows = installed_plugin("FabFilter Pro-Q 3", Path::new("C:/Program Files/Common Files/VST3/FabFilter Pro-Q 3.vst3"), PluginFormat::VST3);
+        windows.uid = Some(String::from("72c4db717a4d459aa97e51a94f1dcd8b"));
+
+        assert_eq!(stable_identity(&mac), "vst3:unknown:72c4db717a4d459aa97e51a94f1dcd8b");
+        let merged = merge_scans(&[
+            (String::from("studio"), vec![mac]),
+            (String::from("laptop"), vec![windows]),
+        ]);
+        assert_eq!(merged.plugins.len(), 1);
+        assert_eq!(merged.plugins[0].machines, ["studio", "laptop"]);
+    }
+
+    #[test]
+    fn falls_back_to_id_when_identities_differ() {
+        // The macOS scan read the vendor and class ID; the Windows one couldn't
+        let mut mac = installed_plugin("Pro-Q 3", Path::new("/Library/Audio/Plug-Ins/VST3/Pro-Q 3.vst3"), PluginFormat::VST3);
+        mac.plugin.author = Some(String::from("FabFilter"));
+        mac.uid = Some(String::from("72c4db717a4d459aa97e51a94f1dcd8b"));
+        let mut windows = installed_plugin("Pro-Q 3", Path::new("C:/Program Files/Common Files/VST3/Pro-Q 3.vst3"), PluginFormat::VST3);
+        windows.plugin.version = String::from("0.9.0");
+        assert_ne!(stable_identity(&mac), stable_identity(&windows));
+
+        let inventory = Inventory::from_plugins(&[mac]);
+        let diff = import_inventory(&inventory, &[windows.clone()]);
+        assert!(diff.missing.is_empty());
+        assert_eq!(diff.version_mismatch.len(), 1);
+
+        let plan = migration_plan(&inventory, &[windows]);
+        assert!(plan.install.is_empty());
+        assert_eq!(plan.update[0].installed_version, "0.9.0");
+    }
+
+    #[test]
+    fn compares_versions() {
+        let cases = [
+            ("1.10.2", "1.9", Ordering::Greater),
+            ("1.2", "1.2.0", Ordering::Equal),
+            ("1.2.1", "1.2", Ordering::Greater),
+            ("1.0-beta", "1.0", Ordering::Less),
+            ("1.0rc1", "1.0", Ordering::Less),
+            ("1.0-beta", "1.0-rc1", Ordering::Less),
+            ("2.0-beta2", "2.0-beta10", Ordering::Less),
+            ("10a", "9", Ordering::Greater),
+            ("10a", "10", Ordering::Less),
+            ("1.01", "1.1", Ordering::Equal),
+            ("99999999999999999999999", "1", Ordering::Greater),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(compare_versions(a, b), expected, "{} vs {}", a, b);
+            assert_eq!(compare_versions(b, a), expected.reverse(), "{} vs {}", b, a);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! PluginDepot Core Library
 //!
-//! Cross-platform audio plugin manager core for VST2, VST3, AU, and AAX plugins.
+//! Cross-platform audio plugin manager core for VST2, VST3, AU, AAX, and LV2 plugins.
 //!
 //! # Architecture
 //!
@@ -12,19 +12,113 @@
 //!
 //! ## Plugin Discovery (`registry` module)
 //! - `scan_installed()` - Scan system for installed plugins
+//! - `scan_grouped_by_format()` - Scan once and get plugins per format
+//! - `add_global_scan_dir()` - Register extra scan folders once at startup
+//! - `plugin_directories()` - List the folders being scanned and whether each exists
+//! - `refresh_plugin()` - Re-read a single plugin after it was updated
+//! - `slugify()` - File name- and URL-safe plugin names, as used in plugin IDs
+//! - `legacy_plugin_id()` - Pre-slug plugin IDs, still matched by saved state, scan exclusions, and inventories
 //! - `detect_orphaned_files()` - Find leftover files from uninstalled plugins
+//! - `detect_incomplete_installs()` - Find partial files from interrupted installs
+//! - `last_accessed()` - Best-effort "last used" time from file access times
 //! - Automatic discovery of related paths (presets, libraries, preferences)
+//! - `add_related_path_rule()` - Custom related-path locations, with glob wildcards for versioned folders
+//! - `related_paths_report()` - Dump discovered related paths with categories and confidence for diagnostics
+//!
+//! ## Duplicate Detection (`duplicates` module)
+//! - `find_duplicates()` - Group copies of the same plugin found in several folders
+//! - `companion_formats()` - Find the same plugin installed in other formats
+//! - `find_identical_plugins()` - Group byte-identical copies, even when renamed
+//! - `find_scope_conflicts()` - Plugins installed both system-wide and per-user
+//! - `find_bitness_duplicates()` - Plugins installed as both 32-bit and 64-bit copies
+//!
+//! ## Architecture Detection (`arch` module)
+//! - `detect_architecture()` - Read Mach-O/PE headers to find a plugin's CPU architecture
+//!
+//! ## User State (`state` module)
+//! - `set_display_name()` - Custom label for a plugin, applied on every scan
+//! - `add_tag()` / `remove_tag()` / `list_tags()` - User tags merged into `Plugin::tags` on scan
+//! - `UserState` - Per-user settings stored as JSON in the config directory
+//!
+//! ## Machine Inventory (`inventory` module)
+//! - `export_inventory()` - Portable list of everything installed on this machine
+//! - `import_inventory()` - Report which inventory plugins are present, missing, or version-mismatched
+//! - `migration_plan()` - Plugins to install, update, or skip to bring a machine in line with an inventory
+//! - `merge_scans()` - Studio-wide view of which workstations have each plugin
+//! - `stable_identity()` - Key that matches the same plugin across machines and platforms
+//!
+//! ## Directory Snapshots (`snapshot` module)
+//! - `snapshot_state()` - Lightweight record of the plugin directories (paths and metadata, no contents)
+//! - `diff_against_snapshot()` - What was added, removed, or changed since a snapshot, for a history/undo feature
+//!
+//! ## Bundle Metadata (`metadata` module)
+//! - `extract_bundle_metadata()` - Read version/vendor/category from Info.plist and moduleinfo.json
+//!   (AAX: PE version resource on Windows, Native vs DSP)
+//! - `MetadataCache` - Reuse parsed metadata across scans, keyed by path + mtime
+//! - `read_sidecar()` - Packager-supplied overrides from a `<Plugin>.pdmeta.json` next to the plugin
+//! - `register_metadata_extractor()` - Plug in a `MetadataExtractor` for a format
+//! - LV2: name, author, and version from `manifest.ttl` (parsed by the `turtle` module)
+//! - `extract_vst2_metadata()` - Best-effort name/vendor/version for VST2 DLLs, with a confidence level
+//!
+//! ## VST3 Presets (`vstpreset` module)
+//! - `read_vstpreset()` - Preset name and the class ID of the plugin that saved it
+//! - `preset_matches_plugin()` - Check a preset against an installed plugin's class ID
 //!
 //! ## Plugin Management (`operations` module)
 //! - `backup_plugin()` - Create timestamped backups of plugins and related files
+//! - `backup_binary_only()` - Small backups of just the plugin binary
+//! - `has_space_for()` - Free-space pre-flight check (also run by backup and export)
+//! - `restore_backup()` - Restore a backup with permissions, timestamps, and optionally extended attributes preserved
+//! - `relocate_backup()` - Move a backup when reorganizing backup folders
+//! - `compare_to_backup()` - Verify a backup corresponds to what is installed
 //! - `uninstall_plugin()` - Safe uninstall with dry-run support
 //! - `export_plugin()` - Package plugins for migration to another machine
-//! - `import_plugin()` - Restore plugins from export packages (TODO)
+//! - `export_plugin_to_writer()` - Stream an export as a zip archive (e.g., into an HTTP response)
+//! - `export_metadata()` - Describe a plugin (no files copied) for sharing what is installed
+//! - `import_plugin()` - Install a plugin from an export package on this machine
+//! - `clean_orphaned_files()` - Remove user-confirmed orphaned files
+//! - `prune_empty_dirs()` - Clean up empty vendor and preset folders left after uninstalling
+//! - `normalize_extensions()` - Fix miscased plugin extensions (`Plugin.VST3` -> `Plugin.vst3`)
+//! - `rename_preset_folder()` - Rename a preset category folder within the plugin's preset locations
+//! - `relocate_library()` - Move content folders to another drive, leaving symlinks behind
+//! - `repoint_content()` - Point a plugin's preferences at a manually moved content folder
+//!
+//! ## Audit Log (`audit` module)
+//! - `read_audit_log()` - History of every backup, uninstall, export, and import, with paths and results
+//!
+//! ## Package Manifests (`manifest` module)
+//! - `Manifest` - Versioned description of a backup or export package and its files
+//!
+//! ## Crash-Safe Writes (`atomic` module)
+//! - `write_atomic()` - Temp file + rename, used for manifests and the user state store
+//!
+//! ## macOS Quarantine (`quarantine` module)
+//! - `is_quarantined()` - Detect the `com.apple.quarantine` attribute on a bundle
+//! - `remove_quarantine()` - Clear the attribute so hosts will load the plugin
+//!
+//! ## AAX Activation (`activation` module)
+//! - `aax_activation_status()` - Best-effort PACE/iLok activation check via Pro Tools' plugin cache (macOS)
+//!
+//! ## License Detection (`license` module)
+//! - `detect_license()` - Flag trial/demo/NFR/beta plugins from marker files and metadata, with a confidence level
+//!
+//! ## Code Signing (`signing` module)
+//! - `check_signature()` - Report signed/unsigned, team identifier, and notarization (macOS)
+//!
+//! ## Linked Libraries (`dependencies` module)
+//! - `check_dependencies()` - Find vendor frameworks/dylibs a plugin links against that are missing (macOS)
+//!
+//! ## DAW Detection (`daw` module)
+//! - `detect_installed_daws()` - Which of Ableton, Logic, REAPER, Pro Tools, Cubase, and FL Studio are installed
+//!
+//! ## Health Check (`health` module)
+//! - `health_check()` - One report of every problem with a plugin (architecture, libraries, signature, bundle, quarantine, license, duplicates)
+//! - `audit_all()` - Health reports for the whole system in one call
 //!
 //! ## Data Structures (`plugin` module)
 //! - `Plugin` - Basic plugin information (name, version, description)
 //! - `InstalledPlugin` - Plugin with installation path and related files
-//! - `RelatedPaths` - Discovered preset, library, and preference locations
+//! - `RelatedPaths` - Discovered preset, library, and preference locations, each with a match confidence
 //! - `Vendor` - Manufacturer information
 //! - `License` - License key and activation information
 //! - `PluginMetadata` - Complete plugin info with vendor and licensing
@@ -40,8 +134,30 @@
 
 pub mod plugin;
 pub mod registry;
+pub mod metadata;
+pub mod pe;
+pub mod turtle;
+pub mod vstpreset;
+pub mod arch;
+pub mod duplicates;
 pub mod operations;
+pub mod manifest;
+pub mod atomic;
+pub mod inventory;
+pub mod snapshot;
+pub mod state;
 pub mod ffi;
 pub mod icons;
+pub mod quarantine;
+pub mod signing;
+pub mod dependencies;
+pub mod activation;
+pub mod license;
+pub mod health;
+pub mod daw;
+pub mod audit;
+
+#[cfg(test)]
+mod test_support;
 
-pub use plugin::{Plugin, InstalledPlugin, PluginFormat, RelatedPaths, Vendor, License, PluginMetadata};
\ No newline at end of file
+pub use plugin::{Plugin, InstalledPlugin, PluginFormat, PathTarget, InstallScope, Confidence, Architecture, Architectures, RelatedPaths, RelatedPath, RelatedCategory, Vendor, License, PluginMetadata};
\ No newline at end of file
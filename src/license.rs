@@ -0,0 +1,145 @@
+//! Detection of non-commercial plugin licenses (trial, demo, NFR, beta).
+//!
+//! Vendors don't expose license state in a standard way, so this looks for common
+//! conventions and reports how much to trust the result:
+//! - Marker files such as `Serum.demo`, `trial.lic`, or `NFR.txt` inside the bundle,
+//!   next to the plugin, or in its support folders (Medium)
+//! - "Demo", "Trial", "NFR", or "Beta" in the plugin's name or version (Medium)
+//! - Those words as values in the plugin's preference plists (Low)
+//!
+//! Plugins with no markers get no license entry; that doesn't mean they are licensed.
+
+use crate::{Confidence, InstalledPlugin, License};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Detected license types, keyed by the word that marks them
+const LICENSE_MARKERS: &[(&str, &str)] = &[
+    ("trial", "Trial"),
+    ("demo", "Demo"),
+    ("nfr", "NFR"),
+    ("beta", "Beta"),
+];
+
+/// Extensions of marker files named after a license type ("trial.lic"); other
+/// files with such names ("demo.wav", "beta.dll") are content, not markers
+const MARKER_STEM_EXTENSIONS: &[&str] = &["lic", "txt"];
+
+/// Bundle folders that may hold license marker files
+const BUNDLE_MARKER_DIRS: &[&str] = &["", "Contents", "Contents/Resources"];
+
+/// Detects a trial, demo, NFR, or beta license from marker files and metadata.
+/// Returns None if no marker was found.
+pub fn detect_license(plugin: &InstalledPlugin) -> Option<License> {
+    if let Some((license_type, marker)) = find_marker_file(plugin) {
+        return Some(detected(license_type, Some(marker), Confidence::Medium));
+    }
+
+    let name_or_version = classify(&plugin.plugin.name).or_else(|| classify(&plugin.plugin.version));
+    if let Some(license_type) = name_or_version {
+        return Some(detected(license_type, None, Confidence::Medium));
+    }
+
+    plugin.related_paths.preference_files.iter()
+        .find_map(|related| {
+            classify_plist(&related.path)
+                .map(|license_type| detected(license_type, Some(related.path.clone()), Confidence::Low))
+        })
+}
+
+fn detected(license_type: &str, license_file: Option<PathBuf>, confidence: Confidence) -> License {
+    License {
+        key: None,
+        license_type: Some(license_type.to_string()),
+        email: None,
+        license_file,
+        expiration: None,
+        confidence: Some(confidence),
+    }
+}
+
+/// Returns the license type for text containing a marker word, e.g., "Serum Demo"
+/// or "2.1-beta". Only whole words match ("demolition" doesn't).
+pub fn classify(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text.split(|c: char| !c.is_alphanumeric())
+        .map(|word| word.to_lowercase())
+        .collect();
+
+    LICENSE_MARKERS.iter()
+        .find(|(marker, _)| words.iter().any(|word| word == marker))
+        .map(|(_, license_type)| *license_type)
+}
+
+/// Looks for a marker file named after a license type in the places vendors put them.
+fn find_marker_file(plugin: &InstalledPlugin) -> Option<(&'static str, PathBuf)> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if plugin.install_path.is_dir() {
+        dirs.extend(BUNDLE_MARKER_DIRS.iter().map(|dir| plugin.install_path.join(dir)));
+    }
+    dirs.extend(plugin.related_paths.support_locations.iter().map(|related| related.path.clone()));
+    dirs.extend(plugin.related_paths.library_locations.iter().map(|related| related.path.clone()));
+
+    // Next to the plugin, only markers named after it count (e.g., "Serum.demo");
+    // the plugin folder is shared with other plugins
+    if let (Some(parent), Some(stem)) = (plugin.install_path.parent(), plugin.install_path.file_stem()) {
+        let sibling = LICENSE_MARKERS.iter()
+            .map(|(marker, license_type)| (*license_type, parent.join(format!("{}.{}", stem.to_string_lossy(), marker))))
+            .find(|(_, path)| path.is_file());
+        if sibling.is_some() {
+            return sibling;
+        }
+    }
+
+    dirs.iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .find_map(|path| marker_file_type(&path).map(|license_type| (license_type, path)))
+}
+
+/// A marker file has a marker extension ("x.demo") or a marker stem with a license
+/// file extension or none ("trial.lic", "NFR").
+fn marker_file_type(path: &Path) -> Option<&'static str> {
+    marker_extension(path).or_else(|| {
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+        if extension.is_some_and(|ext| !MARKER_STEM_EXTENSIONS.contains(&ext.as_str())) {
+            return None;
+        }
+        let stem = path.file_stem()?.to_string_lossy().to_lowercase();
+        LICENSE_MARKERS.iter()
+            .find(|(marker, _)| stem == *marker)
+            .map(|(_, license_type)| *license_type)
+    })
+}
+
+fn marker_extension(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    LICENSE_MARKERS.iter()
+        .find(|(marker, _)| ext == *marker)
+        .map(|(_, license_type)| *license_type)
+}
+
+/// Checks the string values of a plist for a license marker word.
+fn classify_plist(path: &Path) -> Option<&'static str> {
+    let value = plist::Value::from_file(path).ok()?;
+    let dict = value.as_dictionary()?;
+
+    dict.values()
+        .filter_map(|v| v.as_string())
+        .find_map(classify)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_license_files_are_marker_files() {
+        assert_eq!(marker_file_type(Path::new("Support/trial.lic")), Some("Trial"));
+        assert_eq!(marker_file_type(Path::new("Support/NFR.txt")), Some("NFR"));
+        assert_eq!(marker_file_type(Path::new("Support/Beta")), Some("Beta"));
+        assert_eq!(marker_file_type(Path::new("Support/Serum.demo")), Some("Demo"));
+        assert_eq!(marker_file_type(Path::new("Samples/demo.wav")), None);
+        assert_eq!(marker_file_type(Path::new("Support/beta.dll")), None);
+    }
+}
@@ -0,0 +1,211 @@
+//! Versioned manifests for backup and export packages.
+//!
+//! Every backup and export folder contains a JSON manifest describing the plugin
+//! and where each packaged file came from. The `manifest_version` field lets the
+//! format evolve: readers reject manifests newer than they understand instead of
+//! misinterpreting them. Manifests written before versioning are read as version 1.
+
+use crate::atomic::write_atomic;
+use crate::{InstalledPlugin, PluginFormat, RelatedCategory};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Manifest format version written by this library
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// File name of the manifest in a backup folder
+pub const BACKUP_MANIFEST_FILE: &str = "backup_manifest.json";
+
+/// File name of the manifest in an export package
+pub const EXPORT_MANIFEST_FILE: &str = "export_manifest.json";
+
+/// File name of the manifest an export in progress keeps up to date, listing the
+/// files copied so far; replaced by `EXPORT_MANIFEST_FILE` once the export completes
+pub const EXPORT_PARTIAL_MANIFEST_FILE: &str = "export_manifest.partial.json";
+
+/// How files are arranged inside an export package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExportLayout {
+    /// The plugin under `Plugin/`, related files under `Presets/`, `Libraries/`,
+    /// `Support/`, and `Preferences/`
+    #[default]
+    Categorized,
+    /// Every file at the top level of the package
+    Flat,
+}
+
+/// A file stored in a backup or export package.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Where the file was on the machine it was packaged from
+    pub original_path: PathBuf,
+    /// Location inside the package, relative to the package folder
+    pub backup_name: PathBuf,
+    /// Category of a related file; None for the plugin itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<RelatedCategory>,
+    /// Where a related file was relative to the standard folder of its category
+    /// (e.g., `Pro-Q 3/User/Init.ffp` under the presets folder), so an import can
+    /// put it in the same place under this machine's folder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_path: Option<PathBuf>,
+}
+
+/// Describes a backup or export package.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Format version; see `MANIFEST_VERSION`
+    #[serde(default = "legacy_manifest_version")]
+    pub manifest_version: u32,
+    pub plugin_name: String,
+    pub plugin_id: String,
+    pub version: String,
+    pub format: PluginFormat,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Where the plugin binary/bundle was installed
+    #[serde(default)]
+    pub install_path: Option<PathBuf>,
+    /// RFC 3339 timestamp of when the package was created
+    #[serde(alias = "backup_date", alias = "export_date")]
+    pub created: String,
+    #[serde(default)]
+    pub files: Vec<ManifestFile>,
+    /// Arrangement of `files` in the package
+    #[serde(default = "legacy_layout")]
+    pub layout: ExportLayout,
+    /// Whether the packaged files carry the originals' extended attributes (macOS)
+    #[serde(default)]
+    pub extended_attributes: bool,
+}
+
+/// Manifests written before versioning was introduced have no version field.
+fn legacy_manifest_version() -> u32 {
+    1
+}
+
+/// Packages written before layouts were introduced are flat.
+fn legacy_layout() -> ExportLayout {
+    ExportLayout::Flat
+}
+
+impl Manifest {
+    /// Creates a manifest for a plugin. `files` maps each original path to its
+    /// copy inside `package_dir`.
+    pub fn new(plugin: &InstalledPlugin, package_dir: &Path, files: &[(PathBuf, PathBuf)]) -> Self {
+        Manifest {
+            manifest_version: MANIFEST_VERSION,
+            plugin_name: plugin.plugin.name.clone(),
+            plugin_id: plugin.plugin.id.clone(),
+            version: plugin.plugin.version.clone(),
+            format: plugin.format,
+            description: plugin.plugin.description.clone(),
+            author: plugin.plugin.author.clone(),
+            install_path: Some(plugin.install_path.clone()),
+            created: chrono::Local::now().to_rfc3339(),
+            files: files.iter()
+                .map(|(original, copy)| {
+                    let related = related_location(plugin, original);
+                    ManifestFile {
+                        original_path: original.clone(),
+                        backup_name: copy.strip_prefix(package_dir).unwrap_or(copy).to_path_buf(),
+                        category: related.as_ref().map(|(category, _)| *category),
+                        relative_path: related.map(|(_, relative)| relative),
+                    }
+                })
+                .collect(),
+            layout: ExportLayout::Flat,
+            extended_attributes: false,
+        }
+    }
+
+    /// Reads a manifest, rejecting versions newer than `MANIFEST_VERSION`.
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .context(format!("Failed to read manifest: {:?}", path))?;
+        let value: serde_json::Value = serde_json::from_str(&content)
+            .context(format!("Failed to parse manifest: {:?}", path))?;
+
+        // Check the version before the layout, which may have changed in newer versions
+        let version = value.get("manifest_version")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(legacy_manifest_version() as u64);
+        if version > MANIFEST_VERSION as u64 {
+            anyhow::bail!(
+                "Manifest {:?} has version {}, but this version of PluginDepot only supports up to {}. Please update PluginDepot.",
+                path, version, MANIFEST_VERSION
+            );
+        }
+
+        serde_json::from_value(value)
+            .context(format!("Invalid manifest: {:?}", path))
+    }
+
+    /// Writes the manifest as pretty-printed JSON, atomically.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(path, content.as_bytes())
+            .context(format!("Failed to write manifest: {:?}", path))?;
+        Ok(())
+    }
+}
+
+/// Finds the related location of `plugin` that `path` is in, and returns its
+/// category with the path relative to the folder holding that location. A vendor
+/// folder around the location (`<Vendor>/<Plugin>`) is kept in the relative path.
+fn related_location(plugin: &InstalledPlugin, path: &Path) -> Option<(RelatedCategory, PathBuf)> {
+    RelatedCategory::ALL.into_iter()
+        .find_map(|category| plugin.related_paths.paths(category).iter()
+            .find(|related| path.starts_with(&related.path))
+            .map(|related| (category, &related.path)))
+        .and_then(|(category, location)| {
+            let mut base = location.parent()?;
+            let in_vendor_folder = plugin.plugin.author.as_deref().is_some_and(|vendor| {
+                base.file_name().is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(vendor))
+            });
+            if in_vendor_folder {
+                base = base.parent()?;
+            }
+            Some((category, path.strip_prefix(base).ok()?.to_path_buf()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_manifest(dir: &Path, version: u32) -> PathBuf {
+        let path = dir.join(EXPORT_MANIFEST_FILE);
+        let manifest = serde_json::json!({
+            "manifest_version": version,
+            "plugin_name": "Synth",
+            "plugin_id": "lv2.synth",
+            "version": "1.0.0",
+            "format": "LV2",
+            "created": "2024-01-01T00:00:00+00:00",
+            "files": [{ "original_path": "/plugins/Synth.lv2", "backup_name": "Synth.lv2" }],
+        });
+        fs::write(&path, manifest.to_string()).unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_version_1_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = Manifest::read(&write_manifest(dir.path(), 1)).unwrap();
+        assert_eq!(manifest.manifest_version, 1);
+        assert_eq!(manifest.files[0].backup_name, Path::new("Synth.lv2"));
+        assert_eq!(manifest.files[0].category, None);
+    }
+
+    #[test]
+    fn rejects_unknown_future_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = Manifest::read(&write_manifest(dir.path(), 999)).unwrap_err();
+        assert!(error.to_string().contains("version 999"), "{}", error);
+    }
+}
@@ -0,0 +1,731 @@
+//! Plugin bundle metadata extraction.
+//!
+//! Reads version, vendor, and category information from plugin bundles:
+//! - `Contents/Info.plist` for AU, VST2, VST3, and AAX bundles on macOS
+//! - `Contents/Resources/moduleinfo.json` for VST3 bundles (all platforms)
+//! - The PE version resource of VST2 DLLs (Windows), see `extract_vst2_metadata()`
+//! - The PE version resource of AAX binaries on Windows, which have no Info.plist
+//! - `manifest.ttl` and the Turtle files it references for LV2 bundles
+//!
+//! Packagers can also ship a `<Plugin>.pdmeta.json` sidecar next to a plugin (see
+//! `read_sidecar()`); its values override everything detected from the plugin itself.
+//!
+//! Each format has a `MetadataExtractor`, looked up by format in a process-wide
+//! registry. Formats can be given a different extractor with
+//! `register_metadata_extractor()` without changing the scanner.
+//!
+//! Parsing is redundant when bundles rarely change, so results are cached in memory
+//! keyed by bundle path and modification time. A bundle is re-parsed only when its
+//! mtime changes.
+
+use crate::pe::read_version_strings;
+use crate::turtle::{Graph, Term, RDF_TYPE};
+use crate::{Confidence, PluginFormat};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::SystemTime;
+
+/// Metadata parsed from a plugin bundle.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BundleMetadata {
+    /// Plugin name, for formats that declare one apart from the file name (LV2)
+    pub name: Option<String>,
+    /// Version string (e.g., "3.21")
+    pub version: Option<String>,
+    /// Vendor/manufacturer name
+    pub vendor: Option<String>,
+    /// Bundle identifier (e.g., "com.fabfilter.Pro-Q.3")
+    pub bundle_id: Option<String>,
+    /// Identifier the plugin declares identically on every platform
+    /// (VST3 class ID, LV2 plugin URI)
+    pub uid: Option<String>,
+    /// Plugin category (e.g., "Effect", "Instrument", "Fx|EQ")
+    pub category: Option<String>,
+    /// Whether an AAX plugin runs on Pro Tools DSP hardware (None for other formats)
+    pub aax_type: Option<AaxType>,
+    /// Names of the plugins a shell bundle declares, when it declares more than one
+    pub members: Vec<String>,
+}
+
+/// Where an AAX plugin can run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AaxType {
+    /// Host CPU only
+    Native,
+    /// Ships DSP code for HDX/HD hardware (usually alongside the native build)
+    Dsp,
+}
+
+impl BundleMetadata {
+    /// Fills fields that are still missing from another metadata source.
+    fn merge_missing(&mut self, other: BundleMetadata) {
+        if self.name.is_none() {
+            self.name = other.name;
+        }
+        if self.version.is_none() {
+            self.version = other.version;
+        }
+        if self.vendor.is_none() {
+            self.vendor = other.vendor;
+        }
+        if self.bundle_id.is_none() {
+            self.bundle_id = other.bundle_id;
+        }
+        if self.uid.is_none() {
+            self.uid = other.uid;
+        }
+        if self.members.is_empty() {
+            self.members = other.members;
+        }
+        if self.category.is_none() {
+            self.category = other.category;
+        }
+        if self.aax_type.is_none() {
+            self.aax_type = other.aax_type;
+        }
+    }
+}
+
+/// Reads metadata from a plugin of one format.
+pub trait MetadataExtractor: Send + Sync {
+    /// Extracts what metadata `path` declares. Fields it can't determine are left None.
+    fn extract(&self, path: &Path) -> Result<BundleMetadata>;
+}
+
+/// Audio Units: `Contents/Info.plist`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AuExtractor;
+
+impl MetadataExtractor for AuExtractor {
+    fn extract(&self, path: &Path) -> Result<BundleMetadata> {
+        info_plist_metadata(path)
+    }
+}
+
+/// VST2: `Contents/Info.plist` of macOS bundles, the version resource of Windows DLLs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Vst2Extractor;
+
+impl MetadataExtractor for Vst2Extractor {
+    fn extract(&self, path: &Path) -> Result<BundleMetadata> {
+        let mut metadata = BundleMetadata::default();
+        if path.is_dir() {
+            return info_plist_metadata(path);
+        }
+
+        // Only trust what the DLL itself declares, not folder-name guesses
+        if is_dll(path) {
+            let vst2 = extract_vst2_metadata(path)?;
+            if vst2.confidence >= Confidence::Medium {
+                metadata.version = vst2.version;
+                metadata.vendor = vst2.vendor;
+            }
+        }
+        Ok(metadata)
+    }
+}
+
+/// VST3: `Contents/Info.plist`, then `Contents/Resources/moduleinfo.json`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Vst3Extractor;
+
+impl MetadataExtractor for Vst3Extractor {
+    fn extract(&self, path: &Path) -> Result<BundleMetadata> {
+        let mut metadata = info_plist_metadata(path)?;
+
+        let module_info = path.join("Contents/Resources/moduleinfo.json");
+        if module_info.exists() {
+            metadata.merge_missing(parse_module_info(&module_info)?);
+        }
+        Ok(metadata)
+    }
+}
+
+/// AAX: `Contents/Info.plist` on macOS, the version resource of the binary on
+/// Windows, plus whether the bundle ships DSP code.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AaxExtractor;
+
+impl MetadataExtractor for AaxExtractor {
+    fn extract(&self, path: &Path) -> Result<BundleMetadata> {
+        if !path.is_dir() {
+            let mut metadata = parse_version_resource(path);
+            metadata.aax_type = Some(AaxType::Native);
+            return Ok(metadata);
+        }
+
+        let mut metadata = info_plist_metadata(path)?;
+        // Windows AAX bundles have no Info.plist; the binary is under Contents/<arch>/
+        if let Some(binary) = aax_windows_binary(path) {
+            metadata.merge_missing(parse_version_resource(&binary));
+        }
+        metadata.aax_type = Some(aax_type(path));
+        Ok(metadata)
+    }
+}
+
+/// LV2: `manifest.ttl` and the Turtle files it references with `rdfs:seeAlso`.
+/// Bundles holding several plugins are described by their first plugin.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lv2Extractor;
+
+const LV2_CORE: &str = "http://lv2plug.in/ns/lv2core#";
+const DOAP: &str = "http://usefulinc.com/ns/doap#";
+const FOAF_NAME: &str = "http://xmlns.com/foaf/0.1/name";
+const RDFS_SEE_ALSO: &str = "http://www.w3.org/2000/01/rdf-schema#seeAlso";
+
+impl MetadataExtractor for Lv2Extractor {
+    fn extract(&self, path: &Path) -> Result<BundleMetadata> {
+        let manifest = path.join("manifest.ttl");
+        if !path.is_dir() || !manifest.exists() {
+            return Ok(BundleMetadata::default());
+        }
+
+        let mut graph = Graph::default();
+        graph.add_file(&manifest)?;
+
+        // Plugin details usually live in a separate file next to the manifest
+        let mut see_also: Vec<PathBuf> = Vec::new();
+        for triple in graph.triples.iter().filter(|t| t.predicate == RDFS_SEE_ALSO) {
+            if let Some(file) = triple.object.as_iri().and_then(|iri| bundle_file(path, iri)) {
+                if !see_also.contains(&file) {
+                    see_also.push(file);
+                }
+            }
+        }
+        for file in see_also {
+            if let Err(e) = graph.add_file(&file) {
+                eprintln!("Warning: {:#}", e);
+            }
+        }
+
+        let plugin_class = format!("{}Plugin", LV2_CORE);
+        let plugins = graph.subjects_of_type(&plugin_class);
+        let plugin = match plugins.first() {
+            Some(plugin) => (*plugin).clone(),
+            None => return Ok(BundleMetadata::default()),
+        };
+        let literal = |subject: &Term, predicate: &str| {
+            graph.object(subject, predicate)
+                .and_then(Term::as_literal)
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        // One bundle can hold a whole plugin suite
+        let members = if plugins.len() > 1 {
+            plugins.iter()
+                .filter_map(|member| literal(member, &format!("{}name", DOAP))
+                    .or_else(|| member.as_iri().and_then(|iri| iri.rsplit(['/', '#']).next()).map(str::to_string)))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Authors are on the plugin, or on the project it belongs to
+        let project = graph.object(&plugin, &format!("{}project", LV2_CORE)).cloned();
+        let vendor = [Some(&plugin), project.as_ref()].into_iter().flatten()
+            .flat_map(|subject| ["maintainer", "developer"].map(|role| (subject, format!("{}{}", DOAP, role))))
+            .flat_map(|(subject, predicate)| graph.objects(subject, &predicate).cloned().collect::<Vec<_>>())
+            .find_map(|person| match &person {
+                Term::Literal(name) => Some(name.trim().to_string()),
+                _ => literal(&person, FOAF_NAME),
+            });
+
+        // The major version is part of the plugin URI; hosts show "minor.micro"
+        let version = match (literal(&plugin, &format!("{}minorVersion", LV2_CORE)), literal(&plugin, &format!("{}microVersion", LV2_CORE))) {
+            (Some(minor), Some(micro)) => Some(format!("{}.{}", minor, micro)),
+            _ => None,
+        };
+
+        // Plugin classes such as lv2:CompressorPlugin give the category ("Compressor")
+        let category = graph.objects(&plugin, RDF_TYPE)
+            .filter_map(Term::as_iri)
+            .filter_map(|class| class.strip_prefix(LV2_CORE))
+            .filter_map(|class| class.strip_suffix("Plugin"))
+            .find(|class| !class.is_empty())
+            .map(str::to_string);
+
+        Ok(BundleMetadata {
+            name: literal(&plugin, &format!("{}name", DOAP)),
+            version,
+            vendor,
+            bundle_id: plugin.as_iri().map(str::to_string),
+            uid: plugin.as_iri().map(str::to_string),
+            category,
+            aax_type: None,
+            members,
+        })
+    }
+}
+
+/// Resolves an IRI from an LV2 manifest to a file inside the bundle. IRIs
+/// pointing elsewhere (other bundles, the web) are ignored.
+fn bundle_file(bundle: &Path, iri: &str) -> Option<PathBuf> {
+    if iri.contains("://") || iri.starts_with('/') || iri.split('/').any(|part| part == "..") {
+        return None;
+    }
+    Some(bundle.join(iri))
+}
+
+/// Reads `Contents/Info.plist` of a bundle; empty for files and bundles without one.
+fn info_plist_metadata(path: &Path) -> Result<BundleMetadata> {
+    let info_plist = path.join("Contents/Info.plist");
+    if path.is_dir() && info_plist.exists() {
+        parse_info_plist(&info_plist)
+    } else {
+        Ok(BundleMetadata::default())
+    }
+}
+
+type ExtractorMap = HashMap<PluginFormat, Arc<dyn MetadataExtractor>>;
+
+/// Extractors registered at runtime; None uses `default_extractors()`
+static METADATA_EXTRACTORS: RwLock<Option<ExtractorMap>> = RwLock::new(None);
+
+fn default_extractors() -> ExtractorMap {
+    let mut extractors: ExtractorMap = HashMap::new();
+    extractors.insert(PluginFormat::VST2, Arc::new(Vst2Extractor));
+    extractors.insert(PluginFormat::VST3, Arc::new(Vst3Extractor));
+    extractors.insert(PluginFormat::AU, Arc::new(AuExtractor));
+    extractors.insert(PluginFormat::AAX, Arc::new(AaxExtractor));
+    extractors.insert(PluginFormat::LV2, Arc::new(Lv2Extractor));
+    extractors
+}
+
+/// Makes scans read metadata of `format` plugins with `extractor`, replacing the
+/// previous one. Cached metadata is dropped so the next scan uses the new extractor.
+pub fn register_metadata_extractor(format: PluginFormat, extractor: Arc<dyn MetadataExtractor>) {
+    METADATA_EXTRACTORS.write().unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(default_extractors)
+        .insert(format, extractor);
+    metadata_cache().clear();
+}
+
+/// Restores the built-in extractors.
+pub fn reset_metadata_extractors() {
+    *METADATA_EXTRACTORS.write().unwrap_or_else(|e| e.into_inner()) = None;
+    metadata_cache().clear();
+}
+
+/// Returns the extractor used for `format`, if any.
+pub fn metadata_extractor(format: &PluginFormat) -> Option<Arc<dyn MetadataExtractor>> {
+    let extractors = METADATA_EXTRACTORS.read().unwrap_or_else(|e| e.into_inner());
+    match extractors.as_ref() {
+        Some(extractors) => extractors.get(format).cloned(),
+        None => default_extractors().remove(format),
+    }
+}
+
+/// Extracts metadata from a plugin with the extractor registered for its format.
+/// Returns empty metadata for plugins without metadata files or formats without an extractor.
+pub fn extract_bundle_metadata(path: &Path, format: &PluginFormat) -> Result<BundleMetadata> {
+    match metadata_extractor(format) {
+        Some(extractor) => extractor.extract(path),
+        None => Ok(BundleMetadata::default()),
+    }
+}
+
+/// Per-architecture binary folders of a Windows AAX bundle, preferred first
+const AAX_WINDOWS_ARCH_DIRS: &[&str] = &["x64", "Win32"];
+
+/// Locates the DLL inside a Windows AAX bundle (`Contents/x64/<Name>.aaxplugin`).
+fn aax_windows_binary(bundle: &Path) -> Option<PathBuf> {
+    AAX_WINDOWS_ARCH_DIRS.iter()
+        .filter_map(|dir| fs::read_dir(bundle.join("Contents").join(dir)).ok())
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+        .find(|p| p.is_file())
+}
+
+/// DSP-capable AAX bundles ship TI DSP images (`.dxe` files) in their resources.
+fn aax_type(bundle: &Path) -> AaxType {
+    let has_dsp_code = fs::read_dir(bundle.join("Contents/Resources"))
+        .map(|entries| entries.flatten().any(|entry| {
+            entry.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("dxe"))
+        }))
+        .unwrap_or(false);
+
+    if has_dsp_code { AaxType::Dsp } else { AaxType::Native }
+}
+
+/// Reads version and vendor from a PE binary's version resource.
+/// Returns empty metadata if the file has none or isn't a PE file.
+fn parse_version_resource(path: &Path) -> BundleMetadata {
+    let strings = read_version_strings(path).unwrap_or_else(|e| {
+        eprintln!("Warning: Failed to read version resource of {:?}: {}", path, e);
+        HashMap::new()
+    });
+    let field = |keys: &[&str]| keys.iter().find_map(|k| strings.get(*k).cloned());
+
+    BundleMetadata {
+        version: field(&["ProductVersion", "FileVersion"]),
+        vendor: field(&["CompanyName"]),
+        ..Default::default()
+    }
+}
+
+/// Metadata guessed for a VST2 DLL.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vst2Metadata {
+    pub name: Option<String>,
+    pub vendor: Option<String>,
+    pub version: Option<String>,
+    /// High if name and vendor came from the version resource, Medium if only
+    /// some fields did, Low if everything was guessed from file and folder names
+    pub confidence: Confidence,
+}
+
+/// Folder names that hold plugins from many vendors and so say nothing about the vendor
+const GENERIC_PLUGIN_FOLDERS: &[&str] = &[
+    "vstplugins", "vst", "vst2", "vst plugins", "plugins", "plug-ins",
+    "x64", "x86", "64-bit", "32-bit", "steinberg", "common files",
+];
+
+/// Best-effort name and vendor for a VST2 DLL, which has no standard metadata file.
+/// Reads the PE version resource (ProductName, CompanyName, FileVersion) without
+/// loading the DLL, then falls back to the file name and a vendor-named parent folder.
+pub fn extract_vst2_metadata(path: &Path) -> Result<Vst2Metadata> {
+    let strings = if is_dll(path) {
+        read_version_strings(path).unwrap_or_else(|e| {
+            eprintln!("Warning: Failed to read version resource of {:?}: {}", path, e);
+            HashMap::new()
+        })
+    } else {
+        HashMap::new()
+    };
+    let field = |keys: &[&str]| keys.iter().find_map(|k| strings.get(*k).cloned());
+
+    let name = field(&["ProductName", "FileDescription", "InternalName"]);
+    let vendor = field(&["CompanyName"]);
+    let version = field(&["ProductVersion", "FileVersion"]);
+
+    let confidence = match (&name, &vendor, &version) {
+        (Some(_), Some(_), _) => Confidence::High,
+        (None, None, None) => Confidence::Low,
+        _ => Confidence::Medium,
+    };
+
+    // Plugins are often installed as VSTPlugins/<Vendor>/<Plugin>.dll
+    let folder_vendor = path.parent()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .filter(|n| !GENERIC_PLUGIN_FOLDERS.contains(&n.to_lowercase().as_str()));
+
+    Ok(Vst2Metadata {
+        name: name.or_else(|| path.file_stem().map(|s| s.to_string_lossy().to_string())),
+        vendor: vendor.or(folder_vendor),
+        version,
+        confidence,
+    })
+}
+
+/// Returns true if the path has a `.dll` extension.
+fn is_dll(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("dll"))
+}
+
+/// Parses an Info.plist (XML or binary) for version, vendor, and category.
+fn parse_info_plist(path: &Path) -> Result<BundleMetadata> {
+    let value = plist::Value::from_file(path)
+        .context(format!("Failed to parse plist: {:?}", path))?;
+    let dict = value.as_dictionary()
+        .context(format!("Info.plist is not a dictionary: {:?}", path))?;
+
+    let string_value = |key: &str| {
+        dict.get(key)
+            .and_then(|v| v.as_string())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    let mut metadata = BundleMetadata {
+        version: string_value("CFBundleShortVersionString").or_else(|| string_value("CFBundleVersion")),
+        bundle_id: string_value("CFBundleIdentifier"),
+        ..Default::default()
+    };
+
+    // Audio Units declare "Vendor: Plugin Name" and a four-char type code
+    let components = dict.get("AudioComponents")
+        .and_then(|v| v.as_array())
+        .map(|components| components.as_slice())
+        .unwrap_or_default();
+    let component = components.first().and_then(|v| v.as_dictionary());
+
+    if components.len() > 1 {
+        metadata.members = components.iter()
+            .filter_map(|component| component.as_dictionary()?.get("name")?.as_string())
+            .map(|name| name.split_once(':').map_or(name, |(_, name)| name).trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+    }
+
+    if let Some(component) = component {
+        if let Some((vendor, _)) = component.get("name").and_then(|v| v.as_string()).and_then(|n| n.split_once(':')) {
+            let vendor = vendor.trim();
+            if !vendor.is_empty() {
+                metadata.vendor = Some(vendor.to_string());
+            }
+        }
+
+        metadata.category = component.get("type")
+            .and_then(|v| v.as_string())
+            .and_then(au_type_category)
+            .map(String::from);
+    }
+
+    Ok(metadata)
+}
+
+/// Maps an Audio Unit four-char type code to a category name.
+fn au_type_category(code: &str) -> Option<&'static str> {
+    match code {
+        "aufx" | "aumf" => Some("Effect"),
+        "aumu" => Some("Instrument"),
+        "aumi" => Some("MIDI"),
+        "augn" => Some("Generator"),
+        _ => None,
+    }
+}
+
+/// Parses a VST3 moduleinfo.json for version, vendor, and sub-categories.
+fn parse_module_info(path: &Path) -> Result<BundleMetadata> {
+    let content = fs::read_to_string(path)
+        .context(format!("Failed to read moduleinfo: {:?}", path))?;
+
+    // moduleinfo.json is JSON5 and commonly has trailing commas
+    let value: serde_json::Value = serde_json::from_str(&content)
+        .or_else(|_| serde_json::from_str(&strip_trailing_commas(&content)))
+        .context(format!("Failed to parse moduleinfo: {:?}", path))?;
+
+    let string_at = |pointer: &str| {
+        value.pointer(pointer)
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    // Shells list one audio class per plugin they provide
+    let audio_classes: Vec<String> = value.pointer("/Classes")
+        .and_then(|v| v.as_array())
+        .map(|classes| classes.iter()
+            .filter(|class| class.get("Category").and_then(|v| v.as_str()) == Some("Audio Module Class"))
+            .filter_map(|class| class.get("Name").and_then(|v| v.as_str()))
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect())
+        .unwrap_or_default();
+
+    Ok(BundleMetadata {
+        version: string_at("/Version"),
+        vendor: string_at("/Factory Info/Vendor"),
+        name: None,
+        bundle_id: None,
+        uid: string_at("/Classes/0/CID"),
+        aax_type: None,
+        members: if audio_classes.len() > 1 { audio_classes } else { Vec::new() },
+        // Sub Categories is a list like ["Fx", "EQ"]; keep the VST3 "Fx|EQ" notation
+        category: value.pointer("/Classes/0/Sub Categories")
+            .and_then(|v| v.as_array())
+            .map(|cats| cats.iter().filter_map(|c| c.as_str()).collect::<Vec<_>>().join("|"))
+            .filter(|s| !s.is_empty()),
+    })
+}
+
+/// Removes commas that directly precede a closing brace or bracket.
+fn strip_trailing_commas(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut pending_comma = false;
+
+    for c in content.chars() {
+        if pending_comma {
+            if c.is_whitespace() {
+                continue;
+            }
+            if c != '}' && c != ']' {
+                result.push(',');
+            }
+            pending_comma = false;
+        }
+
+        if c == ',' {
+            pending_comma = true;
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+/// Extension of metadata sidecar files (`<Plugin>.pdmeta.json`)
+pub const SIDECAR_EXTENSION: &str = "pdmeta.json";
+
+/// Curated metadata shipped next to a plugin. Every field is optional; those
+/// present override the values detected from the plugin.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SidecarMetadata {
+    pub name: Option<String>,
+    pub vendor: Option<String>,
+    pub description: Option<String>,
+    /// Replaces the tags inferred from the plugin's category
+    pub tags: Option<Vec<String>>,
+    /// Plugins provided by a shell plugin whose binary doesn't list them (e.g., VST2 shells)
+    pub members: Option<Vec<String>>,
+}
+
+/// Path of the sidecar for a plugin: `Pro-Q 3.vst3` → `Pro-Q 3.pdmeta.json` in the same folder.
+pub fn sidecar_path(plugin_path: &Path) -> Option<PathBuf> {
+    let stem = plugin_path.file_stem()?;
+    Some(plugin_path.with_file_name(format!("{}.{}", stem.to_string_lossy(), SIDECAR_EXTENSION)))
+}
+
+/// Reads the sidecar next to a plugin, if there is one.
+/// An invalid sidecar is reported as a warning and ignored.
+pub fn read_sidecar(plugin_path: &Path) -> Option<SidecarMetadata> {
+    let path = sidecar_path(plugin_path)?;
+    let content = fs::read_to_string(&path).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| eprintln!("Warning: Ignoring invalid metadata sidecar {:?}: {}", path, e))
+        .ok()
+}
+
+/// In-memory cache of extracted metadata keyed by bundle path and modification time.
+#[derive(Debug, Default)]
+pub struct MetadataCache {
+    entries: Mutex<HashMap<PathBuf, (SystemTime, BundleMetadata)>>,
+}
+
+impl MetadataCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns cached metadata for `path` if its mtime is unchanged,
+    /// otherwise runs `extract` and caches the result.
+    pub fn get_or_extract<F>(&self, path: &Path, format: &PluginFormat, extract: F) -> Result<BundleMetadata>
+    where
+        F: FnOnce(&Path, &PluginFormat) -> Result<BundleMetadata>,
+    {
+        let mtime = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .context(format!("Failed to read modification time: {:?}", path))?;
+
+        if let Ok(entries) = self.entries.lock() {
+            if let Some((cached_mtime, metadata)) = entries.get(path) {
+                if *cached_mtime == mtime {
+                    return Ok(metadata.clone());
+                }
+            }
+        }
+
+        let metadata = extract(path, format)?;
+
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(path.to_path_buf(), (mtime, metadata.clone()));
+        }
+
+        Ok(metadata)
+    }
+
+    /// Number of cached bundles.
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|e| e.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes the cached entry for `path`, so the next lookup re-reads the bundle.
+    /// Needed when files inside a bundle change, which doesn't touch the bundle's own mtime.
+    pub fn invalidate(&self, path: &Path) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.remove(path);
+        }
+    }
+
+    /// Removes all cached entries.
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+/// Returns the process-wide metadata cache used by scans.
+pub fn metadata_cache() -> &'static MetadataCache {
+    static CACHE: OnceLock<MetadataCache> = OnceLock::new();
+    CACHE.get_or_init(MetadataCache::new)
+}
+
+/// Extracts bundle metadata through the process-wide cache.
+pub fn cached_bundle_metadata(path: &Path, format: &PluginFormat) -> Result<BundleMetadata> {
+    metadata_cache().get_or_extract(path, format, extract_bundle_metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn second_lookup_hits_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Synth.vst3");
+        fs::create_dir_all(&bundle).unwrap();
+        let cache = MetadataCache::new();
+        let parsed = AtomicUsize::new(0);
+        let lookup = || cache.get_or_extract(&bundle, &PluginFormat::VST3, |_, _| {
+            parsed.fetch_add(1, Ordering::SeqCst);
+            Ok(BundleMetadata { version: Some(String::from("1.0")), ..BundleMetadata::default() })
+        });
+
+        assert_eq!(lookup().unwrap().version.as_deref(), Some("1.0"));
+        lookup().unwrap();
+        assert_eq!(parsed.load(Ordering::SeqCst), 1);
+
+        // An updated bundle gets a new modification time and is read again
+        filetime::set_file_mtime(&bundle, filetime::FileTime::from_unix_time(1_600_000_000, 0)).unwrap();
+        lookup().unwrap();
+        assert_eq!(parsed.load(Ordering::SeqCst), 2);
+    }
+
+    struct MockExtractor(AtomicUsize);
+
+    impl MetadataExtractor for MockExtractor {
+        fn extract(&self, _path: &Path) -> Result<BundleMetadata> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(BundleMetadata { name: Some(String::from("Mock Synth")), ..BundleMetadata::default() })
+        }
+    }
+
+    #[test]
+    fn scans_use_registered_extractors() {
+        crate::test_support::isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        // Audio Units are bundles only on macOS, where they exist
+        if cfg!(target_os = "macos") {
+            fs::create_dir_all(dir.path().join("Synth.component")).unwrap();
+        } else {
+            fs::write(dir.path().join("Synth.component"), "").unwrap();
+        }
+        let extractor = Arc::new(MockExtractor(AtomicUsize::new(0)));
+        register_metadata_extractor(PluginFormat::AU, extractor.clone());
+
+        let config = crate::registry::ScanConfig {
+            include_default_dirs: false,
+            extra_dirs: vec![(dir.path().to_path_buf(), PluginFormat::AU)],
+            ..Default::default()
+        };
+        let scanned = crate::registry::scan(&config, None);
+        reset_metadata_extractors();
+
+        assert_eq!(scanned.unwrap()[0].plugin.name, "Mock Synth");
+        assert!(extractor.0.load(Ordering::SeqCst) > 0);
+    }
+}
@@ -2,18 +2,184 @@
 //!
 //! This module provides functions for managing installed plugins:
 //! - Backup: Create archives of plugins and their related files
+//! - Restore: Put a backup back in place with permissions and timestamps intact
 //! - Uninstall: Safely remove plugins and cleanup related files
 //! - Export: Package plugins for migration to another machine
 //! - Import: Restore plugins from migration packages
+//!
+//! Backups, uninstalls, exports, and imports are recorded in the audit log
+//! (see the `audit` module).
 
-use crate::{InstalledPlugin, registry::enumerate_plugin_files};
+use crate::{Confidence, InstalledPlugin, License, PluginMetadata, RelatedCategory, Vendor, registry::{enumerate_plugin_files, is_skipped_file, path_size, scan_installed, disabled_dir_for, DISABLED_DIR_SUFFIX, DISABLED_FILE_EXTENSION}};
+use crate::atomic::write_atomic;
+use crate::audit::{self, AuditAction};
+use crate::manifest::{ExportLayout, Manifest, ManifestFile, BACKUP_MANIFEST_FILE, EXPORT_MANIFEST_FILE, EXPORT_PARTIAL_MANIFEST_FILE};
+use crate::registry::{default_install_dir, detect_miscased_extensions, is_plugin_bundle, normalize_path_for_comparison, recognize_plugin, related_install_dir, resolve_symlink, plugin_directories};
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::io::{Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Progress of a file-by-file operation, reported after each file is processed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpProgress {
+    /// File or folder just processed
+    pub current_file: PathBuf,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+/// Tracks counters for `OpProgress` across a list of files.
+struct ProgressTracker {
+    sizes: Vec<u64>,
+    files_done: usize,
+    bytes_done: u64,
+    bytes_total: u64,
+}
+
+impl ProgressTracker {
+    fn new(files: &[PathBuf]) -> Self {
+        let sizes: Vec<u64> = files.iter().map(|f| path_size(f)).collect();
+        let bytes_total = sizes.iter().sum();
+        ProgressTracker { sizes, files_done: 0, bytes_done: 0, bytes_total }
+    }
+    
+    /// Records that the file at `index` was processed and returns the new progress.
+    fn advance(&mut self, index: usize, file: &Path) -> OpProgress {
+        self.files_done += 1;
+        self.bytes_done += self.sizes[index];
+        OpProgress {
+            current_file: file.to_path_buf(),
+            files_done: self.files_done,
+            files_total: self.sizes.len(),
+            bytes_done: self.bytes_done,
+            bytes_total: self.bytes_total,
+        }
+    }
+}
+
+/// Settings for backups.
+#[derive(Debug, Clone, Default)]
+pub struct BackupOptions {
+    /// Also copy extended attributes (quarantine flag, Finder tags, resource forks),
+    /// which plain copies drop, so `restore_backup()` can put them back.
+    /// Only supported on macOS; ignored elsewhere.
+    pub preserve_xattrs: bool,
+    /// Copy only the plugin binary or bundle, leaving out presets, libraries,
+    /// support files, and preferences. See `backup_binary_only()`.
+    pub binary_only: bool,
+}
+
+/// Why `validate_backup_destination()` rejected a folder. Returned inside the
+/// `anyhow::Error` of backup functions; check with `error.downcast_ref()`.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum BackupDestinationError {
+    #[error("Backup destination must be an absolute path: {0:?}")]
+    NotAbsolute(PathBuf),
+    /// Only the last folder of the destination is created, so a typo doesn't
+    /// silently create a whole directory tree
+    #[error("Parent folder of the backup destination doesn't exist: {0:?}")]
+    MissingParent(PathBuf),
+    #[error("Backup destination {destination:?} is inside the plugin folder {plugin_dir:?}")]
+    InsidePluginFolder { destination: PathBuf, plugin_dir: PathBuf },
+    #[error("Backup destination is not writable: {0:?}")]
+    NotWritable(PathBuf),
+}
+
+/// Checks a user-provided backup folder and returns its canonical form (symlinks
+/// and `..` resolved). The folder must be absolute, writable, outside every
+/// scanned plugin folder (hosts would find the copies as duplicate plugins), and
+/// either exist or have an existing parent.
+pub fn validate_backup_destination(dir: &Path) -> Result<PathBuf> {
+    if !dir.is_absolute() {
+        return Err(BackupDestinationError::NotAbsolute(dir.to_path_buf()).into());
+    }
+    
+    let (existing, missing) = match fs::canonicalize(dir) {
+        Ok(canonical) => (canonical, None),
+        Err(_) => {
+            let parent = dir.parent().and_then(|parent| fs::canonicalize(parent).ok())
+                .ok_or_else(|| BackupDestinationError::MissingParent(dir.to_path_buf()))?;
+            (parent, dir.file_name())
+        }
+    };
+    let destination = match missing {
+        Some(name) => existing.join(name),
+        None => existing.clone(),
+    };
+    
+    let destination_key = normalize_path_for_comparison(&destination);
+    for (plugin_dir, _, _) in plugin_directories()? {
+        let canonical = fs::canonicalize(&plugin_dir).unwrap_or(plugin_dir);
+        if destination_key.starts_with(normalize_path_for_comparison(&canonical)) {
+            return Err(BackupDestinationError::InsidePluginFolder { destination, plugin_dir: canonical }.into());
+        }
+    }
+    
+    if !existing.is_dir() || !can_modify_dir(&existing) {
+        return Err(BackupDestinationError::NotWritable(destination).into());
+    }
+    
+    Ok(destination)
+}
 
 /// Backup a plugin and all its related files to a specified directory.
-/// Creates a timestamped folder containing all plugin files.
+/// Creates a timestamped folder containing all plugin files. `backup_dir` is
+/// checked with `validate_backup_destination()` first.
 pub fn backup_plugin(plugin: &InstalledPlugin, backup_dir: &Path) -> Result<PathBuf> {
+    backup_plugin_with_progress(plugin, backup_dir, |_| {})
+}
+
+/// Like `backup_plugin()`, calling `progress` after each file is copied.
+pub fn backup_plugin_with_progress<F>(plugin: &InstalledPlugin, backup_dir: &Path, progress: F) -> Result<PathBuf>
+where
+    F: FnMut(&OpProgress),
+{
+    backup_plugin_with_options(plugin, backup_dir, &BackupOptions::default(), progress)
+}
+
+/// Backs up only the plugin binary or bundle with a manifest, skipping its related
+/// files. A quick, small snapshot to take before updating a plugin whose presets
+/// and libraries won't change.
+pub fn backup_binary_only(plugin: &InstalledPlugin, backup_dir: &Path) -> Result<PathBuf> {
+    let options = BackupOptions { binary_only: true, ..BackupOptions::default() };
+    backup_plugin_with_options(plugin, backup_dir, &options, |_| {})
+}
+
+/// Like `backup_plugin_with_progress()`, with non-default `options`.
+pub fn backup_plugin_with_options<F>(plugin: &InstalledPlugin, backup_dir: &Path, options: &BackupOptions, progress: F) -> Result<PathBuf>
+where
+    F: FnMut(&OpProgress),
+{
+    let result = copy_to_backup(plugin, backup_dir, options, progress);
+    audit::record(AuditAction::Backup, &plugin.plugin.id, false, result.as_ref().map(|path| vec![path.clone()]));
+    result
+}
+
+fn copy_to_backup<F>(plugin: &InstalledPlugin, backup_dir: &Path, options: &BackupOptions, mut progress: F) -> Result<PathBuf>
+where
+    F: FnMut(&OpProgress),
+{
+    let backup_dir = &validate_backup_destination(backup_dir)?;
+    
+    // Enumerate all files to backup
+    let files = if options.binary_only {
+        vec![plugin.install_path.clone()]
+    } else {
+        enumerate_plugin_files(plugin)?
+    };
+    let mut tracker = ProgressTracker::new(&files);
+    
+    // Refuse up front rather than leave a partial backup when the disk fills up
+    ensure_space_for(backup_dir, tracker.bytes_total)?;
+    
     // Create backup directory with timestamp
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
     let plugin_folder_name = format!("{}_{}", plugin.plugin.name.replace(" ", "_"), timestamp);
@@ -22,26 +188,402 @@ pub fn backup_plugin(plugin: &InstalledPlugin, backup_dir: &Path) -> Result<Path
     fs::create_dir_all(&backup_path)
         .context(format!("Failed to create backup directory: {:?}", backup_path))?;
     
-    // Enumerate all files to backup
-    let files = enumerate_plugin_files(plugin)?;
-    
     // Copy each file, preserving relative structure
-    for file in &files {
-        if let Err(e) = copy_file_to_backup(file, &backup_path) {
-            eprintln!("Warning: Failed to backup file {:?}: {}", file, e);
+    let preserve_xattrs = options.preserve_xattrs && cfg!(target_os = "macos");
+    let mut backed_up = Vec::new();
+    let mut used_names = HashSet::new();
+    for (index, file) in files.iter().enumerate() {
+        match copy_file_to_backup(file, &backup_path, &mut used_names) {
+            Ok(Some(dest)) => {
+                if preserve_xattrs {
+                    if let Err(e) = copy_extended_attributes(file, &dest) {
+                        eprintln!("Warning: Failed to copy extended attributes of {:?}: {}", file, e);
+                    }
+                }
+                backed_up.push((file.clone(), dest));
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Warning: Failed to backup file {:?}: {}", file, e),
         }
+        progress(&tracker.advance(index, file));
     }
     
     // Create a manifest file with plugin metadata
-    create_backup_manifest(plugin, &backup_path)?;
+    let mut manifest = Manifest::new(plugin, &backup_path, &backed_up);
+    manifest.extended_attributes = preserve_xattrs;
+    manifest.write(&backup_path.join(BACKUP_MANIFEST_FILE))?;
     
     Ok(backup_path)
 }
 
+/// Returns true if the volume holding `dest` has at least `bytes` of free space.
+/// `dest` doesn't need to exist yet; its nearest existing parent is checked.
+pub fn has_space_for(dest: &Path, bytes: u64) -> Result<bool> {
+    Ok(available_space(dest)? >= bytes)
+}
+
+/// Fails with a clear error if `dest` doesn't have room for `bytes`.
+fn ensure_space_for(dest: &Path, bytes: u64) -> Result<()> {
+    let available = available_space(dest)?;
+    if available < bytes {
+        anyhow::bail!(
+            "Not enough free space at {:?}: {} bytes needed, {} bytes available",
+            dest, bytes, available
+        );
+    }
+    Ok(())
+}
+
+/// Free space available to the current user on the volume holding `path`.
+fn available_space(path: &Path) -> Result<u64> {
+    // A relative path with no existing component lives in the current directory
+    let existing = path.ancestors()
+        .find(|p| p.exists())
+        .or_else(|| path.is_relative().then(|| Path::new(".")))
+        .context(format!("No existing parent directory for {:?}", path))?;
+    
+    fs2::available_space(existing)
+        .context(format!("Failed to query free space for {:?}", existing))
+}
+
+/// Restore a backup created by backup_plugin() to the original locations.
+/// Existing files at those locations are replaced. Permissions and modification
+/// times are restored from the backed-up copies, and extended attributes too if
+/// the backup preserved them (see `BackupOptions`).
+/// Returns the list of paths that were restored.
+pub fn restore_backup(backup_path: &Path) -> Result<Vec<PathBuf>> {
+    let manifest = Manifest::read(&backup_path.join(BACKUP_MANIFEST_FILE))?;
+    
+    if manifest.files.is_empty() {
+        anyhow::bail!("Backup manifest does not list any files: {:?}", backup_path);
+    }
+    
+    let mut restored = Vec::new();
+    for entry in &manifest.files {
+        let restored_from = package_entry_path(backup_path, &entry.backup_name)
+            .and_then(|source| restore_path(&source, &entry.original_path).map(|_| source));
+        let source = match restored_from {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Warning: Failed to restore {:?}: {}", entry.original_path, e);
+                continue;
+            }
+        };
+        if manifest.extended_attributes {
+            if let Err(e) = copy_extended_attributes(&source, &entry.original_path) {
+                eprintln!("Warning: Failed to restore extended attributes of {:?}: {}", entry.original_path, e);
+            }
+        }
+        restored.push(entry.original_path.clone());
+    }
+    
+    Ok(restored)
+}
+
+/// Copy one backed-up file or directory over its original location.
+/// The copy is made under a temporary name next to `dest` and renamed into place
+/// once complete, so a failed copy leaves what was installed untouched.
+fn restore_path(source: &Path, dest: &Path) -> Result<()> {
+    if !source.exists() {
+        anyhow::bail!("Missing from backup: {:?}", source);
+    }
+    
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let partial = sibling_path(dest, "partial")?;
+    delete_path(&partial)?;
+    
+    let copied = if source.is_dir() {
+        copy_directory_recursive(source, &partial)
+    } else {
+        copy_file_preserving(source, &partial)
+    };
+    if let Err(e) = copied.and_then(|_| replace_path(&partial, dest)) {
+        let _ = delete_path(&partial);
+        return Err(e);
+    }
+    
+    Ok(())
+}
+
+/// Rename `source` to `dest`, replacing whatever is at `dest`. A directory can't
+/// be renamed over another, so the existing entry is first moved aside and put
+/// back if the rename fails.
+fn replace_path(source: &Path, dest: &Path) -> Result<()> {
+    if fs::symlink_metadata(dest).is_err() {
+        return fs::rename(source, dest)
+            .context(format!("Failed to move {:?} into place", source));
+    }
+    
+    let previous = sibling_path(dest, "old")?;
+    delete_path(&previous)?;
+    fs::rename(dest, &previous)
+        .context(format!("Failed to move {:?} aside", dest))?;
+    if let Err(e) = fs::rename(source, dest) {
+        let _ = fs::rename(&previous, dest);
+        return Err(e).context(format!("Failed to move {:?} into place", source));
+    }
+    
+    if let Err(e) = delete_path(&previous) {
+        eprintln!("Warning: Failed to remove replaced copy {:?}: {}", previous, e);
+    }
+    Ok(())
+}
+
+/// Hidden path next to `path` for a copy in progress, e.g., `.Foo.vst3.partial`.
+fn sibling_path(path: &Path, suffix: &str) -> Result<PathBuf> {
+    let name = path.file_name()
+        .context(format!("Invalid path: {:?}", path))?;
+    Ok(path.with_file_name(format!(".{}.{}", name.to_string_lossy(), suffix)))
+}
+
+/// Move a backup folder (or a backup archive file) into `new_dir`, keeping its name,
+/// and return its new location. Manifests store original locations as absolute
+/// paths and package contents relative to the backup, so the moved backup restores
+/// as before. Fails if `new_dir` already holds a backup of the same name, is inside
+/// the backup itself, or if `validate_backup_destination()` rejects it.
+///
+/// Within one volume this is a single rename. Across volumes the backup is copied
+/// under a temporary name and renamed into place once complete, so `new_dir` never
+/// holds a partial backup, and the original is deleted only after that.
+pub fn relocate_backup(backup_path: &Path, new_dir: &Path) -> Result<PathBuf> {
+    if backup_path.is_dir() && !backup_path.join(BACKUP_MANIFEST_FILE).is_file() {
+        anyhow::bail!("Not a backup (no {}): {:?}", BACKUP_MANIFEST_FILE, backup_path);
+    }
+    if !backup_path.exists() {
+        anyhow::bail!("Backup not found: {:?}", backup_path);
+    }
+    
+    let new_dir = &validate_backup_destination(new_dir)?;
+    let canonical = fs::canonicalize(backup_path)
+        .context(format!("Failed to resolve backup path: {:?}", backup_path))?;
+    let backup_key = normalize_path_for_comparison(&canonical);
+    if normalize_path_for_comparison(new_dir).starts_with(&backup_key) {
+        anyhow::bail!("Cannot move backup {:?} into itself: {:?}", backup_path, new_dir);
+    }
+    let name = backup_path.file_name()
+        .context(format!("Invalid backup path: {:?}", backup_path))?;
+    let dest = new_dir.join(name);
+    if dest.exists() {
+        anyhow::bail!("A backup already exists at {:?}", dest);
+    }
+    
+    fs::create_dir_all(new_dir)
+        .context(format!("Failed to create directory: {:?}", new_dir))?;
+    
+    match fs::rename(backup_path, &dest) {
+        Ok(()) => return Ok(dest),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {}
+        Err(e) => {
+            return Err(anyhow::Error::new(e).context(format!("Failed to move backup {:?} to {:?}", backup_path, new_dir)));
+        }
+    }
+    
+    // Different volumes: copy next to the destination, then rename into place
+    ensure_space_for(new_dir, path_size(backup_path))?;
+    let partial = new_dir.join(format!(".{}.partial", name.to_string_lossy()));
+    let copied = if backup_path.is_dir() {
+        copy_directory_recursive(backup_path, &partial)
+    } else {
+        copy_file_preserving(backup_path, &partial)
+    };
+    if let Err(e) = copied.and_then(|_| {
+        fs::rename(&partial, &dest).context(format!("Failed to move {:?} into place", partial))
+    }) {
+        let _ = delete_path(&partial);
+        return Err(e.context(format!("Failed to move backup {:?} to {:?}", backup_path, new_dir)));
+    }
+    
+    delete_path(backup_path)
+        .context(format!("Backup was copied to {:?}, but the original could not be removed", dest))?;
+    Ok(dest)
+}
+
+/// How a file in a backup compares to what is installed now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileComparison {
+    /// Installed copy has the same contents as the backup
+    Same,
+    /// Both exist but their contents differ
+    Changed,
+    /// In the backup, but no longer installed
+    MissingInstalled,
+    /// Listed in the manifest, but missing from the backup folder
+    MissingInBackup,
+}
+
+/// Result of `compare_to_backup()`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupComparison {
+    /// Plugin ID and version recorded in the backup
+    pub backup_id: String,
+    pub backup_version: String,
+    pub id_matches: bool,
+    pub version_matches: bool,
+    /// Each backed-up file or folder (by original path) and how it compares
+    pub files: Vec<(PathBuf, FileComparison)>,
+}
+
+impl BackupComparison {
+    /// True if the backup is of this plugin and every file is unchanged.
+    pub fn is_identical(&self) -> bool {
+        self.id_matches && self.version_matches
+            && self.files.iter().all(|(_, status)| *status == FileComparison::Same)
+    }
+}
+
+/// Compare an installed plugin against a backup created by backup_plugin(), to make
+/// sure a restore would put back the right plugin. Reports whether the ID and version
+/// match and compares every backed-up file or folder with its installed counterpart
+/// by content.
+pub fn compare_to_backup(plugin: &InstalledPlugin, backup_path: &Path) -> Result<BackupComparison> {
+    let manifest = Manifest::read(&backup_path.join(BACKUP_MANIFEST_FILE))?;
+    
+    let mut files = Vec::new();
+    for entry in &manifest.files {
+        let backed_up = package_entry_path(backup_path, &entry.backup_name)?;
+        
+        let status = if fs::symlink_metadata(&backed_up).is_err() {
+            FileComparison::MissingInBackup
+        } else if fs::symlink_metadata(&entry.original_path).is_err() {
+            FileComparison::MissingInstalled
+        } else if paths_identical(&entry.original_path, &backed_up)
+            .context(format!("Failed to compare {:?}", entry.original_path))? {
+            FileComparison::Same
+        } else {
+            FileComparison::Changed
+        };
+        
+        files.push((entry.original_path.clone(), status));
+    }
+    
+    Ok(BackupComparison {
+        id_matches: manifest.plugin_id == plugin.plugin.id,
+        version_matches: manifest.version == plugin.plugin.version,
+        backup_id: manifest.plugin_id,
+        backup_version: manifest.version,
+        files,
+    })
+}
+
+/// Returns true if two files have the same contents, or two directories contain the
+/// same names with identical contents.
+fn paths_identical(a: &Path, b: &Path) -> Result<bool> {
+    if a.is_dir() != b.is_dir() {
+        return Ok(false);
+    }
+    
+    if !a.is_dir() {
+        return files_identical(a, b);
+    }
+    
+    let names = |dir: &Path| -> Result<Vec<std::ffi::OsString>> {
+        let mut names = fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.file_name()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        names.sort();
+        Ok(names)
+    };
+    
+    let names_a = names(a)?;
+    if names_a != names(b)? {
+        return Ok(false);
+    }
+    
+    for name in names_a {
+        if !paths_identical(&a.join(&name), &b.join(&name))? {
+            return Ok(false);
+        }
+    }
+    
+    Ok(true)
+}
+
+/// Compares two files byte by byte, without reading either fully into memory.
+fn files_identical(a: &Path, b: &Path) -> Result<bool> {
+    if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+    
+    let mut reader_a = std::io::BufReader::new(fs::File::open(a)?);
+    let mut reader_b = std::io::BufReader::new(fs::File::open(b)?);
+    let mut buf_a = [0u8; 64 * 1024];
+    let mut buf_b = [0u8; 64 * 1024];
+    
+    loop {
+        let read = reader_a.read(&mut buf_a)?;
+        if read == 0 {
+            return Ok(true);
+        }
+        reader_b.read_exact(&mut buf_b[..read])?;
+        if buf_a[..read] != buf_b[..read] {
+            return Ok(false);
+        }
+    }
+}
+
 /// Uninstall a plugin, removing all associated files.
 /// Returns the list of files that were deleted.
 pub fn uninstall_plugin(plugin: &InstalledPlugin, dry_run: bool) -> Result<Vec<PathBuf>> {
-    let files = enumerate_plugin_files(plugin)?;
+    uninstall_plugin_with_progress(plugin, dry_run, |_| {})
+}
+
+/// Like `uninstall_plugin()`, calling `progress` after each file is deleted.
+/// No progress is reported in dry-run mode.
+pub fn uninstall_plugin_with_progress<F>(plugin: &InstalledPlugin, dry_run: bool, progress: F) -> Result<Vec<PathBuf>>
+where
+    F: FnMut(&OpProgress),
+{
+    uninstall_plugin_with_options(plugin, dry_run, &UninstallOptions::default(), progress)
+}
+
+/// Options for `uninstall_plugin_with_options()`.
+#[derive(Debug, Clone)]
+pub struct UninstallOptions {
+    /// Related paths discovered with a lower confidence are left in place. The
+    /// plugin binary or bundle itself is always deleted. Defaults to `Low`,
+    /// which deletes every related path.
+    pub min_confidence: Confidence,
+}
+
+impl Default for UninstallOptions {
+    fn default() -> Self {
+        UninstallOptions { min_confidence: Confidence::Low }
+    }
+}
+
+/// Like `uninstall_plugin_with_progress()`, with non-default `options`.
+pub fn uninstall_plugin_with_options<F>(plugin: &InstalledPlugin, dry_run: bool, options: &UninstallOptions, progress: F) -> Result<Vec<PathBuf>>
+where
+    F: FnMut(&OpProgress),
+{
+    uninstall_recorded(plugin, dry_run, options, true, progress)
+}
+
+/// Uninstalls a plugin and records it in the audit log. Without `include_binary`
+/// only the related files are removed, for a shell member whose shell binary
+/// was already removed along with another member.
+fn uninstall_recorded<F>(plugin: &InstalledPlugin, dry_run: bool, options: &UninstallOptions, include_binary: bool, progress: F) -> Result<Vec<PathBuf>>
+where
+    F: FnMut(&OpProgress),
+{
+    let mut plugin = plugin.clone();
+    plugin.related_paths = plugin.related_paths.with_min_confidence(options.min_confidence);
+    
+    let result = delete_plugin_files(&plugin, dry_run, include_binary, progress);
+    audit::record(AuditAction::Uninstall, &plugin.plugin.id, dry_run, result.as_ref().cloned());
+    result
+}
+
+fn delete_plugin_files<F>(plugin: &InstalledPlugin, dry_run: bool, include_binary: bool, mut progress: F) -> Result<Vec<PathBuf>>
+where
+    F: FnMut(&OpProgress),
+{
+    let mut files = enumerate_plugin_files(plugin)?;
+    if !include_binary {
+        files.retain(|file| *file != plugin.install_path);
+    }
     let mut deleted = Vec::new();
     
     if dry_run {
@@ -49,155 +591,1857 @@ pub fn uninstall_plugin(plugin: &InstalledPlugin, dry_run: bool) -> Result<Vec<P
         return Ok(files);
     }
     
-    // Delete files in reverse order (files before directories)
-    for file in &files {
+    // Fail before deleting anything rather than leave a half-removed plugin
+    let protected = paths_needing_elevation(plugin);
+    if !protected.is_empty() {
+        return Err(NeedsElevation { plugin_id: plugin.plugin.id.clone(), paths: protected }.into());
+    }
+    
+    // Delete in enumeration order: the plugin bundle/file (if included) first,
+    // then its related files
+    let mut tracker = ProgressTracker::new(&files);
+    for (index, file) in files.iter().enumerate() {
         match delete_path(file) {
             Ok(_) => deleted.push(file.clone()),
             Err(e) => eprintln!("Warning: Failed to delete {:?}: {}", file, e),
         }
-    }
-    
-    // Also try to remove the main plugin bundle/directory
-    if let Err(e) = delete_path(&plugin.install_path) {
-        eprintln!("Warning: Failed to delete main plugin at {:?}: {}", plugin.install_path, e);
-    } else {
-        deleted.push(plugin.install_path.clone());
+        progress(&tracker.advance(index, file));
     }
     
     Ok(deleted)
 }
 
+/// Returned (inside the `anyhow::Error`) by `uninstall_plugin()` when some of the
+/// plugin's files can't be deleted without administrator privileges, typically a
+/// system-wide plugin. Nothing has been deleted. Check for it with
+/// `error.downcast_ref::<NeedsElevation>()`.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Administrator privileges needed to uninstall {plugin_id}: {paths:?}")]
+pub struct NeedsElevation {
+    pub plugin_id: String,
+    /// Plugin and related paths the current user can't delete
+    pub paths: Vec<PathBuf>,
+}
+
+/// Returns the plugin's paths (its bundle/file and top-level related paths) that
+/// the current user can't delete, judged by probing write access to each path's
+/// folder and, for directories, the path itself.
+pub fn paths_needing_elevation(plugin: &InstalledPlugin) -> Vec<PathBuf> {
+    let related = RelatedCategory::ALL.iter()
+        .flat_map(|category| plugin.related_paths.paths(*category))
+        .map(|related| &related.path);
+    
+    std::iter::once(&plugin.install_path)
+        .chain(related)
+        .filter(|path| fs::symlink_metadata(path).is_ok())
+        .filter(|path| {
+            let parent_ok = path.parent().is_none_or(can_modify_dir);
+            let contents_ok = !fs::symlink_metadata(path).is_ok_and(|m| m.is_dir()) || can_modify_dir(path);
+            !(parent_ok && contents_ok)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Returns true if entries can be created and removed in `dir`, by creating and
+/// removing a probe file. Permission bits alone don't tell (ownership, ACLs,
+/// read-only volumes).
+fn can_modify_dir(dir: &Path) -> bool {
+    let probe = dir.join(format!(".plugindepot-write-probe-{}", std::process::id()));
+    match fs::OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => fs::remove_file(&probe).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Uninstall several plugins in one pass.
+/// Returns each plugin's ID with the paths deleted (or, in dry-run, the paths that would be).
+/// A plugin whose files cannot be enumerated is reported as a warning and skipped.
+/// Shell members share the shell binary, which is removed (and reported) with the
+/// first of them; only the related files of the others are removed.
+pub fn uninstall_plugins(plugins: &[&InstalledPlugin], dry_run: bool) -> Result<Vec<(String, Vec<PathBuf>)>> {
+    let mut report = Vec::new();
+    let mut removed_binaries = HashSet::new();
+
+    for plugin in plugins {
+        let include_binary = !removed_binaries.contains(&plugin.install_path);
+        match uninstall_recorded(plugin, dry_run, &UninstallOptions::default(), include_binary, |_| {}) {
+            Ok(paths) => {
+                removed_binaries.insert(plugin.install_path.clone());
+                report.push((plugin.plugin.id.clone(), paths));
+            }
+            Err(e) => eprintln!("Warning: Failed to uninstall {}: {}", plugin.plugin.name, e),
+        }
+    }
+
+    Ok(report)
+}
+
 /// Export a plugin for migration to another machine.
 /// Creates a portable package that can be imported on the target system.
+/// Re-running an interrupted export resumes it: files already copied intact are
+/// skipped.
 pub fn export_plugin(plugin: &InstalledPlugin, export_dir: &Path) -> Result<PathBuf> {
+    export_plugin_with_progress(plugin, export_dir, |_| {})
+}
+
+/// Like `export_plugin()`, calling `progress` after each file is copied.
+pub fn export_plugin_with_progress<F>(plugin: &InstalledPlugin, export_dir: &Path, progress: F) -> Result<PathBuf>
+where
+    F: FnMut(&OpProgress),
+{
+    let result = copy_to_export(plugin, export_dir, progress);
+    audit::record(AuditAction::Export, &plugin.plugin.id, false, result.as_ref().map(|path| vec![path.clone()]));
+    result
+}
+
+/// How often an export in progress updates its partial manifest
+const PARTIAL_MANIFEST_INTERVAL: Duration = Duration::from_secs(1);
+
+fn copy_to_export<F>(plugin: &InstalledPlugin, export_dir: &Path, mut progress: F) -> Result<PathBuf>
+where
+    F: FnMut(&OpProgress),
+{
+    let files = enumerate_plugin_files(plugin)?;
+    let mut tracker = ProgressTracker::new(&files);
+    
     let export_name = format!("{}_export", plugin.plugin.name.replace(" ", "_"));
     let export_path = export_dir.join(&export_name);
+    let partial_manifest_path = export_path.join(EXPORT_PARTIAL_MANIFEST_FILE);
+    
+    // Resuming an interrupted export only needs room for what's still missing
+    let resuming = partial_manifest_path.is_file();
+    if resuming {
+        let previous = Manifest::read(&partial_manifest_path)?;
+        if previous.plugin_id != plugin.plugin.id {
+            anyhow::bail!(
+                "{:?} holds an unfinished export of another plugin ({})",
+                export_path, previous.plugin_id
+            );
+        }
+        ensure_space_for(export_dir, tracker.bytes_total.saturating_sub(path_size(&export_path)))?;
+    } else {
+        ensure_space_for(export_dir, tracker.bytes_total)?;
+    }
     
     fs::create_dir_all(&export_path)
         .context(format!("Failed to create export directory: {:?}", export_path))?;
     
-    // Copy all plugin files
-    let files = enumerate_plugin_files(plugin)?;
-    for file in &files {
-        if let Err(e) = copy_file_to_backup(file, &export_path) {
-            eprintln!("Warning: Failed to export file {:?}: {}", file, e);
+    // The partial manifest marks the export as unfinished until the real one is
+    // written; it's refreshed periodically (rewriting it per file would be slow
+    // for libraries of thousands of files)
+    let mut exported = Vec::new();
+    Manifest::new(plugin, &export_path, &exported).write(&partial_manifest_path)?;
+    let mut last_recorded = Instant::now();
+    
+    // Copy all plugin files, skipping those an earlier run already copied. Files
+    // are enumerated in the same order every run, so each gets the same name again.
+    let mut used_names = HashSet::new();
+    for (index, file) in files.iter().enumerate() {
+        match copy_file_to_package(file, &export_path, &mut used_names, true) {
+            Ok(Some(dest)) => {
+                exported.push((file.clone(), dest));
+                if last_recorded.elapsed() >= PARTIAL_MANIFEST_INTERVAL {
+                    Manifest::new(plugin, &export_path, &exported).write(&partial_manifest_path)?;
+                    last_recorded = Instant::now();
+                }
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Warning: Failed to export file {:?}: {}", file, e),
         }
+        progress(&tracker.advance(index, file));
     }
     
     // Create metadata for import
-    create_export_manifest(plugin, &export_path)?;
+    Manifest::new(plugin, &export_path, &exported)
+        .write(&export_path.join(EXPORT_MANIFEST_FILE))?;
+    if let Err(e) = fs::remove_file(&partial_manifest_path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            eprintln!("Warning: Failed to remove {:?}: {}", partial_manifest_path, e);
+        }
+    }
     
     Ok(export_path)
 }
 
-/// Import a plugin from an export package created by export_plugin().
-/// Restores the plugin and its files to the appropriate system locations.
-pub fn import_plugin(_package_path: &Path) -> Result<InstalledPlugin> {
-    // TODO: Implement import logic
-    // 1. Read manifest from package
-    // 2. Determine target locations based on format and platform
-    // 3. Copy files to appropriate system locations
-    // 4. Verify installation
-    // 5. Return InstalledPlugin instance
-    
-    anyhow::bail!("Import not yet implemented");
+/// Settings for zip exports.
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    /// Include presets, libraries, support files, and preferences, not just the plugin
+    pub include_related: bool,
+    /// Deflate-compress entries (otherwise they are stored uncompressed, which is
+    /// faster for already-compressed sample libraries)
+    pub compress: bool,
+    /// Whether related files are grouped into per-category folders
+    pub layout: ExportLayout,
 }
 
-// Helper functions
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            include_related: true,
+            compress: true,
+            layout: ExportLayout::Categorized,
+        }
+    }
+}
 
-/// Copy a file to backup directory, preserving its relative path structure.
-fn copy_file_to_backup(source: &Path, backup_dir: &Path) -> Result<()> {
-    if !source.exists() {
-        return Ok(());
+/// Export a plugin as a zip archive named `<Plugin>_export.zip` in `export_dir`.
+/// The layout is recorded in the archive's manifest, so once extracted it can be
+/// passed to `import_plugin()` either way.
+pub fn export_plugin_archive(plugin: &InstalledPlugin, options: &ExportOptions, export_dir: &Path) -> Result<PathBuf> {
+    let result = write_archive(plugin, options, export_dir);
+    audit::record(AuditAction::Export, &plugin.plugin.id, false, result.as_ref().map(|path| vec![path.clone()]));
+    result
+}
+
+fn write_archive(plugin: &InstalledPlugin, options: &ExportOptions, export_dir: &Path) -> Result<PathBuf> {
+    // Compression only helps, so the uncompressed size is a safe upper bound
+    let entries = archive_entries(plugin, options)?;
+    ensure_space_for(export_dir, entries.iter().map(|(source, _)| path_size(source)).sum())?;
+    
+    fs::create_dir_all(export_dir)
+        .context(format!("Failed to create export directory: {:?}", export_dir))?;
+    
+    let archive_path = export_dir.join(format!("{}_export.zip", plugin.plugin.name.replace(" ", "_")));
+    let file = fs::File::create(&archive_path)
+        .context(format!("Failed to create archive: {:?}", archive_path))?;
+    
+    if let Err(e) = export_plugin_to_writer(plugin, options, file) {
+        // Don't leave a truncated archive behind
+        let _ = fs::remove_file(&archive_path);
+        return Err(e);
     }
     
-    // Use the file name as destination (simplified for now)
-    // TODO: Preserve directory structure better
-    let file_name = source.file_name()
-        .context("Invalid file name")?;
-    let dest = backup_dir.join(file_name);
+    Ok(archive_path)
+}
+
+/// Write a plugin export as a zip archive into any seekable writer (a file, an
+/// in-memory buffer, etc.), without staging files on disk.
+pub fn export_plugin_to_writer<W: Write + Seek>(plugin: &InstalledPlugin, options: &ExportOptions, writer: W) -> Result<()> {
+    let entries = archive_entries(plugin, options)?;
     
-    if source.is_dir() {
-        copy_directory_recursive(source, &dest)?;
-    } else {
-        if let Some(parent) = dest.parent() {
-            fs::create_dir_all(parent)?;
+    let method = if options.compress { CompressionMethod::Deflated } else { CompressionMethod::Stored };
+    let mut zip = ZipWriter::new(writer);
+    let mut used_names = HashSet::new();
+    let mut exported = Vec::new();
+    
+    for (source, folder) in &entries {
+        if !source.exists() {
+            continue;
         }
-        fs::copy(source, dest)?;
+        
+        let name = unique_entry_name(source, folder, &mut used_names)?;
+        add_to_archive(&mut zip, source, &name, method)
+            .context(format!("Failed to add {:?} to archive", source))?;
+        exported.push((source.clone(), name));
     }
     
+    // Entry names are relative to the archive root, which stands in for the package folder
+    let mut manifest = Manifest::new(plugin, Path::new(""), &exported);
+    manifest.layout = options.layout;
+    zip.start_file(EXPORT_MANIFEST_FILE, SimpleFileOptions::default().compression_method(method))?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    
+    zip.finish()
+        .context("Failed to finish archive")?;
+    
     Ok(())
 }
 
-/// Recursively copy a directory.
-fn copy_directory_recursive(source: &Path, dest: &Path) -> Result<()> {
-    fs::create_dir_all(dest)?;
-    
-    for entry in fs::read_dir(source)? {
-        let entry = entry?;
-        let path = entry.path();
-        let dest_path = dest.join(entry.file_name());
-        
-        if path.is_dir() {
-            copy_directory_recursive(&path, &dest_path)?;
-        } else {
-            fs::copy(&path, &dest_path)?;
+/// Returns the paths to put in an export archive, each with the archive folder it
+/// goes in (empty for the top level).
+fn archive_entries(plugin: &InstalledPlugin, options: &ExportOptions) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let entries = match options.layout {
+        ExportLayout::Flat => {
+            let files = if options.include_related {
+                enumerate_plugin_files(plugin)?
+            } else {
+                vec![plugin.install_path.clone()]
+            };
+            files.into_iter().map(|file| (file, PathBuf::new())).collect()
         }
+        ExportLayout::Categorized => {
+            let mut entries = vec![(plugin.install_path.clone(), PathBuf::from("Plugin"))];
+            if options.include_related {
+                for category in RelatedCategory::ALL {
+                    let folder = PathBuf::from(category_folder(category));
+                    entries.extend(plugin.related_paths.paths(category).iter()
+                        .map(|related| (related.path.clone(), folder.clone())));
+                }
+            }
+            entries
+        }
+    };
+    
+    Ok(entries)
+}
+
+/// Archive folder for a related-path category in a categorized export
+fn category_folder(category: RelatedCategory) -> &'static str {
+    match category {
+        RelatedCategory::Presets => "Presets",
+        RelatedCategory::Libraries => "Libraries",
+        RelatedCategory::Support => "Support",
+        RelatedCategory::Preferences => "Preferences",
     }
+}
+
+/// Returns the package or archive name for an entry placed in `folder`: its file
+/// name, with a numeric suffix if another entry already uses it.
+fn unique_entry_name(path: &Path, folder: &Path, used_names: &mut HashSet<PathBuf>) -> Result<PathBuf> {
+    let file_name = path.file_name()
+        .context(format!("Invalid file name: {:?}", path))?;
     
-    Ok(())
+    let mut name = folder.join(file_name);
+    let mut counter = 2;
+    while used_names.contains(&name) {
+        let stem = Path::new(file_name).file_stem().unwrap_or(file_name).to_string_lossy();
+        name = match Path::new(file_name).extension() {
+            Some(ext) => folder.join(format!("{} ({}).{}", stem, counter, ext.to_string_lossy())),
+            None => folder.join(format!("{} ({})", stem, counter)),
+        };
+        counter += 1;
+    }
+    
+    used_names.insert(name.clone());
+    Ok(name)
 }
 
-/// Delete a file or directory.
-fn delete_path(path: &Path) -> Result<()> {
-    if !path.exists() {
-        return Ok(());
+/// Add a file, or a directory and everything under it, to a zip archive as `name`.
+fn add_to_archive<W: Write + Seek>(zip: &mut ZipWriter<W>, source: &Path, name: &Path, method: CompressionMethod) -> Result<()> {
+    let metadata = fs::metadata(source)?;
+    let mut options = SimpleFileOptions::default().compression_method(method);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        options = options.unix_permissions(metadata.permissions().mode());
     }
     
-    if path.is_dir() {
-        fs::remove_dir_all(path)
-            .context(format!("Failed to remove directory: {:?}", path))?;
+    // Zip entry names always use forward slashes
+    let entry_name = name.to_string_lossy().replace('\\', "/");
+    
+    if metadata.is_dir() {
+        zip.add_directory(entry_name, options)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            if is_skipped_file(&entry.path()) {
+                continue;
+            }
+            let entry_path = name.join(entry.file_name());
+            // Links inside are stored as links: following one to a parent folder
+            // would never finish
+            if entry.file_type()?.is_symlink() {
+                let target = fs::read_link(entry.path())?;
+                let link_name = entry_path.to_string_lossy().replace('\\', "/");
+                zip.add_symlink(link_name, target.to_string_lossy(), SimpleFileOptions::default())?;
+                continue;
+            }
+            add_to_archive(zip, &entry.path(), &entry_path, method)?;
+        }
     } else {
-        fs::remove_file(path)
-            .context(format!("Failed to remove file: {:?}", path))?;
+        // Large libraries can exceed 4 GB
+        options = options.large_file(metadata.len() >= u32::MAX as u64);
+        zip.start_file(entry_name, options)?;
+        let mut file = fs::File::open(source)?;
+        std::io::copy(&mut file, zip)?;
     }
     
     Ok(())
 }
 
-/// Create a manifest file for backup with plugin metadata.
-fn create_backup_manifest(plugin: &InstalledPlugin, backup_dir: &Path) -> Result<()> {
-    let manifest_path = backup_dir.join("backup_manifest.json");
-    let manifest = serde_json::json!({
-        "plugin_name": plugin.plugin.name,
-        "plugin_id": plugin.plugin.id,
-        "version": plugin.plugin.version,
-        "format": format!("{:?}", plugin.format),
-        "install_path": plugin.install_path.to_string_lossy(),
-        "backup_date": chrono::Local::now().to_rfc3339(),
-    });
+/// Describe a plugin without packaging any of its files: name, version, format,
+/// vendor, tags, and detected license type. Serialize the result to share what is
+/// installed without sharing (possibly licensed) binaries.
+/// License keys and emails are never included.
+pub fn export_metadata(plugin: &InstalledPlugin) -> Result<PluginMetadata> {
+    let vendor = plugin.plugin.author.as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| Vendor {
+            id: name.to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join("-"),
+            name: name.to_string(),
+            website: None,
+        });
     
-    let content = serde_json::to_string_pretty(&manifest)?;
-    fs::write(manifest_path, content)?;
+    let license = plugin.license.as_ref().map(|license| License {
+        key: None,
+        email: None,
+        license_file: None,
+        ..license.clone()
+    });
     
-    Ok(())
+    Ok(PluginMetadata {
+        plugin: plugin.plugin.clone(),
+        format: plugin.format,
+        vendor,
+        license,
+        tags: plugin.plugin.tags.clone(),
+    })
 }
 
-/// Create a manifest file for export with platform-independent metadata.
-fn create_export_manifest(plugin: &InstalledPlugin, export_dir: &Path) -> Result<()> {
-    let manifest_path = export_dir.join("export_manifest.json");
-    let manifest = serde_json::json!({
-        "plugin_name": plugin.plugin.name,
-        "plugin_id": plugin.plugin.id,
-        "version": plugin.plugin.version,
-        "format": format!("{:?}", plugin.format),
-        "description": plugin.plugin.description,
-        "author": plugin.plugin.author,
-        "export_date": chrono::Local::now().to_rfc3339(),
-    });
+/// Scan this machine and describe every installed plugin with `export_metadata()`.
+pub fn export_all_metadata() -> Result<Vec<PluginMetadata>> {
+    scan_installed()?.iter()
+        .map(export_metadata)
+        .collect()
+}
+
+/// What `import_plugin()` did with one target of an export package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportOutcome {
+    /// Copied to a location that was free
+    Installed,
+    /// Left as it was because something already exists there
+    SkippedExisting,
+    /// Replaced what was there (`overwrite` was set)
+    Overwritten,
+    /// The plugin is already installed in another version, which was left as it was
+    VersionConflict { installed_version: String },
+    /// Copying failed
+    Failed(String),
+}
+
+/// Per-target report of an import, for showing the user what was reconciled.
+#[derive(Debug, Clone)]
+pub struct ImportResult {
+    /// The plugin at its install location on this machine: the imported copy, or
+    /// the existing install if that was kept
+    pub plugin: InstalledPlugin,
+    /// Destination of each file in the package and what happened to it
+    pub targets: Vec<(PathBuf, ImportOutcome)>,
+}
+
+impl ImportResult {
+    /// Returns true if every target was installed or overwritten.
+    pub fn is_complete(&self) -> bool {
+        self.targets.iter()
+            .all(|(_, outcome)| matches!(outcome, ImportOutcome::Installed | ImportOutcome::Overwritten))
+    }
+}
+
+/// Import a plugin from an export package created by export_plugin().
+/// The plugin goes into this platform's standard folder for its format, and its
+/// related files into this user's standard folders for their categories (never to
+/// absolute paths from the package). Each file is copied under a temporary name and
+/// renamed into place once complete. Existing files are kept unless `overwrite` is
+/// set; the result reports what happened to each target instead of failing on the
+/// first conflict. Packages listing files outside the package folder are rejected.
+pub fn import_plugin(package_path: &Path, overwrite: bool) -> Result<ImportResult> {
+    let manifest = Manifest::read(&package_path.join(EXPORT_MANIFEST_FILE))?;
+    let result = default_install_dir(&manifest.format)
+        .and_then(|install_dir| install_package(package_path, &manifest, &install_dir, overwrite));
+    let written = result.as_ref().map(|imported| imported.targets.iter()
+        .filter(|(_, outcome)| matches!(outcome, ImportOutcome::Installed | ImportOutcome::Overwritten))
+        .map(|(path, _)| path.clone())
+        .collect());
+    audit::record(AuditAction::Import, &manifest.plugin_id, false, written);
+    result
+}
+
+fn install_package(package_path: &Path, manifest: &Manifest, install_dir: &Path, overwrite: bool) -> Result<ImportResult> {
+    // Check every entry before copying anything
+    for entry in &manifest.files {
+        package_entry_path(package_path, &entry.backup_name)?;
+    }
     
-    let content = serde_json::to_string_pretty(&manifest)?;
-    fs::write(manifest_path, content)?;
+    let mut install_path = None;
+    let mut targets = Vec::new();
     
-    Ok(())
+    for entry in &manifest.files {
+        let source = package_entry_path(package_path, &entry.backup_name)?;
+        let is_plugin = manifest.install_path.as_ref() == Some(&entry.original_path);
+        
+        let dest = if is_plugin {
+            let name = entry.original_path.file_name()
+                .context(format!("Invalid plugin path in manifest: {:?}", entry.original_path))?;
+            install_dir.join(name)
+        } else {
+            match related_import_path(manifest, entry) {
+                Ok(dest) => dest,
+                Err(e) => {
+                    targets.push((entry.original_path.clone(), ImportOutcome::Failed(e.to_string())));
+                    continue;
+                }
+            }
+        };
+        
+        let exists = fs::symlink_metadata(&dest).is_ok();
+        let outcome = if exists && !overwrite {
+            let installed_version = if is_plugin {
+                recognize_plugin(dest.clone(), &manifest.format)
+                    .map(|existing| existing.plugin.version)
+                    .filter(|version| *version != manifest.version)
+            } else {
+                None
+            };
+            match installed_version {
+                Some(installed_version) => ImportOutcome::VersionConflict { installed_version },
+                None => ImportOutcome::SkippedExisting,
+            }
+        } else {
+            match restore_path(&source, &dest) {
+                Ok(_) if exists => ImportOutcome::Overwritten,
+                Ok(_) => ImportOutcome::Installed,
+                // Without the plugin itself the import has failed
+                Err(e) if is_plugin => return Err(e.context(format!("Failed to install plugin to {:?}", dest))),
+                Err(e) => ImportOutcome::Failed(e.to_string()),
+            }
+        };
+        
+        if is_plugin {
+            install_path = Some(dest.clone());
+        }
+        targets.push((dest, outcome));
+    }
+    
+    let install_path = install_path
+        .context("Export package does not contain the plugin binary")?;
+    
+    let plugin = recognize_plugin(install_path.clone(), &manifest.format)
+        .context(format!("Imported plugin was not recognized: {:?}", install_path))?;
+    
+    Ok(ImportResult { plugin, targets })
+}
+
+/// Resolves a path from a manifest inside the package folder. Manifests are
+/// untrusted input, so absolute paths and `..` are rejected.
+fn package_entry_path(package_path: &Path, name: &Path) -> Result<PathBuf> {
+    if !is_plain_relative_path(name) {
+        anyhow::bail!("Manifest lists a file outside the package: {:?}", name);
+    }
+    Ok(package_path.join(name))
+}
+
+/// True for a non-empty relative path made only of plain names (no root, drive,
+/// or `..`).
+fn is_plain_relative_path(path: &Path) -> bool {
+    path.components().next().is_some()
+        && path.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// Where an imported related file goes: its recorded place under this user's
+/// standard folder for its category. Older packages don't record the place, but
+/// categorized ones keep the category as the top folder of the entry.
+fn related_import_path(manifest: &Manifest, entry: &ManifestFile) -> Result<PathBuf> {
+    let (category, relative) = match (entry.category, &entry.relative_path) {
+        (Some(category), Some(relative)) => (category, relative.clone()),
+        _ if manifest.layout == ExportLayout::Categorized => {
+            let mut components = entry.backup_name.components();
+            let folder = components.next().map(|c| c.as_os_str().to_string_lossy().to_string());
+            let category = RelatedCategory::ALL.into_iter()
+                .find(|category| Some(category_folder(*category)) == folder.as_deref())
+                .context(format!("Unknown category folder for {:?}", entry.backup_name))?;
+            (category, components.as_path().to_path_buf())
+        }
+        _ => anyhow::bail!("Package doesn't record where {:?} belongs", entry.original_path),
+    };
+    
+    if !is_plain_relative_path(&relative) {
+        anyhow::bail!("Invalid location in manifest: {:?}", relative);
+    }
+    let folder = related_install_dir(category)
+        .context(format!("No standard {:?} folder on this machine", category))?;
+    Ok(folder.join(relative))
+}
+
+/// Rename a preset category folder inside one of the plugin's preset locations.
+/// `old` must be a folder strictly inside a discovered preset location (the location
+/// itself can't be renamed), and `new_name` must be a plain folder name.
+/// Returns the folder's new path.
+pub fn rename_preset_folder(plugin: &InstalledPlugin, old: &Path, new_name: &str) -> Result<PathBuf> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() || new_name == "." || new_name == ".." || new_name.contains(['/', '\\']) {
+        anyhow::bail!("Invalid folder name: {:?}", new_name);
+    }
+    
+    if !old.is_dir() {
+        anyhow::bail!("Preset folder not found: {:?}", old);
+    }
+    
+    // Compare resolved paths so ".." or symlinks can't escape the preset locations
+    let resolved = fs::canonicalize(old)
+        .context(format!("Failed to resolve path: {:?}", old))?;
+    let inside_presets = plugin.related_paths.preset_locations.iter()
+        .filter_map(|related| fs::canonicalize(&related.path).ok())
+        .any(|location| resolved.starts_with(&location) && resolved != location);
+    if !inside_presets {
+        anyhow::bail!("{:?} is not inside a preset location of {}", old, plugin.plugin.name);
+    }
+    
+    let new_path = old.with_file_name(new_name);
+    if fs::symlink_metadata(&new_path).is_ok() {
+        anyhow::bail!("A file or folder named {:?} already exists", new_name);
+    }
+    
+    fs::rename(old, &new_path)
+        .context(format!("Failed to rename {:?} to {:?}", old, new_path))?;
+    
+    Ok(new_path)
+}
+
+/// Enable or disable a plugin without uninstalling it.
+/// Bundles are moved into a "(Disabled)" folder next to their plugin directory
+/// (e.g., `VST3 (Disabled)`); file-based plugins get a `.disabled` extension.
+/// The plugin's recorded paths are updated to the new location. Reversible.
+pub fn set_plugin_enabled(plugin: &mut InstalledPlugin, enabled: bool) -> Result<()> {
+    if plugin.enabled == enabled {
+        return Ok(());
+    }
+    
+    let current = plugin.install_path.clone();
+    let file_name = current.file_name()
+        .context("Invalid plugin path")?
+        .to_string_lossy()
+        .to_string();
+    let parent = current.parent()
+        .context("Plugin has no parent directory")?;
+    
+    let target = if current.is_dir() {
+        if enabled {
+            // Move back out of "<dir> (Disabled)" into "<dir>"
+            let parent_name = parent.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let original_name = parent_name.strip_suffix(DISABLED_DIR_SUFFIX)
+                .context(format!("Plugin is not in a disabled folder: {:?}", current))?;
+            parent.with_file_name(original_name).join(&file_name)
+        } else {
+            disabled_dir_for(parent).join(&file_name)
+        }
+    } else if enabled {
+        let original_name = file_name.strip_suffix(&format!(".{}", DISABLED_FILE_EXTENSION))
+            .context(format!("Plugin file is not disabled: {:?}", current))?;
+        parent.join(original_name)
+    } else {
+        parent.join(format!("{}.{}", file_name, DISABLED_FILE_EXTENSION))
+    };
+    
+    if target.exists() {
+        anyhow::bail!("Cannot move plugin, destination already exists: {:?}", target);
+    }
+    
+    if let Some(target_parent) = target.parent() {
+        fs::create_dir_all(target_parent)
+            .context(format!("Failed to create directory: {:?}", target_parent))?;
+    }
+    
+    fs::rename(&current, &target)
+        .context(format!("Failed to move plugin from {:?} to {:?}", current, target))?;
+    
+    plugin.resolved_path = resolve_symlink(&target);
+    plugin.install_path = target;
+    plugin.enabled = enabled;
+    
+    Ok(())
+}
+
+/// Move one category of a plugin's related folders (e.g., sample libraries) under
+/// `new_root`, leaving a symlink at each original location so hosts still find them.
+/// Returns the (original, new) location of every moved path.
+/// If any move fails, the ones already done are reverted before returning the error.
+/// Use `revert_relocation()` with the returned list to undo.
+pub fn relocate_library(plugin: &InstalledPlugin, category: RelatedCategory, new_root: &Path) -> Result<Vec<(PathBuf, PathBuf)>> {
+    // Skip paths that are missing or already relocated
+    let sources: Vec<&PathBuf> = plugin.related_paths.paths(category).iter()
+        .map(|related| &related.path)
+        .filter(|p| fs::symlink_metadata(p).is_ok_and(|m| !m.file_type().is_symlink()))
+        .collect();
+    
+    // Check every destination before moving anything
+    let mut planned = Vec::new();
+    for source in sources {
+        let name = source.file_name()
+            .context(format!("Invalid path: {:?}", source))?;
+        let dest = new_root.join(name);
+        if dest.exists() {
+            anyhow::bail!("Destination already exists: {:?}", dest);
+        }
+        planned.push((source.clone(), dest));
+    }
+    
+    fs::create_dir_all(new_root)
+        .context(format!("Failed to create directory: {:?}", new_root))?;
+    
+    let mut moved = Vec::new();
+    for (source, dest) in planned {
+        if let Err(e) = move_path(&source, &dest).and_then(|_| create_symlink(&dest, &source)) {
+            // Put back a move whose symlink couldn't be created
+            if dest.exists() && !source.exists() {
+                if let Err(undo) = move_path(&dest, &source) {
+                    eprintln!("Warning: Failed to move {:?} back to {:?}: {}", dest, source, undo);
+                }
+            }
+            if let Err(undo) = revert_relocation(&moved) {
+                eprintln!("Warning: Failed to revert relocation: {}", undo);
+            }
+            return Err(e.context(format!("Failed to relocate {:?}", source)));
+        }
+        moved.push((source, dest));
+    }
+    
+    Ok(moved)
+}
+
+/// Undo `relocate_library()`: replace each symlink with the folder it points to.
+pub fn revert_relocation(moves: &[(PathBuf, PathBuf)]) -> Result<()> {
+    for (original, relocated) in moves.iter().rev() {
+        let is_symlink = fs::symlink_metadata(original).is_ok_and(|m| m.file_type().is_symlink());
+        if !is_symlink {
+            anyhow::bail!("Expected a symlink at {:?}", original);
+        }
+        remove_symlink(original)?;
+        move_path(relocated, original)?;
+    }
+    
+    Ok(())
+}
+
+/// Point a plugin's preference files at a content folder that was moved by hand.
+/// Every value referencing one of the category's known locations (or something
+/// inside it) is rewritten to the same place under `new_path`.
+/// Only plist and ini-style (`key=value`) preference files can be edited; fails if
+/// none of the plugin's preference files could be updated.
+pub fn repoint_content(plugin: &InstalledPlugin, category: RelatedCategory, new_path: &Path) -> Result<()> {
+    if category == RelatedCategory::Preferences {
+        anyhow::bail!("Preference files can't be repointed, only content folders");
+    }
+    
+    let old_paths: Vec<&PathBuf> = plugin.related_paths.paths(category).iter()
+        .map(|related| &related.path)
+        .collect();
+    if old_paths.is_empty() {
+        anyhow::bail!("No {:?} locations are known for {}", category, plugin.plugin.name);
+    }
+    
+    let mut updated = false;
+    let mut opaque = Vec::new();
+    
+    for related in &plugin.related_paths.preference_files {
+        let path = &related.path;
+        if !path.is_file() {
+            continue;
+        }
+        
+        let ext = path.extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let changed = match ext.as_str() {
+            "plist" => repoint_plist(path, &old_paths, new_path)?,
+            "ini" | "cfg" | "conf" | "txt" | "settings" => repoint_ini(path, &old_paths, new_path)?,
+            _ => {
+                opaque.push(path.clone());
+                continue;
+            }
+        };
+        updated |= changed;
+    }
+    
+    if !updated {
+        if !opaque.is_empty() {
+            anyhow::bail!(
+                "{} stores its settings in a format PluginDepot can't edit ({:?}); update the content location in the plugin itself",
+                plugin.plugin.name, opaque
+            );
+        }
+        anyhow::bail!("None of {}'s preference files reference its {:?} location", plugin.plugin.name, category);
+    }
+    
+    Ok(())
+}
+
+/// Rewrites matching string values anywhere in a plist, keeping its binary or XML encoding.
+fn repoint_plist(path: &Path, old_paths: &[&PathBuf], new_path: &Path) -> Result<bool> {
+    let data = fs::read(path)
+        .context(format!("Failed to read preference file: {:?}", path))?;
+    let mut value = plist::Value::from_reader(std::io::Cursor::new(&data))
+        .context(format!("Failed to parse preference file: {:?}", path))?;
+    
+    if !repoint_plist_value(&mut value, old_paths, new_path) {
+        return Ok(false);
+    }
+    
+    let mut content = Vec::new();
+    if data.starts_with(b"bplist") {
+        value.to_writer_binary(&mut content)?;
+    } else {
+        value.to_writer_xml(&mut content)?;
+    }
+    write_atomic(path, &content)
+        .context(format!("Failed to write preference file: {:?}", path))?;
+    Ok(true)
+}
+
+fn repoint_plist_value(value: &mut plist::Value, old_paths: &[&PathBuf], new_path: &Path) -> bool {
+    match value {
+        plist::Value::String(text) => match repointed_path(text, old_paths, new_path) {
+            Some(repointed) => {
+                *text = repointed;
+                true
+            }
+            None => false,
+        },
+        plist::Value::Dictionary(dict) => dict.values_mut()
+            .fold(false, |changed, v| repoint_plist_value(v, old_paths, new_path) | changed),
+        plist::Value::Array(items) => items.iter_mut()
+            .fold(false, |changed, v| repoint_plist_value(v, old_paths, new_path) | changed),
+        _ => false,
+    }
+}
+
+/// Rewrites matching values of `key=value` lines, leaving comments and other lines as they are.
+fn repoint_ini(path: &Path, old_paths: &[&PathBuf], new_path: &Path) -> Result<bool> {
+    let content = fs::read_to_string(path)
+        .context(format!("Failed to read preference file: {:?}", path))?;
+    
+    let mut changed = false;
+    let lines: Vec<String> = content.lines()
+        .map(|line| {
+            let is_comment = line.trim_start().starts_with([';', '#']);
+            if let (false, Some((key, value))) = (is_comment, line.split_once('=')) {
+                let old_value = value.trim().trim_matches('"');
+                if let Some(repointed) = repointed_path(old_value, old_paths, new_path) {
+                    changed = true;
+                    return format!("{}={}", key, value.replacen(old_value, &repointed, 1));
+                }
+            }
+            line.to_string()
+        })
+        .collect();
+    
+    if !changed {
+        return Ok(false);
+    }
+    
+    let line_ending = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut updated = lines.join(line_ending);
+    if content.ends_with('\n') {
+        updated.push_str(line_ending);
+    }
+    write_atomic(path, updated.as_bytes())
+        .context(format!("Failed to write preference file: {:?}", path))?;
+    Ok(true)
+}
+
+/// If `value` is one of `old_paths` or inside one, returns the same location under `new_path`.
+fn repointed_path(value: &str, old_paths: &[&PathBuf], new_path: &Path) -> Option<String> {
+    let value_path = Path::new(value);
+    if value.is_empty() || !value_path.is_absolute() {
+        return None;
+    }
+    
+    let normalized = normalize_path_for_comparison(value_path);
+    old_paths.iter()
+        .map(|old| normalize_path_for_comparison(old))
+        .find(|old| normalized.starts_with(old))
+        .map(|old| {
+            // Keep the original spelling of whatever is below the old location
+            let rest: PathBuf = value_path.components()
+                .filter(|c| !matches!(c, std::path::Component::CurDir))
+                .skip(old.components().count())
+                .collect();
+            if rest.as_os_str().is_empty() {
+                new_path.to_string_lossy().into_owned()
+            } else {
+                new_path.join(rest).to_string_lossy().into_owned()
+            }
+        })
+}
+
+/// Remove orphaned files previously reported by `detect_orphaned_files()`.
+/// Only the paths passed in are touched, so the user can confirm the list first.
+/// Paths that now belong to an installed plugin are re-checked and skipped.
+/// Returns the paths that were removed (or would be removed in dry-run mode).
+pub fn clean_orphaned_files(paths: &[PathBuf], dry_run: bool, to_trash: bool) -> Result<Vec<PathBuf>> {
+    let installed = scan_installed()?;
+    let mut known_paths = Vec::new();
+    for plugin in &installed {
+        known_paths.push(normalize_path_for_comparison(&plugin.install_path));
+        known_paths.extend(plugin.related_paths.all_paths().map(|p| normalize_path_for_comparison(p)));
+    }
+    
+    let mut cleaned = Vec::new();
+    
+    for path in paths {
+        // Refuse paths that are (or contain, or live inside) an installed plugin's files
+        let normalized = normalize_path_for_comparison(path);
+        if known_paths.iter().any(|known| known.starts_with(&normalized) || normalized.starts_with(known)) {
+            eprintln!("Warning: Skipping {:?}: it belongs to an installed plugin", path);
+            continue;
+        }
+        
+        if !path.exists() {
+            continue;
+        }
+        
+        if dry_run {
+            cleaned.push(path.clone());
+            continue;
+        }
+        
+        let result = if to_trash {
+            trash_path(path)
+        } else {
+            delete_path(path)
+        };
+        
+        match result {
+            Ok(_) => cleaned.push(path.clone()),
+            Err(e) => eprintln!("Warning: Failed to remove {:?}: {}", path, e),
+        }
+    }
+    
+    Ok(cleaned)
+}
+
+/// Find directories left empty under `roots` (e.g., vendor folders and preset
+/// folders after an uninstall) and, unless `dry_run`, remove them bottom-up.
+/// A folder holding only empty folders or OS cruft (see `is_skipped_file()`)
+/// counts as empty. The roots themselves are never removed, and plugin bundles
+/// are never looked into.
+/// Returns the removed (or, in dry-run mode, removable) directories, deepest first.
+pub fn prune_empty_dirs(roots: &[PathBuf], dry_run: bool) -> Result<Vec<PathBuf>> {
+    let normalized_roots: HashSet<PathBuf> = roots.iter().map(|root| normalize_path_for_comparison(root)).collect();
+    let mut pruned = Vec::new();
+    let mut visited = HashSet::new();
+    for root in roots {
+        if root.is_dir() && visited.insert(normalize_path_for_comparison(root)) {
+            // The roots stay even if they end up empty
+            prune_subdirs(root, &normalized_roots, dry_run, &mut pruned);
+        }
+    }
+    Ok(pruned)
+}
+
+/// Prunes the empty subdirectories of `dir`. If `dir` is then left (or, in dry-run
+/// mode, would be left) with nothing but OS cruft, returns those cruft files.
+fn prune_subdirs(dir: &Path, roots: &HashSet<PathBuf>, dry_run: bool, pruned: &mut Vec<PathBuf>) -> Option<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Warning: Failed to read directory {:?}: {}", dir, e);
+            return None;
+        }
+    };
+    
+    let mut empty = true;
+    let mut cruft = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        
+        if is_dir && roots.contains(&normalize_path_for_comparison(&path)) {
+            // A nested root is pruned on its own and never removed
+            empty = false;
+        } else if is_dir && !is_plugin_bundle(&path) {
+            match prune_subdirs(&path, roots, dry_run, pruned) {
+                Some(_) if dry_run => pruned.push(path),
+                Some(cruft) => match remove_empty_dir(&path, &cruft) {
+                    Ok(_) => pruned.push(path),
+                    Err(e) => {
+                        eprintln!("Warning: Failed to remove empty directory {:?}: {}", path, e);
+                        empty = false;
+                    }
+                },
+                None => empty = false,
+            }
+        } else if !is_dir && is_skipped_file(&path) {
+            cruft.push(path);
+        } else {
+            empty = false;
+        }
+    }
+    
+    empty.then_some(cruft)
+}
+
+/// Removes a directory that holds only the given cruft files. Fails, keeping the
+/// directory, if anything else has appeared in it since it was checked.
+fn remove_empty_dir(dir: &Path, cruft: &[PathBuf]) -> Result<()> {
+    for file in cruft {
+        fs::remove_file(file)
+            .context(format!("Failed to remove file: {:?}", file))?;
+    }
+    fs::remove_dir(dir)
+        .context(format!("Failed to remove directory: {:?}", dir))
+}
+
+/// Renames plugins whose extension has non-canonical case (e.g., `Plugin.VST3` to
+/// `Plugin.vst3`). Hosts on case-insensitive volumes load either spelling, but
+/// some tools match extensions exactly. In `dry_run` mode nothing is renamed.
+/// Returns each (old, new) path renamed (or, in dry-run mode, to be renamed). A
+/// plugin whose canonical name is already taken by another entry, which can only
+/// happen on a case-sensitive volume, is skipped with a warning.
+pub fn normalize_extensions(dry_run: bool) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let mut renamed = Vec::new();
+    
+    for (path, canonical) in detect_miscased_extensions()? {
+        if canonical_name_taken(&canonical) {
+            eprintln!("Warning: Not renaming {:?}: {:?} already exists", path, canonical);
+            continue;
+        }
+        if !dry_run {
+            if let Err(e) = fs::rename(&path, &canonical) {
+                eprintln!("Warning: Failed to rename {:?} to {:?}: {}", path, canonical, e);
+                continue;
+            }
+        }
+        renamed.push((path, canonical));
+    }
+    
+    Ok(renamed)
+}
+
+/// Returns true if the folder holds an entry spelled exactly like `path`'s name.
+/// `path.exists()` can't tell: on a case-insensitive volume it finds the
+/// differently-cased plugin itself.
+fn canonical_name_taken(path: &Path) -> bool {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return false;
+    };
+    fs::read_dir(parent)
+        .map(|entries| entries.flatten().any(|entry| entry.file_name() == name))
+        .unwrap_or(false)
+}
+
+// Helper functions
+
+/// Copy a file or directory into a backup directory. See `copy_file_to_package()`.
+fn copy_file_to_backup(source: &Path, backup_dir: &Path, used_names: &mut HashSet<PathBuf>) -> Result<Option<PathBuf>> {
+    copy_file_to_package(source, backup_dir, used_names, false)
+}
+
+/// Copy a file or directory into a backup or export folder under its own name, or
+/// with a numeric suffix if another file of the package already took that name
+/// (`used_names` tracks them). With `skip_unchanged`, files already present there
+/// as complete copies are left alone.
+fn copy_file_to_package(source: &Path, package_dir: &Path, used_names: &mut HashSet<PathBuf>, skip_unchanged: bool) -> Result<Option<PathBuf>> {
+    if !source.exists() {
+        return Ok(None);
+    }
+    
+    let dest = package_dir.join(unique_entry_name(source, Path::new(""), used_names)?);
+    
+    if source.is_dir() {
+        copy_directory_with(source, &dest, skip_unchanged)?;
+    } else if !(skip_unchanged && is_complete_copy(source, &dest)) {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        copy_file_preserving(source, &dest)?;
+    }
+    
+    Ok(Some(dest))
+}
+
+/// Recursively copy a directory, preserving permissions and timestamps.
+fn copy_directory_recursive(source: &Path, dest: &Path) -> Result<()> {
+    copy_directory_with(source, dest, false)
+}
+
+fn copy_directory_with(source: &Path, dest: &Path, skip_unchanged: bool) -> Result<()> {
+    fs::create_dir_all(dest)?;
+    
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let path = entry.path();
+        let dest_path = dest.join(entry.file_name());
+        if is_skipped_file(&path) {
+            continue;
+        }
+        
+        // Links are copied as links: following them would duplicate a bundle's
+        // `Versions/Current` and never end for a link to a parent folder
+        if entry.file_type()?.is_symlink() {
+            copy_symlink(&path, &dest_path)?;
+        } else if path.is_dir() {
+            copy_directory_with(&path, &dest_path, skip_unchanged)?;
+        } else if !(skip_unchanged && is_complete_copy(&path, &dest_path)) {
+            copy_file_preserving(&path, &dest_path)?;
+        }
+    }
+    
+    // Set the directory's attributes last; copying children updates its mtime
+    copy_file_attributes(source, dest)?;
+    
+    Ok(())
+}
+
+/// Recreate the symlink `source` at `dest` with the same target, replacing
+/// whatever is at `dest`, and give it the link's own timestamps.
+fn copy_symlink(source: &Path, dest: &Path) -> Result<()> {
+    let target = fs::read_link(source)
+        .context(format!("Failed to read symlink: {:?}", source))?;
+    
+    match fs::symlink_metadata(dest) {
+        Ok(existing) if existing.is_symlink() => {
+            if fs::read_link(dest).is_ok_and(|existing| existing == target) {
+                return Ok(());
+            }
+            remove_symlink(dest)?;
+        }
+        Ok(_) => delete_path(dest)?,
+        Err(_) => {}
+    }
+    create_symlink(&target, dest)?;
+    
+    let metadata = fs::symlink_metadata(source)
+        .context(format!("Failed to read metadata: {:?}", source))?;
+    let atime = filetime::FileTime::from_last_access_time(&metadata);
+    let mtime = filetime::FileTime::from_last_modification_time(&metadata);
+    if let Err(e) = filetime::set_symlink_file_times(dest, atime, mtime) {
+        eprintln!("Warning: Failed to preserve timestamps on {:?}: {}", dest, e);
+    }
+    
+    Ok(())
+}
+
+/// Returns true if `dest` is a finished copy of `source`: same size and
+/// modification time. Copies get the source's time only once fully written, so a
+/// file cut short by an interruption never matches.
+fn is_complete_copy(source: &Path, dest: &Path) -> bool {
+    match (fs::metadata(source), fs::metadata(dest)) {
+        (Ok(source), Ok(dest)) => dest.is_file()
+            && source.len() == dest.len()
+            && filetime::FileTime::from_last_modification_time(&source)
+                == filetime::FileTime::from_last_modification_time(&dest),
+        _ => false,
+    }
+}
+
+/// Copy a single file, preserving permissions (including the executable bit) and timestamps.
+fn copy_file_preserving(source: &Path, dest: &Path) -> Result<()> {
+    fs::copy(source, dest)
+        .context(format!("Failed to copy {:?} to {:?}", source, dest))?;
+    copy_file_attributes(source, dest)
+}
+
+/// Apply the source's permissions and access/modification times to the destination.
+fn copy_file_attributes(source: &Path, dest: &Path) -> Result<()> {
+    let metadata = fs::metadata(source)
+        .context(format!("Failed to read metadata: {:?}", source))?;
+    
+    fs::set_permissions(dest, metadata.permissions())
+        .context(format!("Failed to set permissions: {:?}", dest))?;
+    
+    let atime = filetime::FileTime::from_last_access_time(&metadata);
+    let mtime = filetime::FileTime::from_last_modification_time(&metadata);
+    if let Err(e) = filetime::set_file_times(dest, atime, mtime) {
+        // Some filesystems (e.g., network shares) don't allow setting times
+        eprintln!("Warning: Failed to preserve timestamps on {:?}: {}", dest, e);
+    }
+    
+    Ok(())
+}
+
+/// Copy the extended attributes of `source` onto `dest`, and of everything inside
+/// `source` onto the same paths inside `dest`. Paths missing from `dest` are skipped.
+#[cfg(target_os = "macos")]
+fn copy_extended_attributes(source: &Path, dest: &Path) -> Result<()> {
+    if fs::symlink_metadata(dest).is_err() {
+        return Ok(());
+    }
+    
+    let names = xattr::list(source)
+        .context(format!("Failed to list extended attributes: {:?}", source))?;
+    for name in names {
+        if let Some(value) = xattr::get(source, &name)? {
+            xattr::set(dest, &name, &value)
+                .context(format!("Failed to set extended attribute {:?} on {:?}", name, dest))?;
+        }
+    }
+    
+    if fs::symlink_metadata(source).is_ok_and(|m| m.is_dir()) {
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_extended_attributes(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+    }
+    
+    Ok(())
+}
+
+/// Extended attributes are only preserved on macOS
+#[cfg(not(target_os = "macos"))]
+fn copy_extended_attributes(_source: &Path, _dest: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Move a file or directory, copying across volumes when a rename isn't possible.
+/// Other rename failures (permissions, an existing destination) are returned as is.
+fn move_path(source: &Path, dest: &Path) -> Result<()> {
+    match fs::rename(source, dest) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => copy_then_delete(source, dest),
+        Err(e) => Err(anyhow::Error::new(e).context(format!("Failed to move {:?} to {:?}", source, dest))),
+    }
+}
+
+/// Move across volumes: copy, then delete the original only once the copy is complete.
+/// On failure the copy is removed, after putting back anything already deleted
+/// from the original, so the source is left as it was.
+fn copy_then_delete(source: &Path, dest: &Path) -> Result<()> {
+    let copied = if source.is_dir() {
+        copy_directory_recursive(source, dest)
+    } else {
+        copy_file_preserving(source, dest)
+    };
+    if let Err(e) = copied {
+        let _ = delete_path(dest);
+        return Err(e.context(format!("Failed to copy {:?} to {:?}", source, dest)));
+    }
+    
+    let Err(e) = delete_path(source) else {
+        return Ok(());
+    };
+    // Removing a folder can stop partway; files still in place are skipped
+    let restored = if dest.is_dir() {
+        copy_directory_with(dest, source, true)
+    } else {
+        Ok(())
+    };
+    match restored {
+        Ok(()) => {
+            if let Err(cleanup) = delete_path(dest) {
+                eprintln!("Warning: Failed to remove partial move {:?}: {}", dest, cleanup);
+            }
+        }
+        Err(restore) => {
+            eprintln!("Warning: Failed to restore {:?} from {:?}, keeping the copy: {}", source, dest, restore);
+        }
+    }
+    Err(e.context(format!("Failed to move {:?} to {:?}", source, dest)))
+}
+
+/// Create a symlink at `link` pointing to `target`.
+fn create_symlink(target: &Path, link: &Path) -> Result<()> {
+    #[cfg(unix)]
+    let result = std::os::unix::fs::symlink(target, link);
+    
+    #[cfg(windows)]
+    let result = if target.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    };
+    
+    result.context(format!("Failed to create symlink {:?} -> {:?}", link, target))
+}
+
+/// Remove a symlink without touching what it points to.
+fn remove_symlink(link: &Path) -> Result<()> {
+    // Windows directory symlinks are removed like directories
+    #[cfg(windows)]
+    if link.is_dir() {
+        return fs::remove_dir(link)
+            .context(format!("Failed to remove symlink: {:?}", link));
+    }
+    
+    fs::remove_file(link)
+        .context(format!("Failed to remove symlink: {:?}", link))
+}
+
+/// Delete a file or directory.
+fn delete_path(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+            .context(format!("Failed to remove directory: {:?}", path))?;
+    } else {
+        fs::remove_file(path)
+            .context(format!("Failed to remove file: {:?}", path))?;
+    }
+    
+    Ok(())
+}
+
+/// Move a file or directory to the system trash (Trash on macOS, Recycle Bin on Windows).
+fn trash_path(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    
+    trash::delete(path)
+        .context(format!("Failed to move to trash: {:?}", path))?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{installed_plugin, isolated_home};
+    use crate::{Confidence, PluginFormat, RelatedPath};
+
+    #[test]
+    fn backup_keeps_files_with_the_same_name_apart() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Plugins/Synth.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "bundle").unwrap();
+        for bank in ["Bank A", "Bank B"] {
+            let presets = dir.path().join("Presets").join(bank);
+            fs::create_dir_all(&presets).unwrap();
+            fs::write(presets.join("Init.preset"), bank).unwrap();
+        }
+        let mut plugin = installed_plugin("Synth", &bundle, PluginFormat::LV2);
+        plugin.related_paths.preset_locations = vec![RelatedPath::new(dir.path().join("Presets"), Confidence::High)];
+        let backups = dir.path().join("Backups");
+        fs::create_dir(&backups).unwrap();
+
+        let backup = backup_plugin(&plugin, &backups).unwrap();
+
+        let manifest = Manifest::read(&backup.join(BACKUP_MANIFEST_FILE)).unwrap();
+        let mut contents: Vec<String> = manifest.files.iter()
+            .filter(|file| file.original_path.ends_with("Init.preset"))
+            .map(|file| fs::read_to_string(backup.join(&file.backup_name)).unwrap())
+            .collect();
+        contents.sort();
+        assert_eq!(contents, ["Bank A", "Bank B"]);
+    }
+
+    #[test]
+    fn restore_puts_back_backed_up_files() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Plugins/Synth.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "original").unwrap();
+        let plugin = installed_plugin("Synth", &bundle, PluginFormat::LV2);
+        let backups = dir.path().join("Backups");
+        fs::create_dir(&backups).unwrap();
+        let backup = backup_plugin(&plugin, &backups).unwrap();
+
+        fs::write(bundle.join("manifest.ttl"), "updated").unwrap();
+        fs::write(bundle.join("extra.ttl"), "added later").unwrap();
+        let restored = restore_backup(&backup).unwrap();
+
+        assert_eq!(restored, vec![bundle.clone()]);
+        assert_eq!(fs::read_to_string(bundle.join("manifest.ttl")).unwrap(), "original");
+        assert!(!bundle.join("extra.ttl").exists());
+        assert_eq!(fs::read_dir(bundle.parent().unwrap()).unwrap().count(), 1, "temporary copies left behind");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_restore_leaves_installed_files_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("backup/Synth.lv2");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("manifest.ttl"), "backup").unwrap();
+        // A socket can't be opened, so it can't be copied
+        let _socket = std::os::unix::net::UnixListener::bind(source.join("plugin.sock")).unwrap();
+        let dest = dir.path().join("Plugins/Synth.lv2");
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(dest.join("manifest.ttl"), "installed").unwrap();
+
+        assert!(restore_path(&source, &dest).is_err());
+
+        assert_eq!(fs::read_to_string(dest.join("manifest.ttl")).unwrap(), "installed");
+        assert_eq!(fs::read_dir(dest.parent().unwrap()).unwrap().count(), 1, "temporary copies left behind");
+    }
+
+    /// Exports a plugin with one preset folder and returns the package and the
+    /// temporary folder holding everything.
+    /// An installed LV2 synth under `dir` with one preset folder.
+    fn synth_plugin(dir: &Path) -> InstalledPlugin {
+        let bundle = dir.join("Plugins/Synth.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "").unwrap();
+        let presets = dir.join("Presets/Acme/Synth");
+        fs::create_dir_all(&presets).unwrap();
+        fs::write(presets.join("Init.preset"), "init").unwrap();
+        let mut plugin = installed_plugin("Synth", &bundle, PluginFormat::LV2);
+        plugin.plugin.author = Some(String::from("Acme"));
+        plugin.related_paths.preset_locations = vec![RelatedPath::new(presets, Confidence::High)];
+        plugin
+    }
+
+    fn export_synth() -> (tempfile::TempDir, PathBuf) {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = synth_plugin(dir.path());
+
+        let package = export_plugin(&plugin, &dir.path().join("Exports")).unwrap();
+        (dir, package)
+    }
+
+    fn edit_manifest(package: &Path, edit: impl FnOnce(&mut Manifest)) -> Manifest {
+        let path = package.join(EXPORT_MANIFEST_FILE);
+        let mut manifest = Manifest::read(&path).unwrap();
+        edit(&mut manifest);
+        manifest.write(&path).unwrap();
+        manifest
+    }
+
+    #[test]
+    fn import_places_files_in_standard_folders() {
+        let (dir, package) = export_synth();
+        let manifest = Manifest::read(&package.join(EXPORT_MANIFEST_FILE)).unwrap();
+        let install_dir = dir.path().join("Imported");
+
+        let result = install_package(&package, &manifest, &install_dir, false).unwrap();
+
+        assert!(result.is_complete(), "{:?}", result.targets);
+        assert_eq!(result.plugin.install_path, install_dir.join("Synth.lv2"));
+        let preset = related_install_dir(RelatedCategory::Presets).unwrap().join("Acme/Synth/Init.preset");
+        assert_eq!(fs::read_to_string(preset).unwrap(), "init");
+    }
+
+    #[test]
+    fn import_rejects_files_outside_the_package() {
+        let (dir, package) = export_synth();
+        let outside = dir.path().join("outside.txt");
+        fs::write(&outside, "secret").unwrap();
+        for name in [PathBuf::from("../../outside.txt"), outside.clone()] {
+            let manifest = edit_manifest(&package, |manifest| manifest.files[0].backup_name = name.clone());
+            let install_dir = dir.path().join("Imported");
+
+            assert!(install_package(&package, &manifest, &install_dir, false).is_err(), "{:?}", name);
+            assert!(!install_dir.exists());
+        }
+    }
+
+    #[test]
+    fn import_ignores_absolute_related_paths() {
+        let (dir, package) = export_synth();
+        let target = dir.path().join("elsewhere/Init.preset");
+        let manifest = edit_manifest(&package, |manifest| {
+            for file in manifest.files.iter_mut().filter(|file| file.category.is_some()) {
+                file.original_path = target.clone();
+                file.relative_path = Some(PathBuf::from("/etc/Init.preset"));
+            }
+        });
+
+        let result = install_package(&package, &manifest, &dir.path().join("Imported"), false).unwrap();
+
+        assert!(!target.exists());
+        assert!(result.targets.iter().any(|(_, outcome)| matches!(outcome, ImportOutcome::Failed(_))));
+    }
+
+    #[test]
+    fn import_rejects_unknown_manifest_version() {
+        let (_dir, package) = export_synth();
+        let manifest_path = package.join(EXPORT_MANIFEST_FILE);
+        let content = fs::read_to_string(&manifest_path).unwrap()
+            .replace("\"manifest_version\": 1", "\"manifest_version\": 999");
+        fs::write(&manifest_path, content).unwrap();
+
+        let error = import_plugin(&package, false).unwrap_err();
+        assert!(error.to_string().contains("version 999"), "{}", error);
+    }
+
+    #[test]
+    fn uninstalling_shell_members_removes_the_shell_once() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let shell = dir.path().join("Plugins/WaveShell.vst3");
+        fs::create_dir_all(shell.parent().unwrap()).unwrap();
+        fs::write(&shell, "shell").unwrap();
+        let members: Vec<InstalledPlugin> = ["Member A", "Member B"].iter()
+            .map(|name| {
+                let preferences = dir.path().join(format!("{}.plist", name));
+                fs::write(&preferences, name).unwrap();
+                let mut member = installed_plugin(name, &shell, PluginFormat::VST3);
+                member.shell = Some(String::from("WaveShell"));
+                member.related_paths.preference_files = vec![RelatedPath::new(preferences, Confidence::Medium)];
+                member
+            })
+            .collect();
+        let selected: Vec<&InstalledPlugin> = members.iter().collect();
+
+        let report = uninstall_plugins(&selected, false).unwrap();
+
+        assert_eq!(report.len(), 2);
+        assert!(report[0].1.contains(&shell));
+        assert_eq!(report[1].1, vec![dir.path().join("Member B.plist")]);
+        assert!(!shell.exists());
+        assert!(!dir.path().join("Member A.plist").exists());
+        assert!(!dir.path().join("Member B.plist").exists());
+    }
+
+    #[test]
+    fn moves_copy_only_across_volumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("Samples");
+        fs::create_dir_all(source.join("Kicks")).unwrap();
+        fs::write(source.join("Kicks/kick.wav"), "kick").unwrap();
+        let dest = dir.path().join("Moved");
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(dest.join("other.wav"), "other").unwrap();
+
+        // Renaming onto a non-empty folder fails on the same volume; nothing is copied
+        assert!(move_path(&source, &dest).is_err());
+        assert!(source.join("Kicks/kick.wav").is_file());
+        assert!(!dest.join("Kicks").exists());
+
+        let dest = dir.path().join("Volume/Samples");
+        fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        copy_then_delete(&source, &dest).unwrap();
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(dest.join("Kicks/kick.wav")).unwrap(), "kick");
+    }
+
+    #[test]
+    fn relocated_backups_still_restore() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Plugins/Synth.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "original").unwrap();
+        let plugin = installed_plugin("Synth", &bundle, PluginFormat::LV2);
+        let backups = dir.path().join("Backups");
+        fs::create_dir(&backups).unwrap();
+        let backup = backup_plugin(&plugin, &backups).unwrap();
+
+        assert!(relocate_backup(&backup, &backup.join("Nested")).is_err());
+        assert!(backup.is_dir());
+
+        let moved = relocate_backup(&backup, &dir.path().join("Archive")).unwrap();
+        assert!(!backup.exists());
+        fs::write(bundle.join("manifest.ttl"), "updated").unwrap();
+        assert_eq!(restore_backup(&moved).unwrap(), vec![bundle.clone()]);
+        assert_eq!(fs::read_to_string(bundle.join("manifest.ttl")).unwrap(), "original");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exports_links_inside_bundles_as_links() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Synth.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "").unwrap();
+        create_symlink(Path::new(".."), &bundle.join("parent")).unwrap();
+        let plugin = installed_plugin("Synth", &bundle, PluginFormat::LV2);
+
+        let mut archive = std::io::Cursor::new(Vec::new());
+        let options = ExportOptions { include_related: false, ..ExportOptions::default() };
+        export_plugin_to_writer(&plugin, &options, &mut archive).unwrap();
+
+        let mut zip = zip::ZipArchive::new(archive).unwrap();
+        let link = zip.by_name("Plugin/Synth.lv2/parent").unwrap();
+        assert!(link.is_symlink());
+    }
+
+    #[test]
+    fn prunes_only_empty_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("Presets");
+        fs::create_dir_all(root.join("Vendor/Synth")).unwrap();
+        fs::write(root.join("Vendor/.DS_Store"), "").unwrap();
+        fs::create_dir_all(root.join("Other")).unwrap();
+        fs::write(root.join("Other/Bass.fxp"), "").unwrap();
+        fs::create_dir_all(root.join("Empty.lv2")).unwrap();
+
+        let removable = prune_empty_dirs(std::slice::from_ref(&root), true).unwrap();
+        assert_eq!(removable, [root.join("Vendor/Synth"), root.join("Vendor")]);
+        assert!(root.join("Vendor/Synth").is_dir());
+
+        assert_eq!(prune_empty_dirs(std::slice::from_ref(&root), false).unwrap(), removable);
+        assert!(!root.join("Vendor").exists());
+        assert!(root.join("Other/Bass.fxp").is_file());
+        assert!(root.join("Empty.lv2").is_dir(), "plugin bundles are left alone");
+
+        // The root stays even once empty
+        fs::remove_dir_all(root.join("Other")).unwrap();
+        fs::remove_dir_all(root.join("Empty.lv2")).unwrap();
+        assert!(prune_empty_dirs(std::slice::from_ref(&root), false).unwrap().is_empty());
+        assert!(root.is_dir());
+    }
+
+    #[test]
+    fn normalizes_miscased_extensions() {
+        let home = isolated_home();
+        let (folder, name) = if cfg!(target_os = "macos") {
+            (home.join("Library/Audio/Plug-Ins/VST3"), "Normalize.VST3")
+        } else if cfg!(target_os = "windows") {
+            (home.join("AppData/Roaming/VST3"), "Normalize.VST3")
+        } else {
+            (home.join(".lv2"), "Normalize.LV2")
+        };
+        let plugin = folder.join(name);
+        fs::create_dir_all(&plugin).unwrap();
+        let canonical = plugin.with_extension(plugin.extension().unwrap().to_ascii_lowercase());
+        let planned = (plugin.clone(), canonical.clone());
+
+        assert!(normalize_extensions(true).unwrap().contains(&planned));
+        assert!(canonical_name_taken(&plugin));
+        assert!(normalize_extensions(false).unwrap().contains(&planned));
+        assert!(canonical_name_taken(&canonical));
+        assert!(!canonical_name_taken(&plugin));
+    }
+
+    #[test]
+    fn exports_to_an_in_memory_zip() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = synth_plugin(dir.path());
+
+        let mut archive = std::io::Cursor::new(Vec::new());
+        export_plugin_to_writer(&plugin, &ExportOptions::default(), &mut archive).unwrap();
+
+        let mut zip = zip::ZipArchive::new(archive).unwrap();
+        let mut names: Vec<&str> = zip.file_names().collect();
+        names.sort();
+        assert_eq!(names, [
+            "Plugin/Synth.lv2/",
+            "Plugin/Synth.lv2/manifest.ttl",
+            "Presets/Synth/",
+            "Presets/Synth/Init.preset",
+            EXPORT_MANIFEST_FILE,
+        ]);
+        let mut preset = String::new();
+        zip.by_name("Presets/Synth/Init.preset").unwrap().read_to_string(&mut preset).unwrap();
+        assert_eq!(preset, "init");
+        let manifest: Manifest = serde_json::from_reader(zip.by_name(EXPORT_MANIFEST_FILE).unwrap()).unwrap();
+        assert_eq!(manifest.files.len(), 2);
+    }
+
+    #[test]
+    fn refuses_operations_larger_than_free_space() {
+        let dir = tempfile::tempdir().unwrap();
+        // No volume has u64::MAX bytes free; a destination that doesn't exist yet
+        // is checked on its nearest existing parent
+        let dest = dir.path().join("Backups/New");
+        assert!(has_space_for(&dest, 0).unwrap());
+        assert!(!has_space_for(&dest, u64::MAX).unwrap());
+        let error = ensure_space_for(&dest, u64::MAX).unwrap_err();
+        assert!(error.to_string().starts_with("Not enough free space"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn uninstalling_from_unwritable_folder_needs_elevation() {
+        use std::os::unix::fs::PermissionsExt;
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let system = dir.path().join("System");
+        let bundle = system.join("Synth.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "bundle").unwrap();
+        fs::set_permissions(&system, fs::Permissions::from_mode(0o555)).unwrap();
+        let plugin = installed_plugin("Synth", &bundle, PluginFormat::LV2);
+
+        // Permissions aren't enforced for root
+        if can_modify_dir(&system) {
+            fs::set_permissions(&system, fs::Permissions::from_mode(0o755)).unwrap();
+            return;
+        }
+        let error = uninstall_plugin(&plugin, false).unwrap_err();
+        fs::set_permissions(&system, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let needs_elevation = error.downcast_ref::<NeedsElevation>().unwrap();
+        assert_eq!(needs_elevation.paths, vec![bundle.clone()]);
+        assert!(bundle.join("manifest.ttl").exists());
+    }
+
+    #[test]
+    fn rerunning_an_interrupted_export_copies_only_what_is_missing() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = synth_plugin(dir.path());
+        let source = plugin.install_path.join("manifest.ttl");
+        fs::write(&source, "bundle").unwrap();
+        let export_dir = dir.path().join("Exports");
+
+        // Interrupted right after the first file is copied
+        let interrupted = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            export_plugin_with_progress(&plugin, &export_dir, |_| panic!("interrupted"))
+        }));
+        assert!(interrupted.is_err());
+        let package = export_dir.join("Synth_export");
+        assert!(package.join(EXPORT_PARTIAL_MANIFEST_FILE).is_file());
+        assert!(!package.join(EXPORT_MANIFEST_FILE).exists());
+
+        // Mark the copy so a second copy would show: same size and time, other bytes
+        let copy = package.join("Synth.lv2/manifest.ttl");
+        fs::write(&copy, "BUNDLE").unwrap();
+        let modified = filetime::FileTime::from_last_modification_time(&fs::metadata(&source).unwrap());
+        filetime::set_file_mtime(&copy, modified).unwrap();
+
+        let mut copied = 0;
+        assert_eq!(export_plugin_with_progress(&plugin, &export_dir, |_| copied += 1).unwrap(), package);
+
+        assert_eq!(copied, 2);
+        assert_eq!(fs::read_to_string(&copy).unwrap(), "BUNDLE");
+        assert_eq!(fs::read_to_string(package.join("Init.preset")).unwrap(), "init");
+        assert_eq!(Manifest::read(&package.join(EXPORT_MANIFEST_FILE)).unwrap().files.len(), 2);
+        assert!(!package.join(EXPORT_PARTIAL_MANIFEST_FILE).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn backups_restore_links_as_links() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Plugins/Synth.vst3");
+        let version = bundle.join("Contents/Versions/A");
+        fs::create_dir_all(&version).unwrap();
+        fs::write(version.join("Synth"), "binary").unwrap();
+        create_symlink(Path::new("A"), &bundle.join("Contents/Versions/Current")).unwrap();
+        let plugin = installed_plugin("Synth", &bundle, PluginFormat::VST3);
+        let backups = dir.path().join("Backups");
+        fs::create_dir(&backups).unwrap();
+
+        let backup = backup_plugin(&plugin, &backups).unwrap();
+        fs::remove_dir_all(&bundle).unwrap();
+        restore_backup(&backup).unwrap();
+
+        let current = bundle.join("Contents/Versions/Current");
+        assert!(fs::symlink_metadata(&current).unwrap().is_symlink());
+        assert_eq!(fs::read_link(&current).unwrap(), Path::new("A"));
+        assert_eq!(fs::read_to_string(current.join("Synth")).unwrap(), "binary");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn folder_exports_keep_links_to_parent_folders() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Synth.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "").unwrap();
+        create_symlink(Path::new(".."), &bundle.join("parent")).unwrap();
+        let plugin = installed_plugin("Synth", &bundle, PluginFormat::LV2);
+
+        let package = export_plugin(&plugin, &dir.path().join("Exports")).unwrap();
+
+        let link = package.join("Synth.lv2/parent");
+        assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(fs::read_link(&link).unwrap(), Path::new(".."));
+    }
+
+    #[test]
+    fn folder_copies_leave_out_cruft() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Synth.lv2");
+        fs::create_dir_all(bundle.join(".git")).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "").unwrap();
+        fs::write(bundle.join(".DS_Store"), "").unwrap();
+        fs::write(bundle.join(".git/HEAD"), "").unwrap();
+        let copy = dir.path().join("Copy.lv2");
+
+        copy_directory_recursive(&bundle, &copy).unwrap();
+
+        let names: Vec<_> = fs::read_dir(&copy).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(names, ["manifest.ttl"]);
+    }
+
+    #[test]
+    fn comparison_refuses_entries_outside_the_backup() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Plugins/Synth.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "").unwrap();
+        let plugin = installed_plugin("Synth", &bundle, PluginFormat::LV2);
+        let backups = dir.path().join("Backups");
+        fs::create_dir(&backups).unwrap();
+        let backup = backup_plugin(&plugin, &backups).unwrap();
+        assert!(compare_to_backup(&plugin, &backup).is_ok());
+
+        let manifest_path = backup.join(BACKUP_MANIFEST_FILE);
+        let mut manifest = Manifest::read(&manifest_path).unwrap();
+        manifest.files[0].backup_name = PathBuf::from("../../Plugins/Synth.lv2");
+        manifest.write(&manifest_path).unwrap();
+
+        let error = compare_to_backup(&plugin, &backup).unwrap_err();
+        assert!(error.to_string().contains("outside the package"));
+    }
+
+    #[test]
+    fn uninstall_deletes_what_the_dry_run_lists() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = synth_plugin(dir.path());
+
+        let planned = uninstall_plugin(&plugin, true).unwrap();
+        let mut calls = 0;
+        let deleted = uninstall_plugin_with_progress(&plugin, false, |_| calls += 1).unwrap();
+
+        assert_eq!(deleted, planned);
+        assert_eq!(calls, planned.len());
+        assert!(!plugin.install_path.exists());
+    }
+
+    #[test]
+    fn refuses_backups_inside_plugin_folders() {
+        let home = isolated_home();
+        let folder = if cfg!(target_os = "macos") {
+            home.join("Library/Audio/Plug-Ins/VST3")
+        } else if cfg!(target_os = "windows") {
+            home.join("AppData/Roaming/VST3")
+        } else {
+            home.join(".lv2")
+        };
+        let bundle = folder.join("Backed Up.vst3");
+        fs::create_dir_all(&bundle).unwrap();
+        let plugin = installed_plugin("Backed Up", &bundle, PluginFormat::VST3);
+
+        let error = backup_plugin(&plugin, &bundle.join("Backups")).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<BackupDestinationError>(),
+            Some(BackupDestinationError::InsidePluginFolder { .. })
+        ));
+        assert!(!bundle.join("Backups").exists());
+
+        let outside = tempfile::tempdir().unwrap();
+        assert!(validate_backup_destination(outside.path()).is_ok());
+    }
+
+    #[test]
+    fn disabling_and_enabling_restores_the_original_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("VST3/Synth.vst3");
+        let file = dir.path().join("VST/Synth.so");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, "binary").unwrap();
+
+        for (original, disabled) in [
+            (&bundle, dir.path().join(format!("VST3{}/Synth.vst3", DISABLED_DIR_SUFFIX))),
+            (&file, dir.path().join(format!("VST/Synth.so.{}", DISABLED_FILE_EXTENSION))),
+        ] {
+            let mut plugin = installed_plugin("Synth", original, PluginFormat::VST2);
+
+            set_plugin_enabled(&mut plugin, false).unwrap();
+            assert!(!plugin.enabled);
+            assert_eq!(plugin.install_path, disabled);
+            assert!(disabled.exists() && !original.exists());
+
+            set_plugin_enabled(&mut plugin, true).unwrap();
+            assert!(plugin.enabled);
+            assert_eq!(&plugin.install_path, original);
+            assert!(original.exists() && !disabled.exists());
+        }
+    }
+
+    #[test]
+    fn confident_uninstall_leaves_loose_matches() {
+        isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let mut plugin = synth_plugin(dir.path());
+        let presets = plugin.related_paths.preset_locations[0].path.clone();
+        let documents = dir.path().join("Documents/Synth");
+        fs::create_dir_all(&documents).unwrap();
+        fs::write(documents.join("Song.preset"), "song").unwrap();
+        plugin.related_paths.preset_locations.push(RelatedPath::new(documents.clone(), Confidence::Low));
+        let options = UninstallOptions { min_confidence: Confidence::High };
+
+        let deleted = uninstall_plugin_with_options(&plugin, false, &options, |_| {}).unwrap();
+
+        assert_eq!(deleted, [plugin.install_path.clone(), presets.join("Init.preset")]);
+        assert!(!plugin.install_path.exists());
+        assert!(!presets.join("Init.preset").exists());
+        assert!(documents.join("Song.preset").exists());
+    }
 }
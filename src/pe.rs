@@ -0,0 +1,284 @@
+//! Minimal reader for Windows PE (DLL) files.
+//!
+//! Reads header fields and the version resource (`VS_VERSIONINFO`) directly from
+//! the file bytes. Nothing is ever loaded as code (no `LoadLibrary`), so this is
+//! safe to run on untrusted plugin binaries and works on any platform.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// COFF machine types
+pub const MACHINE_I386: u16 = 0x014c;
+pub const MACHINE_AMD64: u16 = 0x8664;
+pub const MACHINE_ARM64: u16 = 0xaa64;
+/// ARM64EC: ARM64 code that interoperates with x64
+pub const MACHINE_ARM64EC: u16 = 0xa641;
+
+/// Resource type ID of version information (RT_VERSION)
+const RT_VERSION: u32 = 16;
+
+/// Index of the resource table in the optional header's data directories
+const RESOURCE_DIRECTORY_INDEX: usize = 2;
+
+/// Reads the string table of a PE file's version resource.
+/// Keys are the standard names (e.g., "ProductName", "CompanyName", "FileVersion").
+/// Returns an empty map if the file has no version resource.
+pub fn read_version_strings(path: &Path) -> Result<HashMap<String, String>> {
+    let data = fs::read(path)
+        .context(format!("Failed to read file: {:?}", path))?;
+    let pe = PeFile::parse(&data)
+        .context(format!("Not a valid PE file: {:?}", path))?;
+
+    Ok(pe.version_strings().unwrap_or_default())
+}
+
+/// Reads the COFF machine type of a PE file (e.g., `MACHINE_AMD64`).
+/// Only the headers are read, not the whole file.
+pub fn read_machine(path: &Path) -> Result<u16> {
+    let mut header = Vec::with_capacity(4096);
+    fs::File::open(path)
+        .and_then(|file| file.take(4096).read_to_end(&mut header))
+        .context(format!("Failed to read file: {:?}", path))?;
+
+    machine(&header).context(format!("Not a valid PE file: {:?}", path))
+}
+
+/// Returns the machine field from the COFF header, given at least the file's headers.
+pub(crate) fn machine(data: &[u8]) -> Option<u16> {
+    read_u16(data, pe_header_offset(data)? + 4)
+}
+
+/// Validates the DOS stub and returns the offset of the "PE\0\0" signature.
+fn pe_header_offset(data: &[u8]) -> Option<usize> {
+    if data.get(0..2)? != b"MZ" {
+        return None;
+    }
+    let pe_offset = read_u32(data, 0x3C)? as usize;
+    if data.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+        return None;
+    }
+    Some(pe_offset)
+}
+
+/// A section header: where a range of virtual addresses lives in the file.
+struct Section {
+    virtual_address: u32,
+    virtual_size: u32,
+    raw_offset: u32,
+    raw_size: u32,
+}
+
+/// Parsed headers of a PE file, borrowing its bytes.
+struct PeFile<'a> {
+    data: &'a [u8],
+    sections: Vec<Section>,
+    /// RVA and size of the resource directory (0 if absent)
+    resource_rva: u32,
+    resource_size: u32,
+}
+
+impl<'a> PeFile<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let coff = pe_header_offset(data)? + 4;
+        let section_count = read_u16(data, coff + 2)? as usize;
+        let optional_size = read_u16(data, coff + 16)? as usize;
+        let optional = coff + 20;
+
+        // Data directories start after the fixed part of the optional header,
+        // which is longer for 64-bit (PE32+) images
+        let directories = match read_u16(data, optional)? {
+            0x10b => optional + 96,
+            0x20b => optional + 112,
+            _ => return None,
+        };
+        let resource_entry = directories + RESOURCE_DIRECTORY_INDEX * 8;
+        let (resource_rva, resource_size) = if resource_entry + 8 <= optional + optional_size {
+            (read_u32(data, resource_entry)?, read_u32(data, resource_entry + 4)?)
+        } else {
+            (0, 0)
+        };
+
+        let section_table = optional + optional_size;
+        let sections = (0..section_count)
+            .map(|i| {
+                let header = section_table + i * 40;
+                Some(Section {
+                    virtual_size: read_u32(data, header + 8)?,
+                    virtual_address: read_u32(data, header + 12)?,
+                    raw_size: read_u32(data, header + 16)?,
+                    raw_offset: read_u32(data, header + 20)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(PeFile { data, sections, resource_rva, resource_size })
+    }
+
+    /// Converts a relative virtual address to a file offset.
+    fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        // Section fields come from the file, so sums of them may overflow
+        let section = self.sections.iter()
+            .find(|s| rva.checked_sub(s.virtual_address).is_some_and(|delta| delta < s.virtual_size.max(s.raw_size)))?;
+        (rva - section.virtual_address).checked_add(section.raw_offset)
+            .map(|offset| offset as usize)
+    }
+
+    /// Returns the bytes of the first version resource, if any.
+    fn version_resource(&self) -> Option<&'a [u8]> {
+        if self.resource_rva == 0 || self.resource_size == 0 {
+            return None;
+        }
+        let root = self.rva_to_offset(self.resource_rva)?;
+
+        // The resource tree has three levels: type → name → language
+        let by_type = find_directory_entry(self.data, root, root, Some(RT_VERSION))?;
+        let by_name = find_directory_entry(self.data, root, by_type, None)?;
+        let data_entry = find_directory_entry(self.data, root, by_name, None)?;
+
+        let data_rva = read_u32(self.data, data_entry)?;
+        let data_size = read_u32(self.data, data_entry + 4)? as usize;
+        let offset = self.rva_to_offset(data_rva)?;
+        self.data.get(offset..offset.checked_add(data_size)?)
+    }
+
+    /// Collects the key/value pairs from every string table in the version resource.
+    fn version_strings(&self) -> Option<HashMap<String, String>> {
+        let root = VersionBlock::read(self.version_resource()?)?;
+        let mut strings = HashMap::new();
+
+        for file_info in root.children().filter(|b| b.key == "StringFileInfo") {
+            for table in file_info.children() {
+                for entry in table.children() {
+                    let value = utf16_string(entry.value);
+                    if !value.is_empty() {
+                        strings.entry(entry.key).or_insert(value);
+                    }
+                }
+            }
+        }
+
+        Some(strings)
+    }
+}
+
+/// Finds an entry in a resource directory and returns the offset it points to.
+/// With `id`, the entry with that numeric ID is used; otherwise the first entry.
+fn find_directory_entry(data: &[u8], root: usize, directory: usize, id: Option<u32>) -> Option<usize> {
+    let named = read_u16(data, directory + 12)? as usize;
+    let ids = read_u16(data, directory + 14)? as usize;
+
+    for i in 0..named + ids {
+        let entry = directory + 16 + i * 8;
+        let name = read_u32(data, entry)?;
+        if id.is_some_and(|id| name != id) {
+            continue;
+        }
+
+        // Offsets are relative to the root of the resource section; the high bit
+        // marks a subdirectory
+        let target = read_u32(data, entry + 4)? & 0x7FFF_FFFF;
+        return Some(root + target as usize);
+    }
+
+    None
+}
+
+/// One node of a `VS_VERSIONINFO` tree.
+struct VersionBlock<'a> {
+    key: String,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+impl<'a> VersionBlock<'a> {
+    /// Reads the block at the start of `data`.
+    fn read(data: &'a [u8]) -> Option<Self> {
+        let length = read_u16(data, 0)? as usize;
+        let value_length = read_u16(data, 2)? as usize;
+        let is_text = read_u16(data, 4)? == 1;
+        let block = data.get(..length)?;
+
+        let key_end = (6..block.len()).step_by(2)
+            .find(|&i| block[i] == 0 && block.get(i + 1) == Some(&0))?;
+        let key = utf16_string(&block[6..key_end]);
+
+        // Text values are measured in UTF-16 code units, binary values in bytes
+        let value_start = align4(key_end + 2).min(block.len());
+        let value_bytes = if is_text { value_length * 2 } else { value_length };
+        let value_end = (value_start + value_bytes).min(block.len());
+        let children_start = align4(value_end).min(block.len());
+
+        Some(VersionBlock {
+            key,
+            value: &block[value_start..value_end],
+            children: &block[children_start..],
+        })
+    }
+
+    /// Iterates over the child blocks.
+    fn children(&self) -> impl Iterator<Item = VersionBlock<'a>> {
+        let mut rest = self.children;
+        std::iter::from_fn(move || {
+            let length = read_u16(rest, 0)? as usize;
+            if length == 0 {
+                return None;
+            }
+            let block = VersionBlock::read(rest)?;
+            rest = rest.get(align4(length)..).unwrap_or_default();
+            Some(block)
+        })
+    }
+}
+
+/// Decodes little-endian UTF-16, stopping at the first NUL.
+fn utf16_string(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    String::from_utf16_lossy(&units).trim().to_string()
+}
+
+fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pe_with_section(virtual_address: u32, virtual_size: u32, raw_offset: u32) -> PeFile<'static> {
+        PeFile {
+            data: &[],
+            sections: vec![Section { virtual_size, virtual_address, raw_size: 0, raw_offset }],
+            resource_rva: 0,
+            resource_size: 0,
+        }
+    }
+
+    #[test]
+    fn maps_rva_into_section() {
+        let pe = pe_with_section(0x1000, 0x200, 0x400);
+        assert_eq!(pe.rva_to_offset(0x1010), Some(0x410));
+        assert_eq!(pe.rva_to_offset(0x1200), None);
+        assert_eq!(pe.rva_to_offset(0x0fff), None);
+    }
+
+    #[test]
+    fn rejects_overflowing_sections() {
+        assert_eq!(pe_with_section(u32::MAX - 0x10, 0x100, 0).rva_to_offset(u32::MAX), Some(0x10));
+        assert_eq!(pe_with_section(0x1000, 0x200, u32::MAX).rva_to_offset(0x1010), None);
+        assert_eq!(pe_with_section(u32::MAX, u32::MAX, 0).rva_to_offset(0x10), None);
+    }
+}
@@ -1,47 +1,321 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 /// Audio plugin format types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum PluginFormat {
     VST2,
     VST3,
     AU,       // Audio Units (macOS)
     AAX,      // Avid AAX
+    LV2,      // LV2 (mainly Linux)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Plugin {
     pub id: String,
     pub name: String,
+    /// Name found on disk, set when a custom display name replaces `name`
+    #[serde(default)]
+    pub original_name: Option<String>,
     pub version: String,
     pub description: Option<String>,
     pub author: Option<String>,
     /// URL to the plugin's icon/logo for display in native UI
     pub icon_url: Option<String>,
+    /// Normalized tags: inferred from metadata (e.g., category) plus user-defined ones
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct InstalledPlugin {
     pub plugin: Plugin,
+    /// Path where the plugin was found (may be a symlink)
     pub install_path: PathBuf,
+    /// Target of the install path if it is a symlink, otherwise the install path itself
+    pub resolved_path: PathBuf,
     pub format: PluginFormat,
+    /// Identifier the plugin declares on every platform (VST3 class ID, LV2 URI),
+    /// read from its metadata at scan time; None for shell members and plugins
+    /// that don't declare one
+    pub uid: Option<String>,
     pub enabled: bool,
+    /// Whether the plugin was found in a machine-wide or per-user plugin folder;
+    /// changing system-wide plugins needs administrator rights
+    pub scope: InstallScope,
+    /// Set for shell plugins, where one binary provides several plugins (e.g., WaveShell):
+    /// the name of the shell. Members expanded from one shell share `install_path`,
+    /// so operations on one member affect them all
+    pub shell: Option<String>,
+    /// CPU architecture(s) the plugin binary was built for
+    pub architecture: Architecture,
     /// Related paths discovered for this plugin
     pub related_paths: RelatedPaths,
+    /// Most recent access time of the plugin or its presets, see `registry::last_accessed()`
+    pub last_accessed: Option<SystemTime>,
+    /// Non-commercial license (trial, demo, NFR, beta) detected by `license::detect_license()`
+    pub license: Option<License>,
+}
+
+/// CPU architecture of a plugin binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Architecture {
+    Unknown,
+    X86,
+    X86_64,
+    Arm64,
+    /// Contains more than one architecture (e.g., a macOS universal binary, or a
+    /// Windows VST3 bundle with 32- and 64-bit builds)
+    Universal(Architectures),
+}
+
+impl Architecture {
+    /// Returns the architecture this library was compiled for.
+    pub fn host() -> Self {
+        if cfg!(target_arch = "x86_64") {
+            Architecture::X86_64
+        } else if cfg!(target_arch = "aarch64") {
+            Architecture::Arm64
+        } else if cfg!(target_arch = "x86") {
+            Architecture::X86
+        } else {
+            Architecture::Unknown
+        }
+    }
+    
+    /// Returns true if a host of the given architecture can load this plugin natively,
+    /// i.e., the plugin contains a build for (one of) the host's architecture(s).
+    /// Unknown architectures are assumed compatible, since nothing can be said about them.
+    pub fn is_compatible_with(self, host: Architecture) -> bool {
+        match (self, host) {
+            (Architecture::Unknown, _) | (_, Architecture::Unknown) => true,
+            (plugin, host) => host.members().iter().any(|arch| plugin.members().contains(&arch)),
+        }
+    }
+    
+    /// The single architectures this one is made of.
+    pub fn members(self) -> Architectures {
+        Architectures::default().with(self)
+    }
+}
+
+impl fmt::Display for Architecture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Architecture::Unknown => "unknown",
+            Architecture::X86 => "x86",
+            Architecture::X86_64 => "x86_64",
+            Architecture::Arm64 => "arm64",
+            Architecture::Universal(set) => {
+                let names: Vec<String> = set.iter().map(|arch| arch.to_string()).collect();
+                return write!(f, "universal ({})", names.join(", "));
+            }
+        };
+        f.write_str(name)
+    }
+}
+
+/// A set of single architectures (x86, x86_64, arm64), e.g., the slices of a
+/// universal binary
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Architectures(u8);
+
+impl Architectures {
+    const SINGLE: [(Architecture, u8); 3] = [
+        (Architecture::X86, 0b001),
+        (Architecture::X86_64, 0b010),
+        (Architecture::Arm64, 0b100),
+    ];
+    
+    /// Returns the set with `architecture` (or each of its members) added.
+    pub fn with(self, architecture: Architecture) -> Self {
+        let bits = match architecture {
+            Architecture::Universal(set) => set.0,
+            single => Self::SINGLE.iter()
+                .find(|(arch, _)| *arch == single)
+                .map_or(0, |(_, bit)| *bit),
+        };
+        Architectures(self.0 | bits)
+    }
+    
+    /// Returns true if the set has a build for `architecture`, a single one.
+    pub fn contains(self, architecture: &Architecture) -> bool {
+        Self::SINGLE.iter().any(|(arch, bit)| arch == architecture && self.0 & bit != 0)
+    }
+    
+    /// The architectures in the set, in the order x86, x86_64, arm64.
+    pub fn iter(self) -> impl Iterator<Item = Architecture> {
+        Self::SINGLE.into_iter()
+            .filter(move |(_, bit)| self.0 & bit != 0)
+            .map(|(arch, _)| arch)
+    }
+}
+
+/// Which path an operation should act on for a symlinked plugin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathTarget {
+    /// The path as found in the plugin folder (the symlink itself)
+    InstallPath,
+    /// The real bundle/file the symlink points to
+    ResolvedPath,
+}
+
+/// How much a heuristically derived value can be trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Confidence {
+    /// Guessed from names or folder layout
+    Low,
+    /// Partially backed by embedded metadata
+    Medium,
+    /// Read from embedded metadata
+    High,
+}
+
+/// Whether a plugin is installed machine-wide or for the current user only
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InstallScope {
+    /// System-wide location (e.g., `/Library/...`, `C:\Program Files\...`)
+    System,
+    /// Per-user location (e.g., `~/Library/...`, `%LOCALAPPDATA%\...`)
+    User,
+}
+
+impl InstalledPlugin {
+    /// Returns true if the install path resolves to a different location (e.g., a symlink).
+    pub fn is_symlinked(&self) -> bool {
+        self.install_path != self.resolved_path
+    }
+    
+    /// Returns a copy whose `install_path` is the chosen target, so backup/uninstall/export
+    /// operate on either the symlink or its real target.
+    pub fn targeting(&self, target: PathTarget) -> InstalledPlugin {
+        let mut plugin = self.clone();
+        if target == PathTarget::ResolvedPath {
+            plugin.install_path = self.resolved_path.clone();
+        }
+        plugin
+    }
+}
+
+/// Human-readable report of a plugin's discovered state, for logs and support tickets.
+impl fmt::Display for InstalledPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} [{:?}]", self.plugin.name, self.format)?;
+        writeln!(f, "  ID:       {}", self.plugin.id)?;
+        writeln!(f, "  Version:  {}", self.plugin.version)?;
+        writeln!(f, "  Vendor:   {}", self.plugin.author.as_deref().unwrap_or("Unknown"))?;
+        writeln!(f, "  Path:     {}", self.install_path.display())?;
+        if self.is_symlinked() {
+            writeln!(f, "  Resolved: {}", self.resolved_path.display())?;
+        }
+        writeln!(f, "  Arch:     {}", self.architecture)?;
+        writeln!(f, "  Enabled:  {}", if self.enabled { "yes" } else { "no" })?;
+        writeln!(f, "  Icon:     {}", self.plugin.icon_url.as_deref().unwrap_or("Not found"))?;
+        
+        let categories = [
+            ("Presets", &self.related_paths.preset_locations),
+            ("Libraries", &self.related_paths.library_locations),
+            ("Support", &self.related_paths.support_locations),
+            ("Preferences", &self.related_paths.preference_files),
+        ];
+        
+        for (header, paths) in categories {
+            writeln!(f, "  {}:", header)?;
+            if paths.is_empty() {
+                writeln!(f, "    (none)")?;
+            }
+            for related in paths {
+                writeln!(f, "    {} ({:?} confidence)", related.path.display(), related.confidence)?;
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// A discovered plugin-related file or folder
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatedPath {
+    pub path: PathBuf,
+    /// How specifically the location matched the plugin: High for a vendor + plugin
+    /// folder, Medium for a plugin-named folder in a standard location, Low for a
+    /// loose name match in a generic folder (e.g., `~/Documents/<Plugin>`)
+    pub confidence: Confidence,
+}
+
+impl RelatedPath {
+    pub fn new(path: PathBuf, confidence: Confidence) -> Self {
+        RelatedPath { path, confidence }
+    }
 }
 
 /// Paths to plugin-related files and folders
 #[derive(Debug, Clone, Default)]
 pub struct RelatedPaths {
     /// User preset folders
-    pub preset_locations: Vec<PathBuf>,
+    pub preset_locations: Vec<RelatedPath>,
     /// Factory preset/content folders (samples, IRs, etc.)
-    pub library_locations: Vec<PathBuf>,
+    pub library_locations: Vec<RelatedPath>,
     /// Application Support folders (settings, licenses, etc.)
-    pub support_locations: Vec<PathBuf>,
+    pub support_locations: Vec<RelatedPath>,
     /// Preferences/config files
-    pub preference_files: Vec<PathBuf>,
+    pub preference_files: Vec<RelatedPath>,
+}
+
+/// A category of related paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RelatedCategory {
+    Presets,
+    Libraries,
+    Support,
+    Preferences,
+}
+
+impl RelatedCategory {
+    pub const ALL: [RelatedCategory; 4] = [
+        RelatedCategory::Presets,
+        RelatedCategory::Libraries,
+        RelatedCategory::Support,
+        RelatedCategory::Preferences,
+    ];
+}
+
+impl RelatedPaths {
+    /// Returns the entries discovered for one category.
+    pub fn paths(&self, category: RelatedCategory) -> &[RelatedPath] {
+        match category {
+            RelatedCategory::Presets => &self.preset_locations,
+            RelatedCategory::Libraries => &self.library_locations,
+            RelatedCategory::Support => &self.support_locations,
+            RelatedCategory::Preferences => &self.preference_files,
+        }
+    }
+    
+    /// Iterates over the paths of every category.
+    pub fn all_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        RelatedCategory::ALL.into_iter()
+            .flat_map(|category| self.paths(category))
+            .map(|related| &related.path)
+    }
+    
+    /// Returns a copy keeping only entries with at least the given confidence,
+    /// e.g., to limit an uninstall to near-certain matches.
+    pub fn with_min_confidence(&self, min: Confidence) -> RelatedPaths {
+        let keep = |paths: &[RelatedPath]| paths.iter()
+            .filter(|related| related.confidence >= min)
+            .cloned()
+            .collect();
+        
+        RelatedPaths {
+            preset_locations: keep(&self.preset_locations),
+            library_locations: keep(&self.library_locations),
+            support_locations: keep(&self.support_locations),
+            preference_files: keep(&self.preference_files),
+        }
+    }
 }
 
 /// Vendor/manufacturer information
@@ -65,14 +339,35 @@ pub struct License {
     pub license_file: Option<PathBuf>,
     /// Expiration date for trial licenses
     pub expiration: Option<String>,
+    /// How reliable `license_type` is when it was inferred from files rather than
+    /// read from the vendor (None if not inferred)
+    #[serde(default)]
+    pub confidence: Option<Confidence>,
 }
 
 /// Complete plugin metadata including vendor and licensing
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginMetadata {
     pub plugin: Plugin,
+    pub format: PluginFormat,
     pub vendor: Option<Vendor>,
     pub license: Option<License>,
     /// Tags for categorization (e.g., "synth", "effect", "compressor")
+    #[serde(default)]
     pub tags: Vec<String>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn formats_work_as_set_members() {
+        let all = [PluginFormat::VST2, PluginFormat::VST3, PluginFormat::AU, PluginFormat::AAX, PluginFormat::LV2];
+        let mut formats: HashSet<PluginFormat> = all.into_iter().collect();
+        assert_eq!(formats.len(), all.len());
+        assert!(!formats.insert(PluginFormat::VST3));
+        assert!(all.iter().all(|format| formats.contains(format)));
+    }
+}
@@ -0,0 +1,68 @@
+//! macOS quarantine attribute detection and removal.
+//!
+//! Plugins downloaded from the internet carry the `com.apple.quarantine` extended
+//! attribute, and hosts refuse to load them until it is cleared. Bundles are checked
+//! recursively since the attribute can be set on any file inside them.
+//!
+//! On platforms without quarantine, `is_quarantined()` always returns false and
+//! `remove_quarantine()` is a no-op.
+
+use anyhow::Result;
+use std::path::Path;
+
+#[cfg(target_os = "macos")]
+use anyhow::Context;
+#[cfg(target_os = "macos")]
+use std::fs;
+
+/// Extended attribute set by macOS on downloaded files
+pub const QUARANTINE_ATTRIBUTE: &str = "com.apple.quarantine";
+
+/// Returns true if the path, or any file inside it, carries the quarantine attribute.
+#[cfg(target_os = "macos")]
+pub fn is_quarantined(path: &Path) -> bool {
+    if matches!(xattr::get(path, QUARANTINE_ATTRIBUTE), Ok(Some(_))) {
+        return true;
+    }
+
+    // Recurse into bundles, but don't follow symlinks out of them
+    let is_dir = fs::symlink_metadata(path).map(|m| m.is_dir()).unwrap_or(false);
+    if !is_dir {
+        return false;
+    }
+
+    match fs::read_dir(path) {
+        Ok(entries) => entries.flatten().any(|entry| is_quarantined(&entry.path())),
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn is_quarantined(_path: &Path) -> bool {
+    false
+}
+
+/// Removes the quarantine attribute from the path and everything inside it.
+#[cfg(target_os = "macos")]
+pub fn remove_quarantine(path: &Path) -> Result<()> {
+    if matches!(xattr::get(path, QUARANTINE_ATTRIBUTE), Ok(Some(_))) {
+        xattr::remove(path, QUARANTINE_ATTRIBUTE)
+            .context(format!("Failed to remove quarantine attribute: {:?}", path))?;
+    }
+
+    let is_dir = fs::symlink_metadata(path).map(|m| m.is_dir()).unwrap_or(false);
+    if is_dir {
+        let entries = fs::read_dir(path)
+            .context(format!("Failed to read directory: {:?}", path))?;
+        for entry in entries {
+            remove_quarantine(&entry?.path())?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn remove_quarantine(_path: &Path) -> Result<()> {
+    Ok(())
+}
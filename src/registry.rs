@@ -17,14 +17,26 @@
 //! 
 //! ## Windows
 //! - VST2 and AAX plugins are DLL files (.dll, .aax)
-//! - VST3 can be either .vst3 bundles or files in VST3 directory
+//! - VST3 can be either .vst3 bundles or files in VST3 directory, including in vendor subfolders
 //! - Scans Program Files and Common Files locations
 //! - No AU support (macOS-only format)
 
-use crate::{InstalledPlugin, Plugin, PluginFormat, RelatedPaths};
+use crate::arch::{bundle_executable, detect_architecture};
+use crate::atomic::write_atomic;
+use crate::icons::cache_icns_as_png;
+use crate::license::detect_license;
+use crate::duplicates::{scope_of, user_plugin_roots};
+use crate::metadata::{cached_bundle_metadata, metadata_cache, read_sidecar, sidecar_path, AaxType, BundleMetadata};
+use crate::state::{load_user_state, normalize_tag, state_file_path, UserState};
+use crate::{Confidence, InstalledPlugin, Plugin, PluginFormat, RelatedCategory, RelatedPath, RelatedPaths};
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
 
 impl PluginFormat {
     /// Returns the file extension for this plugin format on the current platform
@@ -35,6 +47,7 @@ impl PluginFormat {
             PluginFormat::VST3 => "vst3",
             PluginFormat::AU => "component",
             PluginFormat::AAX => "aaxplugin",
+            PluginFormat::LV2 => "lv2",
         }
     }
     
@@ -45,6 +58,18 @@ impl PluginFormat {
             PluginFormat::VST3 => "vst3",  // VST3 on Windows can be either .vst3 bundle or in VST3 folder
             PluginFormat::AU => "component", // AU doesn't exist on Windows, but keep for completeness
             PluginFormat::AAX => "aax",
+            PluginFormat::LV2 => "lv2",
+        }
+    }
+    
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    fn extension(&self) -> &str {
+        match self {
+            PluginFormat::VST2 => "so",
+            PluginFormat::VST3 => "vst3",
+            PluginFormat::AU => "component", // AU and AAX don't exist on Linux, but keep for completeness
+            PluginFormat::AAX => "aaxplugin",
+            PluginFormat::LV2 => "lv2",
         }
     }
     
@@ -55,9 +80,10 @@ impl PluginFormat {
             true  // All macOS plugins are bundles
         }
         
-        #[cfg(target_os = "windows")]
+        #[cfg(not(target_os = "macos"))]
         {
-            matches!(self, PluginFormat::VST3)  // Only VST3 can be a bundle on Windows
+            // Only VST3 and LV2 are bundles on Windows and Linux
+            matches!(self, PluginFormat::VST3 | PluginFormat::LV2)
         }
     }
 }
@@ -65,28 +91,46 @@ impl PluginFormat {
 /// Returns all standard audio plugin directories for the current platform.
 /// 
 /// macOS: Includes both system-wide (/Library) and user-specific (~/Library) locations.
-/// Windows: Includes Program Files and Common Files locations.
+/// Windows: Includes Program Files and Common Files locations, plus per-user
+/// folders under `%LOCALAPPDATA%` and `%APPDATA%`.
+/// Linux: LV2 bundle directories (`~/.lv2`, `/usr/local/lib/lv2`, `/usr/lib/lv2`).
 fn get_plugin_directories() -> Result<Vec<(PathBuf, PluginFormat)>> {
     let mut dirs = Vec::new();
     
     #[cfg(target_os = "macos")]
     {
-        let home = std::env::var("HOME").context("HOME environment variable not set")?;
+        // Without HOME, still scan the system-wide locations and skip the user ones
+        let home = std::env::var("HOME").ok();
+        if home.is_none() {
+            eprintln!("Warning: HOME environment variable not set; skipping user plugin directories");
+        }
         
         // Audio Units (AU) - macOS native format
         dirs.push((PathBuf::from("/Library/Audio/Plug-Ins/Components"), PluginFormat::AU));
-        dirs.push((PathBuf::from(format!("{}/Library/Audio/Plug-Ins/Components", home)), PluginFormat::AU));
+        if let Some(home) = &home {
+            dirs.push((PathBuf::from(format!("{}/Library/Audio/Plug-Ins/Components", home)), PluginFormat::AU));
+        }
         
         // VST2 - Legacy Steinberg format
         dirs.push((PathBuf::from("/Library/Audio/Plug-Ins/VST"), PluginFormat::VST2));
-        dirs.push((PathBuf::from(format!("{}/Library/Audio/Plug-Ins/VST", home)), PluginFormat::VST2));
+        if let Some(home) = &home {
+            dirs.push((PathBuf::from(format!("{}/Library/Audio/Plug-Ins/VST", home)), PluginFormat::VST2));
+        }
         
         // VST3 - Modern Steinberg format
         dirs.push((PathBuf::from("/Library/Audio/Plug-Ins/VST3"), PluginFormat::VST3));
-        dirs.push((PathBuf::from(format!("{}/Library/Audio/Plug-Ins/VST3", home)), PluginFormat::VST3));
+        if let Some(home) = &home {
+            dirs.push((PathBuf::from(format!("{}/Library/Audio/Plug-Ins/VST3", home)), PluginFormat::VST3));
+        }
         
         // AAX - Avid Pro Tools format
         dirs.push((PathBuf::from("/Library/Application Support/Avid/Audio/Plug-Ins"), PluginFormat::AAX));
+        
+        // LV2 - Open format, mainly used on Linux
+        dirs.push((PathBuf::from("/Library/Audio/Plug-Ins/LV2"), PluginFormat::LV2));
+        if let Some(home) = &home {
+            dirs.push((PathBuf::from(format!("{}/Library/Audio/Plug-Ins/LV2", home)), PluginFormat::LV2));
+        }
     }
     
     #[cfg(target_os = "windows")]
@@ -104,105 +148,1095 @@ fn get_plugin_directories() -> Result<Vec<(PathBuf, PluginFormat)>> {
         // AAX - Avid Pro Tools format
         dirs.push((PathBuf::from(r"C:\Program Files\Common Files\Avid\Audio\Plug-Ins"), PluginFormat::AAX));
         dirs.push((PathBuf::from(r"C:\Program Files (x86)\Common Files\Avid\Audio\Plug-Ins"), PluginFormat::AAX));
+        
+        // LV2 - Open format, mainly used on Linux
+        dirs.push((PathBuf::from(r"C:\Program Files\Common Files\LV2"), PluginFormat::LV2));
+
+        // Per-user locations used by installers that run without admin rights
+        let local_appdata = std::env::var("LOCALAPPDATA").ok().map(PathBuf::from);
+        let appdata = std::env::var("APPDATA").ok().map(PathBuf::from);
+        let user_dirs = [
+            (local_appdata.as_ref().map(|d| d.join(r"Programs\Common\VST3")), PluginFormat::VST3),
+            (local_appdata.as_ref().map(|d| d.join(r"Programs\Common\VST2")), PluginFormat::VST2),
+            (local_appdata.as_ref().map(|d| d.join(r"Programs\VSTPlugins")), PluginFormat::VST2),
+            (appdata.as_ref().map(|d| d.join("VST3")), PluginFormat::VST3),
+            (appdata.as_ref().map(|d| d.join("VSTPlugins")), PluginFormat::VST2),
+            (appdata.as_ref().map(|d| d.join("LV2")), PluginFormat::LV2),
+        ];
+        // Listed even when missing, so `plugin_directories()` can report them
+        // Listed even when missing, so `plugin_directories()` can report them
+        dirs.extend(user_dirs.into_iter().filter_map(|(dir, format)| Some((dir?, format))));
+    }
+    
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        // LV2 - /usr/local first: it's where plugins not managed by the package
+        // manager belong, so imports install there
+        dirs.push((PathBuf::from("/usr/local/lib/lv2"), PluginFormat::LV2));
+        dirs.push((PathBuf::from("/usr/lib/lv2"), PluginFormat::LV2));
+        match std::env::var("HOME") {
+            Ok(home) => dirs.push((PathBuf::from(format!("{}/.lv2", home)), PluginFormat::LV2)),
+            Err(_) => eprintln!("Warning: HOME environment variable not set; skipping user plugin directories"),
+        }
     }
     
     Ok(dirs)
 }
 
+/// Returns the standard directory new plugins of a format are installed to
+/// (the first, system-wide location for that format on this platform).
+pub(crate) fn default_install_dir(format: &PluginFormat) -> Result<PathBuf> {
+    get_plugin_directories()?
+        .into_iter()
+        .find(|(_, dir_format)| dir_format == format)
+        .map(|(dir, _)| dir)
+        .context(format!("No standard {:?} plugin directory on this platform", format))
+}
+
+/// Returns this user's standard folder for related files of a category, which
+/// imports place related files under. None if the environment variable it is
+/// derived from is unset.
+pub(crate) fn related_install_dir(category: RelatedCategory) -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        let home = PathBuf::from(std::env::var("HOME").ok()?);
+        Some(match category {
+            RelatedCategory::Presets => home.join("Library/Audio/Presets"),
+            RelatedCategory::Libraries | RelatedCategory::Support => home.join("Library/Application Support"),
+            RelatedCategory::Preferences => home.join("Library/Preferences"),
+        })
+    }
+    
+    #[cfg(target_os = "windows")]
+    {
+        let variable = match category {
+            RelatedCategory::Libraries => "PROGRAMDATA",
+            _ => "APPDATA",
+        };
+        std::env::var(variable).ok().map(PathBuf::from)
+    }
+    
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let home = PathBuf::from(std::env::var("HOME").ok()?);
+        Some(match category {
+            RelatedCategory::Preferences => home.join(".config"),
+            _ => home.join(".local/share"),
+        })
+    }
+}
+
+/// Lists every directory the default scan looks in, with whether it currently exists.
+/// Includes platform defaults, per-user locations, and the "(Disabled)" siblings.
+pub fn plugin_directories() -> Result<Vec<(PathBuf, PluginFormat, bool)>> {
+    plugin_directories_with(&ScanConfig::default())
+}
+
+/// Like `plugin_directories()`, for the directories a custom `ScanConfig` scans
+/// (extra directories included, excluded ones left out).
+pub fn plugin_directories_with(config: &ScanConfig) -> Result<Vec<(PathBuf, PluginFormat, bool)>> {
+    Ok(config.directories()?
+        .into_iter()
+        .map(|(dir, format)| {
+            let exists = dir.is_dir();
+            (dir, format, exists)
+        })
+        .collect())
+}
+
 /// Scans all standard audio plugin directories and returns a list of installed plugins.
 /// Returns an empty list if no plugins are found.
 pub fn scan_installed() -> Result<Vec<InstalledPlugin>> {
-    let plugin_dirs = get_plugin_directories()?;
+    scan(&ScanConfig::default(), None)
+}
+
+/// Scans all standard plugin directories once and returns the plugins bucketed by
+/// format. Formats with no installed plugins have no entry.
+pub fn scan_grouped_by_format() -> Result<HashMap<PluginFormat, Vec<InstalledPlugin>>> {
+    Ok(group_by_format(scan_installed()?))
+}
+
+/// Buckets plugins by format, keeping their order within each format.
+pub fn group_by_format(plugins: Vec<InstalledPlugin>) -> HashMap<PluginFormat, Vec<InstalledPlugin>> {
+    let mut groups: HashMap<PluginFormat, Vec<InstalledPlugin>> = HashMap::new();
+    for plugin in plugins {
+        groups.entry(plugin.format).or_default().push(plugin);
+    }
+    groups
+}
+
+/// Extra directories scanned along with the standard ones, set at runtime
+static GLOBAL_SCAN_DIRS: RwLock<Vec<(PathBuf, PluginFormat)>> = RwLock::new(Vec::new());
+
+/// Adds a directory that `scan_installed()`, the FFI scan, and any scan whose
+/// config has `include_default_dirs` set will include from now on, e.g., a plugin
+/// folder configured in app settings. It belongs to this process only and isn't
+/// saved with a `ScanConfig`. Adding the same directory and format twice has no effect.
+pub fn add_global_scan_dir(dir: PathBuf, format: PluginFormat) {
+    let mut dirs = GLOBAL_SCAN_DIRS.write().unwrap_or_else(|e| e.into_inner());
+    let entry = (dir, format);
+    if !dirs.contains(&entry) {
+        dirs.push(entry);
+    }
+}
+
+/// Removes every directory added with `add_global_scan_dir()`.
+pub fn clear_global_scan_dirs() {
+    GLOBAL_SCAN_DIRS.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// Returns the directories added with `add_global_scan_dir()`.
+pub fn global_scan_dirs() -> Vec<(PathBuf, PluginFormat)> {
+    GLOBAL_SCAN_DIRS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Settings for `scan()`.
+/// Can be saved to and loaded from a JSON file; keys missing from the file keep
+/// their default values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanConfig {
+    /// Scan the platform's standard plugin directories, and those added with
+    /// `add_global_scan_dir()`
+    pub include_default_dirs: bool,
+    /// Additional directories to scan, with the plugin format expected in each
+    pub extra_dirs: Vec<(PathBuf, PluginFormat)>,
+    /// Directories never scanned; anything under one of these paths is skipped
+    pub exclude_dirs: Vec<PathBuf>,
+    /// Plugin IDs to leave out of results
+    pub exclude_ids: Vec<String>,
+    /// Threads used to read plugin metadata; 1 reads sequentially
+    pub metadata_workers: usize,
+    /// Formats to scan; empty scans every format
+    pub formats: Vec<PluginFormat>,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        ScanConfig {
+            include_default_dirs: true,
+            extra_dirs: Vec::new(),
+            exclude_dirs: Vec::new(),
+            exclude_ids: Vec::new(),
+            metadata_workers: default_metadata_workers(),
+            formats: Vec::new(),
+        }
+    }
+}
+
+/// Keys understood in a scan config file
+const SCAN_CONFIG_KEYS: &[&str] = &[
+    "include_default_dirs",
+    "extra_dirs",
+    "exclude_dirs",
+    "exclude_ids",
+    "metadata_workers",
+    "formats",
+];
+
+/// Upper bound on the default number of metadata workers; extraction is mostly
+/// disk I/O, so more threads than this rarely help
+const MAX_DEFAULT_METADATA_WORKERS: usize = 8;
+
+/// One worker per CPU, capped at `MAX_DEFAULT_METADATA_WORKERS`.
+fn default_metadata_workers() -> usize {
+    std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_DEFAULT_METADATA_WORKERS)
+}
+
+impl ScanConfig {
+    /// Loads a scan configuration from a JSON file.
+    /// Unknown keys are reported as warnings and otherwise ignored, so a config
+    /// written by a newer version still loads.
+    pub fn from_file(path: &Path) -> Result<ScanConfig> {
+        let content = fs::read_to_string(path)
+            .context(format!("Failed to read scan config: {:?}", path))?;
+        let value: serde_json::Value = serde_json::from_str(&content)
+            .context(format!("Failed to parse scan config: {:?}", path))?;
+        
+        if let Some(object) = value.as_object() {
+            for key in object.keys().filter(|key| !SCAN_CONFIG_KEYS.contains(&key.as_str())) {
+                eprintln!("Warning: Unknown key {:?} in scan config {:?}", key, path);
+            }
+        }
+        
+        serde_json::from_value(value)
+            .context(format!("Invalid scan config: {:?}", path))
+    }
+    
+    /// Saves the configuration as pretty-printed JSON, atomically, creating the
+    /// parent directory if needed.
+    pub fn to_file(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .context(format!("Failed to create directory: {:?}", parent))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(path, content.as_bytes())
+            .context(format!("Failed to write scan config: {:?}", path))
+    }
+    
+    /// Returns every directory this configuration scans, in scan order.
+    fn directories(&self) -> Result<Vec<(PathBuf, PluginFormat)>> {
+        let mut dirs = if self.include_default_dirs {
+            get_plugin_directories()?
+        } else {
+            Vec::new()
+        };
+        // Runtime folders are added here rather than to `extra_dirs`, so saving a
+        // config doesn't persist them
+        let global = if self.include_default_dirs { global_scan_dirs() } else { Vec::new() };
+        for extra in global.iter().chain(&self.extra_dirs) {
+            // A user-added folder may also be a default one; scan it only once
+            if !dirs.contains(extra) {
+                dirs.push(extra.clone());
+            }
+        }
+        
+        // Also scan the "(Disabled)" sibling of each directory so disabled plugins are listed
+        let disabled: Vec<_> = dirs.iter()
+            .map(|(dir, format)| (disabled_dir_for(dir), *format))
+            .collect();
+        dirs.extend(disabled);
+        
+        dirs.retain(|(dir, format)| {
+            !self.is_excluded_path(dir) && (self.formats.is_empty() || self.formats.contains(format))
+        });
+        
+        Ok(dirs)
+    }
+    
+    /// Returns true if the path is inside one of the excluded directories.
+    fn is_excluded_path(&self, path: &Path) -> bool {
+        self.exclude_dirs.iter().any(|excluded| path.starts_with(excluded))
+    }
+    
+    /// Returns true if a discovered plugin should be left out of results.
+    fn is_excluded(&self, plugin: &InstalledPlugin) -> bool {
+        self.exclude_ids.iter().any(|id| matches_plugin_id(plugin, id)) || self.is_excluded_path(&plugin.install_path)
+    }
+}
+
+/// Suffix of the sibling folder that disabled plugin bundles are moved into
+/// (e.g., `VST3 (Disabled)` next to `VST3`). Hosts don't scan it.
+pub const DISABLED_DIR_SUFFIX: &str = " (Disabled)";
+
+/// Extension appended to file-based plugins when disabled (e.g., `Plugin.dll.disabled`).
+pub const DISABLED_FILE_EXTENSION: &str = "disabled";
+
+/// Returns the "(Disabled)" sibling folder for a plugin directory.
+pub fn disabled_dir_for(dir: &Path) -> PathBuf {
+    let name = dir.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    dir.with_file_name(format!("{}{}", name, DISABLED_DIR_SUFFIX))
+}
+
+/// Returns true if the path is inside a "(Disabled)" folder or has the disabled extension.
+fn is_disabled_path(path: &Path) -> bool {
+    let in_disabled_dir = path.parent()
+        .and_then(|p| p.file_name())
+        .is_some_and(|n| n.to_string_lossy().ends_with(DISABLED_DIR_SUFFIX));
+    let has_disabled_ext = path.is_file()
+        && path.extension().is_some_and(|e| e == DISABLED_FILE_EXTENSION);
+    
+    in_disabled_dir || has_disabled_ext
+}
+
+/// Returns the path with a trailing `.disabled` extension removed, so the real
+/// plugin extension can be checked.
+fn strip_disabled_extension(path: &Path) -> PathBuf {
+    if path.is_file() && path.extension().is_some_and(|e| e == DISABLED_FILE_EXTENSION) {
+        path.with_extension("")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Cooperative cancellation flag for long-running scans.
+/// Clones share the same flag, so one can be handed to another thread to cancel.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Request cancellation. Scans stop at the next directory or entry boundary.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+    
+    /// Clear a previous cancellation so the token can be reused.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+    
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Progress reported by `scan_with_progress()` as each directory is started.
+#[derive(Debug, Clone)]
+pub struct ScanProgress {
+    pub directories_done: usize,
+    pub directories_total: usize,
+    /// Directory about to be scanned; None once the scan has finished
+    pub current_dir: Option<PathBuf>,
+    pub plugins_found: usize,
+}
+
+/// Scans the directories described by `config`.
+/// If `cancel` is triggered, returns early with the plugins found so far.
+pub fn scan(config: &ScanConfig, cancel: Option<&CancellationToken>) -> Result<Vec<InstalledPlugin>> {
+    scan_with_progress(config, cancel, |_| {})
+}
+
+/// Plugins found by `scan_with_report()`, plus problems found along the way.
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    pub plugins: Vec<InstalledPlugin>,
+    /// Symlinks in plugin directories whose target no longer exists, usually left
+    /// behind by an incomplete uninstall
+    pub broken_symlinks: Vec<PathBuf>,
+}
+
+/// Like `scan()`, also reporting broken symlinks found in the plugin directories.
+pub fn scan_with_report(config: &ScanConfig, cancel: Option<&CancellationToken>) -> Result<ScanReport> {
+    let mut broken_symlinks = Vec::new();
+    let plugins = scan_collecting(config, cancel, |_| {}, &mut broken_symlinks)?;
+    Ok(ScanReport { plugins, broken_symlinks })
+}
+
+/// Rough size of an upcoming scan, for progress UI.
+#[derive(Debug, Clone, Default)]
+pub struct ScanEstimate {
+    /// Each existing directory that will be scanned, with its format and number of entries
+    pub directories: Vec<(PathBuf, PluginFormat, usize)>,
+    /// Expected number of plugins (entries across all directories)
+    pub estimated_plugins: usize,
+    /// Expected scan duration on this machine
+    pub estimated_duration: Duration,
+}
+
+/// Typical time to recognize one plugin, including its metadata, on one worker
+const ESTIMATED_TIME_PER_PLUGIN: Duration = Duration::from_millis(5);
+
+/// Estimates a scan of the default directories without running it.
+pub fn scan_estimate() -> Result<ScanEstimate> {
+    scan_estimate_with(&ScanConfig::default())
+}
+
+/// Estimates a scan of the directories in `config` by counting the top-level
+/// entries of each (no recursion, no metadata), so it's much faster than the scan.
+/// Nested VST3 folders count as one entry, so the plugin count is approximate.
+pub fn scan_estimate_with(config: &ScanConfig) -> Result<ScanEstimate> {
+    let mut estimate = ScanEstimate::default();
+    
+    for (dir, format) in config.directories()? {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        let count = entries.flatten()
+            .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+            .count();
+        estimate.estimated_plugins += count;
+        estimate.directories.push((dir, format, count));
+    }
+    
+    let workers = config.metadata_workers.max(1) as u32;
+    estimate.estimated_duration = ESTIMATED_TIME_PER_PLUGIN * estimate.estimated_plugins as u32 / workers;
+    
+    Ok(estimate)
+}
+
+/// Like `scan()`, but calls `progress` before each directory and once at the end.
+/// Broken symlinks are reported as warnings; use `scan_with_report()` to get them.
+pub fn scan_with_progress<F>(config: &ScanConfig, cancel: Option<&CancellationToken>, progress: F) -> Result<Vec<InstalledPlugin>>
+where
+    F: FnMut(&ScanProgress),
+{
+    let mut broken_symlinks = Vec::new();
+    let plugins = scan_collecting(config, cancel, progress, &mut broken_symlinks)?;
+    for link in &broken_symlinks {
+        eprintln!("Warning: Broken symlink in plugin directory: {:?}", link);
+    }
+    Ok(plugins)
+}
+
+fn scan_collecting<F>(config: &ScanConfig, cancel: Option<&CancellationToken>, mut progress: F, broken_symlinks: &mut Vec<PathBuf>) -> Result<Vec<InstalledPlugin>>
+where
+    F: FnMut(&ScanProgress),
+{
+    let plugin_dirs = config.directories()?;
+    let total = plugin_dirs.len();
+    let is_cancelled = || cancel.is_some_and(|c| c.is_cancelled());
     let mut installed = Vec::new();
+    let mut completed = 0;
     
-    for (dir, format) in plugin_dirs {
-        // Skip directories that don't exist
-        if !dir.exists() {
-            continue;
+    for (dir, format) in &plugin_dirs {
+        if is_cancelled() {
+            break;
         }
         
-        // Scan this directory for plugins
-        match scan_directory(&dir, &format) {
-            Ok(mut plugins) => installed.append(&mut plugins),
-            Err(e) => {
+        progress(&ScanProgress {
+            directories_done: completed,
+            directories_total: total,
+            current_dir: Some(dir.clone()),
+            plugins_found: installed.len(),
+        });
+        
+        if is_scannable_dir(dir) {
+            if let Err(e) = scan_directory(dir, format, config.metadata_workers, cancel, &mut installed, broken_symlinks) {
                 eprintln!("Warning: Failed to scan directory {:?}: {}", dir, e);
             }
         }
+        
+        completed += 1;
     }
     
+    installed.retain(|plugin| !config.is_excluded(plugin));
+    
+    progress(&ScanProgress {
+        directories_done: completed,
+        directories_total: total,
+        current_dir: None,
+        plugins_found: installed.len(),
+    });
+    
     // Share icons between VST2 and VST3 versions of the same plugin
     share_icons_between_formats(&mut installed);
     
+    // Custom display names are applied after name-based matching
+    apply_user_state(&mut installed);
+    
+    sort_plugins(&mut installed);
+    
     Ok(installed)
 }
 
-/// Scans a single directory for plugins of a specific format.
-fn scan_directory(dir: &PathBuf, format: &PluginFormat) -> Result<Vec<InstalledPlugin>> {
-    let mut plugins = Vec::new();
-    let extension = format.extension();
-    let is_bundle = format.is_bundle();
-    
+/// Applies the user's saved plugin settings to scanned plugins, first moving
+/// settings saved under legacy IDs (see `legacy_plugin_id()`) to the current ones.
+fn apply_user_state(plugins: &mut [InstalledPlugin]) {
+    let mut state = user_state();
+    if state.migrate_legacy_ids(plugins) {
+        if let Err(e) = state.save(&state_file_path()) {
+            eprintln!("Warning: Failed to save migrated user state: {:#}", e);
+        }
+    }
+    state.apply(plugins);
+}
+
+/// Loads the user's saved plugin settings, warning and using defaults on failure.
+fn user_state() -> UserState {
+    load_user_state().unwrap_or_else(|e| {
+        eprintln!("Warning: Failed to load user state: {:#}", e);
+        UserState::default()
+    })
+}
+
+/// Sorts plugins by name, then format, then install path, so results are
+/// stable across scans regardless of filesystem iteration order.
+fn sort_plugins(plugins: &mut [InstalledPlugin]) {
+    plugins.sort_by(|a, b| {
+        a.plugin.name.cmp(&b.plugin.name)
+            .then_with(|| format_order(&a.format).cmp(&format_order(&b.format)))
+            .then_with(|| a.install_path.cmp(&b.install_path))
+    });
+}
+
+/// Sort rank of a plugin format (declaration order of `PluginFormat`).
+fn format_order(format: &PluginFormat) -> u8 {
+    match format {
+        PluginFormat::VST2 => 0,
+        PluginFormat::VST3 => 1,
+        PluginFormat::AU => 2,
+        PluginFormat::AAX => 3,
+        PluginFormat::LV2 => 4,
+    }
+}
+
+/// How many levels of vendor/category subfolders are searched for nested VST3 bundles
+const MAX_NESTED_DEPTH: usize = 4;
+
+/// Returns true if `path` is a folder that may contain nested plugins of `format`,
+/// such as `VST3/FabFilter/`. Plugin bundles themselves are leaves.
+fn is_nested_plugin_folder(path: &Path, format: &PluginFormat) -> bool {
+    matches!(format, PluginFormat::VST3)
+        && path.is_dir()
+        && !strip_disabled_extension(path).extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case(format.extension()))
+}
+
+/// Returns true if a configured plugin directory can be scanned. Missing ones are
+/// skipped silently (most standard folders don't exist on a given machine); a
+/// path that exists but isn't a directory (a file, or a symlink to one) is skipped
+/// with a warning, since it's likely a configuration mistake.
+fn is_scannable_dir(dir: &Path) -> bool {
+    if dir.is_dir() {
+        return true;
+    }
+    if dir.exists() {
+        eprintln!("Warning: Skipping plugin directory {:?}: not a directory", dir);
+    }
+    false
+}
+
+/// Scans a single directory for plugins of a specific format, appending them to `plugins`.
+/// VST3 vendor/category subfolders are searched too. Entries are recognized (which
+/// reads their metadata) on up to `workers` threads.
+/// Broken symlinks are appended to `broken_symlinks` instead of being recognized.
+/// Stops between entries if `cancel` is triggered.
+fn scan_directory(
+    dir: &Path,
+    format: &PluginFormat,
+    workers: usize,
+    cancel: Option<&CancellationToken>,
+    plugins: &mut Vec<InstalledPlugin>,
+    broken_symlinks: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let mut candidates = Vec::new();
+    collect_candidates(dir, format, 0, &mut candidates, broken_symlinks)?;
+    plugins.extend(recognize_plugins(candidates, format, workers, cancel));
+    Ok(())
+}
+
+/// Lists the entries of a plugin directory, descending into nested plugin folders.
+fn collect_candidates(dir: &Path, format: &PluginFormat, depth: usize, candidates: &mut Vec<PathBuf>, broken_symlinks: &mut Vec<PathBuf>) -> Result<()> {
     let entries = fs::read_dir(dir)
         .context(format!("Failed to read directory: {:?}", dir))?;
     
     for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
-        
-        // On macOS, plugins are bundles (directories)
-        // On Windows, most plugins are DLLs (files), except some VST3 can be bundles
-        let is_expected_type = if is_bundle {
-            path.is_dir()
+        let path = entry?.path();
+        if is_broken_symlink(&path) {
+            broken_symlinks.push(path);
+        } else if is_incomplete_install(&path) {
+            // Left by an interrupted installer; see detect_incomplete_installs()
+            continue;
+        } else if depth < MAX_NESTED_DEPTH && is_nested_plugin_folder(&path, format) {
+            if let Err(e) = collect_candidates(&path, format, depth + 1, candidates, broken_symlinks) {
+                eprintln!("Warning: Failed to scan directory {:?}: {}", path, e);
+            }
         } else {
-            path.is_file()
+            candidates.push(path);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Returns where `path` points if it is itself a symlink (following chains of
+/// links), or `path` unchanged otherwise. Unlike `fs::canonicalize()`, symlinked
+/// parent folders are kept and Windows paths don't get the `\\?\` prefix, so
+/// an ordinary plugin in a linked folder isn't reported as symlinked.
+pub(crate) fn resolve_symlink(path: &Path) -> PathBuf {
+    let mut resolved = path.to_path_buf();
+    // Bounded, so a link cycle can't loop forever
+    for _ in 0..32 {
+        if !fs::symlink_metadata(&resolved).is_ok_and(|m| m.file_type().is_symlink()) {
+            break;
+        }
+        let Ok(target) = fs::read_link(&resolved) else {
+            break;
         };
-        
-        if !is_expected_type {
-            continue;
+        resolved = match resolved.parent() {
+            Some(parent) if target.is_relative() => parent.join(target),
+            _ => target,
+        };
+    }
+    resolved
+}
+
+/// Returns true if `path` is a symlink whose target doesn't exist.
+fn is_broken_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink()) && fs::metadata(path).is_err()
+}
+
+/// Recognizes candidate paths on a bounded pool of threads, keeping their order.
+/// Each entry is independent: one that fails to parse is reported and skipped
+/// by `recognize_plugin()` without affecting the others.
+fn recognize_plugins(candidates: Vec<PathBuf>, format: &PluginFormat, workers: usize, cancel: Option<&CancellationToken>) -> Vec<InstalledPlugin> {
+    let is_cancelled = || cancel.is_some_and(|c| c.is_cancelled());
+    let workers = workers.clamp(1, candidates.len().max(1));
+    
+    if workers == 1 {
+        return candidates.into_iter()
+            .take_while(|_| !is_cancelled())
+            .flat_map(|path| recognize_plugin_entries(path, format))
+            .collect();
+    }
+    
+    let next = AtomicUsize::new(0);
+    let found = Mutex::new(Vec::new());
+    
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while !is_cancelled() {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = candidates.get(index) else {
+                        break;
+                    };
+                    let entries = recognize_plugin_entries(path.clone(), format);
+                    if let Ok(mut found) = found.lock() {
+                        found.extend(entries.into_iter().map(|plugin| (index, plugin)));
+                    }
+                }
+            });
+        }
+    });
+    
+    let mut found = found.into_inner().unwrap_or_default();
+    found.sort_by_key(|(index, _)| *index);
+    found.into_iter().map(|(_, plugin)| plugin).collect()
+}
+
+/// Lazily scans the same directories as `scan_installed()`, yielding plugins as they are found.
+/// Unlike `scan_installed()`, icons are not shared between formats; call
+/// `share_icons_between_formats()` on the collected results if needed.
+pub fn scan_iter() -> ScanIter {
+    scan_iter_with(ScanConfig::default())
+}
+
+/// Like `scan_iter()`, scanning the directories of `config` and leaving out the
+/// plugins it excludes.
+pub fn scan_iter_with(config: ScanConfig) -> ScanIter {
+    match config.directories() {
+        Ok(dirs) => ScanIter::new(dirs, config),
+        Err(e) => {
+            let mut iter = ScanIter::new(Vec::new(), config);
+            iter.pending_error = Some(e);
+            iter
+        }
+    }
+}
+
+/// Iterator over plugins found in a list of plugin directories.
+/// Directories are read one at a time, so only the current directory handle is held.
+pub struct ScanIter {
+    dirs: std::vec::IntoIter<(PathBuf, PluginFormat)>,
+    /// Directory being read, with its nesting depth below a plugin directory
+    current: Option<(fs::ReadDir, PluginFormat, usize)>,
+    /// Nested VST3 folders found but not yet read
+    nested: Vec<(PathBuf, PluginFormat, usize)>,
+    /// Remaining members of an expanded shell plugin, last one first
+    pending_members: Vec<InstalledPlugin>,
+    pending_error: Option<anyhow::Error>,
+    user_state: UserState,
+    config: ScanConfig,
+}
+
+impl ScanIter {
+    fn new(dirs: Vec<(PathBuf, PluginFormat)>, config: ScanConfig) -> Self {
+        ScanIter {
+            dirs: dirs.into_iter(),
+            current: None,
+            nested: Vec::new(),
+            pending_members: Vec::new(),
+            pending_error: None,
+            user_state: user_state(),
+            config,
+        }
+    }
+}
+
+impl Iterator for ScanIter {
+    type Item = Result<InstalledPlugin>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.pending_error.take() {
+            return Some(Err(e));
+        }
+        if let Some(member) = self.pending_members.pop() {
+            return Some(Ok(member));
         }
         
-        // Check the extension matches (e.g., Plugin.vst3, Plugin.component, Plugin.dll)
-        if let Some(ext) = path.extension() {
-            if ext.to_str() == Some(extension) {
-                // Extract plugin metadata
-                // TODO: Parse actual plugin bundle metadata (Info.plist for AU/VST3, etc.)
-                if let Some(name) = path.file_stem() {
-                    let plugin_name = name.to_string_lossy().to_string();
-                    
-                    // Discover icon from plugin bundle or local files
-                    let icon_url = discover_plugin_icon(&path, &plugin_name);
-                    
-                    // Create a minimal Plugin entry
-                    let plugin = Plugin {
-                        id: format!("{}.{}", format!("{:?}", format).to_lowercase(), plugin_name.to_lowercase().replace(" ", "-")),
-                        name: plugin_name.clone(),
-                        version: String::from("unknown"), // TODO: Extract from bundle
-                        description: Some(format!("{:?} plugin", format)),
-                        author: None, // TODO: Extract from bundle
-                        icon_url,
-                    };
-                    
-                    // Discover related files for this plugin
-                    let related_paths = discover_related_paths(&plugin_name, format);
-                    
-                    plugins.push(InstalledPlugin {
-                        plugin,
-                        install_path: path,
-                        format: format.clone(),
-                        enabled: true, // TODO: Check if plugin is disabled in DAW settings
-                        related_paths,
-                    });
+        loop {
+            if let Some((entries, format, depth)) = &mut self.current {
+                match entries.next() {
+                    Some(Ok(entry)) => {
+                        let path = entry.path();
+                        if is_broken_symlink(&path) {
+                            return Some(Err(anyhow::anyhow!("Broken symlink in plugin directory: {:?}", path)));
+                        } else if *depth < MAX_NESTED_DEPTH && is_nested_plugin_folder(&path, format) {
+                            self.nested.push((path, *format, *depth + 1));
+                        } else {
+                            let mut entries = recognize_plugin_entries(path, format);
+                            entries.retain(|plugin| !self.config.is_excluded(plugin));
+                            self.user_state.apply(&mut entries);
+                            entries.reverse();
+                            if let Some(plugin) = entries.pop() {
+                                self.pending_members = entries;
+                                return Some(Ok(plugin));
+                            }
+                        }
+                    }
+                    Some(Err(e)) => return Some(Err(anyhow::Error::new(e).context("Failed to read directory entry"))),
+                    None => self.current = None,
+                }
+                continue;
+            }
+            
+            // Finish nested folders before moving on to the next plugin directory
+            let (dir, format, depth) = match self.nested.pop() {
+                Some(nested) => nested,
+                None => {
+                    let (dir, format) = self.dirs.next()?;
+                    (dir, format, 0)
+                }
+            };
+            
+            if !is_scannable_dir(&dir) {
+                continue;
+            }
+            
+            match fs::read_dir(&dir) {
+                Ok(entries) => self.current = Some((entries, format, depth)),
+                Err(e) => {
+                    return Some(Err(anyhow::Error::new(e).context(format!("Failed to scan directory {:?}", dir))));
                 }
             }
         }
     }
+}
+
+/// Recursively scans an arbitrary directory tree for plugins of any format.
+///
+/// Entries directly inside `root` are at depth 1; directories deeper than `max_depth`
+/// are not visited. Recognized bundles are treated as leaves and never descended into.
+pub fn scan_tree(root: &Path, max_depth: usize) -> Result<Vec<InstalledPlugin>> {
+    if !root.is_dir() {
+        anyhow::bail!("Scan root is not a directory: {:?}", root);
+    }
     
+    let mut plugins = Vec::new();
+    scan_tree_recursive(root, 1, max_depth, &mut plugins)?;
+    apply_user_state(&mut plugins);
+    sort_plugins(&mut plugins);
     Ok(plugins)
 }
 
+fn scan_tree_recursive(dir: &Path, depth: usize, max_depth: usize, plugins: &mut Vec<InstalledPlugin>) -> Result<()> {
+    if depth > max_depth {
+        return Ok(());
+    }
+    
+    let entries = fs::read_dir(dir)
+        .context(format!("Failed to read directory: {:?}", dir))?;
+    
+    for entry in entries.flatten() {
+        let path = entry.path();
+        
+        if let Some(format) = detect_format(&path) {
+            plugins.extend(recognize_plugin_entries(path, &format));
+        } else if path.is_dir() {
+            if let Err(e) = scan_tree_recursive(&path, depth + 1, max_depth, plugins) {
+                eprintln!("Warning: Failed to scan directory {:?}: {}", path, e);
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// Plugin formats that can be installed on the current platform.
+fn platform_formats() -> &'static [PluginFormat] {
+    #[cfg(target_os = "macos")]
+    {
+        &[PluginFormat::AU, PluginFormat::VST2, PluginFormat::VST3, PluginFormat::AAX, PluginFormat::LV2]
+    }
+    
+    #[cfg(target_os = "windows")]
+    {
+        &[PluginFormat::VST2, PluginFormat::VST3, PluginFormat::AAX, PluginFormat::LV2]
+    }
+    
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        &[PluginFormat::LV2]
+    }
+}
+
+/// Determines the plugin format of a path from its extension and type (bundle or file).
+fn detect_format(path: &Path) -> Option<PluginFormat> {
+    let effective_path = strip_disabled_extension(path);
+    let ext = effective_path.extension()?.to_str()?;
+    
+    platform_formats().iter()
+        .find(|format| {
+            let is_expected_type = if format.is_bundle() { path.is_dir() } else { path.is_file() };
+            format.extension().eq_ignore_ascii_case(ext) && is_expected_type
+        })
+        .cloned()
+}
+
+/// Returns true if `path` is a plugin bundle (a directory) of a format supported on this platform.
+pub(crate) fn is_plugin_bundle(path: &Path) -> bool {
+    path.is_dir() && detect_format(path).is_some_and(|format| format.is_bundle())
+}
+
+/// Builds an `InstalledPlugin` if `path` is a plugin of the given format.
+/// Returns None for entries with the wrong type or extension.
+pub(crate) fn recognize_plugin(path: PathBuf, format: &PluginFormat) -> Option<InstalledPlugin> {
+    // On macOS, plugins are bundles (directories)
+    // On Windows, most plugins are DLLs (files), except some VST3 can be bundles
+    let is_expected_type = if format.is_bundle() {
+        path.is_dir()
+    } else {
+        path.is_file()
+    };
+    
+    if !is_expected_type {
+        return None;
+    }
+    
+    // Check the extension matches (e.g., Plugin.vst3, Plugin.component, Plugin.dll),
+    // in any case, ignoring the ".disabled" suffix of disabled file-based plugins
+    let effective_path = strip_disabled_extension(&path);
+    if !effective_path.extension()?.to_str()?.eq_ignore_ascii_case(format.extension()) {
+        return None;
+    }
+    
+    let plugin_name = effective_path.file_stem()?.to_string_lossy().to_string();
+    let known_shell = is_known_shell(&plugin_name);
+    let enabled = !is_disabled_path(&path);
+    // Before anything below reads the plugin and updates its access time
+    let binary_accessed = binary_accessed(&path);
+    
+    // Extract version/vendor from bundle metadata (cached by path + mtime)
+    let metadata = cached_bundle_metadata(&path, format).unwrap_or_else(|e| {
+        eprintln!("Warning: Failed to read metadata for {:?}: {}", path, e);
+        BundleMetadata::default()
+    });
+    
+    // Discover icon from plugin bundle or local files
+    let icon_url = discover_plugin_icon(&path, &plugin_name);
+    
+    // LV2 bundle names are often abbreviated ("fil4.lv2"); prefer the declared name
+    let plugin_name = metadata.name.clone().unwrap_or(plugin_name);
+    
+    let description = match metadata.aax_type {
+        Some(AaxType::Dsp) => String::from("AAX DSP plugin"),
+        _ => format!("{:?} plugin", format),
+    };
+    
+    // A packager's sidecar overrides what was detected
+    let sidecar = read_sidecar(&effective_path).unwrap_or_default();
+    let members = sidecar.members.unwrap_or(metadata.members);
+    let plugin_name = sidecar.name.unwrap_or(plugin_name);
+    let description = sidecar.description.unwrap_or(description);
+    let vendor = sidecar.vendor.or(metadata.vendor);
+    let tags = match sidecar.tags {
+        Some(tags) => {
+            let mut normalized: Vec<String> = Vec::new();
+            for tag in tags.iter().map(|tag| normalize_tag(tag)) {
+                if !tag.is_empty() && !normalized.contains(&tag) {
+                    normalized.push(tag);
+                }
+            }
+            normalized
+        }
+        None => inferred_tags(metadata.category.as_deref()),
+    };
+    
+    // Create a minimal Plugin entry
+    let plugin = Plugin {
+        id: plugin_id(format, &plugin_name),
+        name: plugin_name.clone(),
+        original_name: None,
+        version: metadata.version.unwrap_or_else(|| String::from("unknown")),
+        description: Some(description),
+        author: vendor,
+        icon_url,
+        tags,
+    };
+    
+    // Discover related files for this plugin
+    let related_paths = discover_related_paths(&plugin_name, plugin.author.as_deref(), format);
+    let last_accessed = binary_accessed.max(presets_accessed(&related_paths));
+    
+    // Symlinked plugins keep both the link path and the real target
+    let resolved_path = resolve_symlink(&path);
+    let architecture = detect_architecture(&path);
+    let scope = scope_of(&path, &user_plugin_roots());
+    
+    let mut installed = InstalledPlugin {
+        plugin,
+        install_path: path,
+        resolved_path,
+        format: *format,
+        uid: metadata.uid,
+        enabled, // TODO: Also check if plugin is disabled in DAW settings
+        scope,
+        shell: (members.len() > 1 || known_shell).then(|| plugin_name.clone()),
+        architecture,
+        related_paths,
+        last_accessed,
+        license: None,
+    };
+    installed.license = detect_license(&installed);
+    
+    Some(installed)
+}
+
+/// ID of a plugin from its format and name, e.g., "vst3.pro-q-3".
+fn plugin_id(format: &PluginFormat, name: &str) -> String {
+    format!("{}.{}", format!("{:?}", format).to_lowercase(), slugify(name))
+}
+
+/// The plugin's ID as versions before slugified IDs built it: the lowercased name
+/// with spaces turned into dashes, e.g., "vst3.pro-q-3-(mono)" rather than
+/// "vst3.pro-q-3-mono". User state, scan configurations, inventories, and audit
+/// logs written by those versions refer to plugins by it.
+pub fn legacy_plugin_id(plugin: &InstalledPlugin) -> String {
+    // IDs come from the scanned name, not a custom display name
+    let name = plugin.plugin.original_name.as_deref().unwrap_or(&plugin.plugin.name);
+    format!("{}.{}", format!("{:?}", plugin.format).to_lowercase(), name.to_lowercase().replace(" ", "-"))
+}
+
+/// Returns true if `id` is the plugin's ID, or its ID from before slugified IDs
+/// (see `legacy_plugin_id()`), e.g., to find a plugin's entries in an older
+/// audit log.
+pub fn matches_plugin_id(plugin: &InstalledPlugin, id: &str) -> bool {
+    plugin.plugin.id == id || legacy_plugin_id(plugin) == id
+}
+
+/// Turns a plugin name into a lowercase ASCII slug that is safe in file names and
+/// URLs: accented Latin letters lose their accents, and every run of other
+/// characters becomes a single dash, e.g., "EQ/Comp (v2)" -> "eq-comp-v2" and
+/// "Über-Synth" -> "uber-synth". Names with no usable characters (e.g., only CJK)
+/// get a short hash of the name so the slug is still unique and stable.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_lowercase) {
+        match fold_to_ascii(c) {
+            Some(folded) => slug.push_str(folded),
+            None if c.is_ascii_alphanumeric() => slug.push(c),
+            None => {
+                if !slug.is_empty() && !slug.ends_with('-') {
+                    slug.push('-');
+                }
+            }
+        }
+    }
+    
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        format!("{:x}", md5::compute(name.as_bytes()))[..8].to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// ASCII spelling of a lowercase accented Latin letter.
+fn fold_to_ascii(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => "a",
+        'æ' => "ae",
+        'ç' | 'č' | 'ć' => "c",
+        'ď' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ě' => "e",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => "i",
+        'ł' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => "o",
+        'œ' => "oe",
+        'ř' => "r",
+        'š' | 'ś' => "s",
+        'ß' => "ss",
+        'ť' => "t",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' => "u",
+        'ý' | 'ÿ' => "y",
+        'ž' | 'ź' | 'ż' => "z",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+/// File name patterns (without extension) of shell plugins whose binaries don't
+/// list the plugins they provide
+pub const KNOWN_SHELL_PATTERNS: &[&str] = &["WaveShell*"];
+
+fn is_known_shell(name: &str) -> bool {
+    let options = glob::MatchOptions { case_sensitive: false, ..Default::default() };
+    KNOWN_SHELL_PATTERNS.iter()
+        .filter_map(|pattern| glob::Pattern::new(pattern).ok())
+        .any(|pattern| pattern.matches_with(name, options))
+}
+
+/// Recognizes `path` like `recognize_plugin()`, expanding a shell plugin that
+/// declares its members (in its metadata or a sidecar's `members`) into one entry
+/// per member. Members keep the shell's install path, metadata, and icon, and get
+/// their own ID, name, and related paths.
+pub(crate) fn recognize_plugin_entries(path: PathBuf, format: &PluginFormat) -> Vec<InstalledPlugin> {
+    let Some(plugin) = recognize_plugin(path, format) else {
+        return Vec::new();
+    };
+    if plugin.shell.is_none() {
+        return vec![plugin];
+    }
+    
+    let members = read_sidecar(&strip_disabled_extension(&plugin.install_path))
+        .and_then(|sidecar| sidecar.members)
+        .or_else(|| cached_bundle_metadata(&plugin.install_path, format).ok().map(|metadata| metadata.members))
+        .unwrap_or_default();
+    if members.len() < 2 {
+        return vec![plugin];
+    }
+    
+    members.iter()
+        .map(|name| {
+            let mut member = plugin.clone();
+            member.plugin.id = plugin_id(format, name);
+            member.plugin.name = name.clone();
+            // The shell's UID is shared by all members, so it can't tell them apart
+            member.uid = None;
+            member.related_paths = discover_related_paths(name, plugin.plugin.author.as_deref(), format);
+            member
+        })
+        .collect()
+}
+
+/// Re-reads a single plugin from disk, e.g., after the user installed an update,
+/// without rescanning everything. Metadata is read fresh rather than from the cache.
+/// Returns None if the plugin is no longer at its install path, or if it's a shell
+/// member its shell no longer provides.
+pub fn refresh_plugin(plugin: &InstalledPlugin) -> Option<InstalledPlugin> {
+    metadata_cache().invalidate(&plugin.install_path);
+    let entries = recognize_plugin_entries(plugin.install_path.clone(), &plugin.format);
+    // A shell member is refreshed from its shell's new member list. A plain plugin
+    // is the only entry at its path, even if an update renamed it (changing its ID).
+    let mut refreshed = match entries.iter().find(|entry| entry.plugin.id == plugin.plugin.id) {
+        Some(entry) => entry.clone(),
+        None if plugin.shell.is_none() && entries.len() == 1 => entries[0].clone(),
+        None => return None,
+    };
+    apply_user_state(std::slice::from_mut(&mut refreshed));
+    
+    // Keep an icon shared from another format (see share_icons_between_formats())
+    if refreshed.plugin.icon_url.is_none() {
+        refreshed.plugin.icon_url = plugin.plugin.icon_url.clone();
+    }
+    Some(refreshed)
+}
+
+/// Tags implied by a plugin's metadata category (e.g., VST3 "Fx|EQ" → "fx", "eq").
+fn inferred_tags(category: Option<&str>) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in category.into_iter().flat_map(|category| category.split('|')).map(normalize_tag) {
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
 /// Share icons between VST2 and VST3 versions of the same plugin.
 /// If a VST2 plugin doesn't have an icon but a VST3 version exists with an icon, use it.
-fn share_icons_between_formats(plugins: &mut [InstalledPlugin]) {
+pub fn share_icons_between_formats(plugins: &mut [InstalledPlugin]) {
     // Build a map of plugin names to their icons by format
     let mut vst3_icons: std::collections::HashMap<String, String> = std::collections::HashMap::new();
     
@@ -219,12 +1253,10 @@ fn share_icons_between_formats(plugins: &mut [InstalledPlugin]) {
     
     // Second pass: apply VST3 icons to VST2 plugins that don't have icons
     for plugin in plugins.iter_mut() {
-        if matches!(plugin.format, PluginFormat::VST2) {
-            if plugin.plugin.icon_url.is_none() {
-                let normalized_name = normalize_plugin_name(&plugin.plugin.name);
-                if let Some(vst3_icon) = vst3_icons.get(&normalized_name) {
-                    plugin.plugin.icon_url = Some(vst3_icon.clone());
-                }
+        if matches!(plugin.format, PluginFormat::VST2) && plugin.plugin.icon_url.is_none() {
+            let normalized_name = normalize_plugin_name(&plugin.plugin.name);
+            if let Some(vst3_icon) = vst3_icons.get(&normalized_name) {
+                plugin.plugin.icon_url = Some(vst3_icon.clone());
             }
         }
     }
@@ -232,7 +1264,7 @@ fn share_icons_between_formats(plugins: &mut [InstalledPlugin]) {
 
 /// Normalize a plugin name for matching between formats.
 /// Removes common suffixes, spaces, and converts to lowercase.
-fn normalize_plugin_name(name: &str) -> String {
+pub(crate) fn normalize_plugin_name(name: &str) -> String {
     let mut normalized = name.to_lowercase();
     
     // Remove common version suffixes and architecture markers
@@ -260,50 +1292,119 @@ fn normalize_plugin_name(name: &str) -> String {
     normalized
 }
 
+/// Bucket name used by `group_by_vendor()` for plugins without a known vendor.
+pub const UNKNOWN_VENDOR: &str = "Unknown";
+
+/// Groups plugins by vendor for a vendor-tree display.
+/// Vendor names are matched ignoring case and whitespace runs (trimmed, inner runs
+/// collapsed to one space); the first casing seen is used as the group name.
+/// Plugins without a vendor go into the "Unknown" bucket.
+pub fn group_by_vendor(plugins: &[InstalledPlugin]) -> BTreeMap<String, Vec<InstalledPlugin>> {
+    let mut display_names: HashMap<String, String> = HashMap::new();
+    let mut groups: BTreeMap<String, Vec<InstalledPlugin>> = BTreeMap::new();
+
+    for plugin in plugins {
+        let vendor = plugin.plugin.author.as_deref()
+            .map(|v| v.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|v| !v.is_empty());
+
+        let group_name = match vendor {
+            Some(vendor) => display_names
+                .entry(vendor.to_lowercase())
+                .or_insert(vendor)
+                .clone(),
+            None => UNKNOWN_VENDOR.to_string(),
+        };
+
+        groups.entry(group_name).or_default().push(plugin.clone());
+    }
+
+    groups
+}
+
+/// Scans installed plugins and counts them per vendor, e.g., for a vendor dashboard.
+/// See `vendor_summary_of()`.
+pub fn vendor_summary() -> Result<Vec<(String, usize)>> {
+    Ok(vendor_summary_of(&scan_installed()?))
+}
+
+/// Counts `plugins` per vendor, grouped as in `group_by_vendor()` (including the
+/// "Unknown" bucket), most plugins first; vendors with equal counts are sorted by name.
+pub fn vendor_summary_of(plugins: &[InstalledPlugin]) -> Vec<(String, usize)> {
+    let mut summary: Vec<(String, usize)> = group_by_vendor(plugins).into_iter()
+        .map(|(vendor, plugins)| (vendor, plugins.len()))
+        .collect();
+    // The groups come sorted by name, so a stable sort keeps ties alphabetical
+    summary.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    summary
+}
+
+/// Selects the plugins whose name matches a glob pattern (case-insensitive),
+/// e.g., `Waves *` or `Pro-?`. Fails if the pattern is invalid.
+pub fn filter_plugins<'a>(plugins: &'a [InstalledPlugin], pattern: &str) -> Result<Vec<&'a InstalledPlugin>> {
+    let pattern = glob::Pattern::new(pattern)
+        .context(format!("Invalid plugin name pattern: {:?}", pattern))?;
+    let options = glob::MatchOptions {
+        case_sensitive: false,
+        ..Default::default()
+    };
+
+    Ok(plugins.iter()
+        .filter(|plugin| pattern.matches_with(&plugin.plugin.name, options))
+        .collect())
+}
+
 /// Discovers related files and folders for a plugin (presets, libraries, support files).
 /// This scans common locations where plugins store their data.
-fn discover_related_paths(plugin_name: &str, _format: &PluginFormat) -> RelatedPaths {
+/// Each path's confidence reflects how specifically it matched: inside a vendor folder
+/// (High), a plugin-named folder in a standard location (Medium), or a loose name
+/// match in a generic folder like Documents (Low).
+fn discover_related_paths(plugin_name: &str, vendor: Option<&str>, _format: &PluginFormat) -> RelatedPaths {
     let mut paths = RelatedPaths::default();
     
     #[cfg(target_os = "macos")]
     {
         if let Ok(home) = std::env::var("HOME") {
             // Common preset locations on macOS
-            let preset_candidates = vec![
-                format!("{}/Music/{}", home, plugin_name),
-                format!("{}/Library/Audio/Presets/{}", home, plugin_name),
-                format!("{}/Documents/{}", home, plugin_name),
-                format!("{}/Documents/{} Library", home, plugin_name),
+            let mut preset_candidates = vec![
+                (format!("{}/Library/Audio/Presets/{}", home, plugin_name), Confidence::Medium),
+                (format!("{}/Music/{}", home, plugin_name), Confidence::Low),
+                (format!("{}/Documents/{}", home, plugin_name), Confidence::Low),
+                (format!("{}/Documents/{} Library", home, plugin_name), Confidence::Low),
             ];
             
             // Common library/content locations
-            let library_candidates = vec![
-                format!("/Library/Application Support/{}", plugin_name),
-                format!("{}/Library/Application Support/{}", home, plugin_name),
-                format!("/Library/Audio/Sounds/{}", plugin_name),
-                format!("{}/Library/Audio/Sounds/{}", home, plugin_name),
+            let mut library_candidates = vec![
+                (format!("/Library/Application Support/{}", plugin_name), Confidence::Medium),
+                (format!("{}/Library/Application Support/{}", home, plugin_name), Confidence::Medium),
+                (format!("/Library/Audio/Sounds/{}", plugin_name), Confidence::Medium),
+                (format!("{}/Library/Audio/Sounds/{}", home, plugin_name), Confidence::Medium),
             ];
             
             // Preferences locations
             let pref_candidates = vec![
-                format!("{}/Library/Preferences/com.{}.plist", home, plugin_name.to_lowercase().replace(" ", "")),
-                format!("{}/Library/Preferences/{}.plist", home, plugin_name.replace(" ", "")),
+                (format!("{}/Library/Preferences/com.{}.plist", home, plugin_name.to_lowercase().replace(" ", "")), Confidence::Medium),
+                (format!("{}/Library/Preferences/{}.plist", home, plugin_name.replace(" ", "")), Confidence::Medium),
             ];
             
-            paths.preset_locations = preset_candidates.into_iter()
-                .map(PathBuf::from)
-                .filter(|p| p.exists())
-                .collect();
+            // AU presets live in <Manufacturer>/<Plugin>, and many vendors nest content the same way
+            if let Some(vendor) = vendor {
+                preset_candidates.insert(0, (format!("{}/Library/Audio/Presets/{}/{}", home, vendor, plugin_name), Confidence::High));
+                library_candidates.insert(0, (format!("/Library/Application Support/{}/{}", vendor, plugin_name), Confidence::High));
+                library_candidates.insert(1, (format!("{}/Library/Application Support/{}/{}", home, vendor, plugin_name), Confidence::High));
+            }
             
-            paths.library_locations = library_candidates.into_iter()
-                .map(PathBuf::from)
-                .filter(|p| p.exists())
-                .collect();
+            paths.preset_locations = existing_related_paths(preset_candidates);
+            paths.library_locations = existing_related_paths(library_candidates);
+            paths.preference_files = existing_related_paths(pref_candidates);
             
-            paths.preference_files = pref_candidates.into_iter()
-                .map(PathBuf::from)
-                .filter(|p| p.exists())
-                .collect();
+            if let Some(vendor) = vendor {
+                for related in vendor_preference_files(&home, vendor, plugin_name) {
+                    if !paths.preference_files.iter().any(|known| known.path == related.path) {
+                        paths.preference_files.push(related);
+                    }
+                }
+            }
         }
     }
     
@@ -311,48 +1412,212 @@ fn discover_related_paths(plugin_name: &str, _format: &PluginFormat) -> RelatedP
     {
         // Common locations on Windows
         if let Ok(appdata) = std::env::var("APPDATA") {
-            let preset_candidates = vec![
-                format!(r"{}\{}", appdata, plugin_name),
-                format!(r"{}\{}\Presets", appdata, plugin_name),
+            let mut preset_candidates = vec![
+                (format!(r"{}\{}", appdata, plugin_name), Confidence::Medium),
+                (format!(r"{}\{}\Presets", appdata, plugin_name), Confidence::Medium),
             ];
+            if let Some(vendor) = vendor {
+                preset_candidates.insert(0, (format!(r"{}\{}\{}", appdata, vendor, plugin_name), Confidence::High));
+            }
             
-            paths.preset_locations = preset_candidates.into_iter()
-                .map(PathBuf::from)
-                .filter(|p| p.exists())
-                .collect();
+            paths.preset_locations = existing_related_paths(preset_candidates);
         }
         
         if let Ok(programdata) = std::env::var("PROGRAMDATA") {
-            let library_candidates = vec![
-                format!(r"{}\{}", programdata, plugin_name),
+            let mut library_candidates = vec![
+                (format!(r"{}\{}", programdata, plugin_name), Confidence::Medium),
             ];
+            if let Some(vendor) = vendor {
+                library_candidates.insert(0, (format!(r"{}\{}\{}", programdata, vendor, plugin_name), Confidence::High));
+            }
             
-            paths.library_locations = library_candidates.into_iter()
-                .map(PathBuf::from)
-                .filter(|p| p.exists())
-                .collect();
+            paths.library_locations = existing_related_paths(library_candidates);
         }
         
         // TODO: Check registry for additional paths
     }
     
+    apply_related_path_rules(&mut paths, plugin_name, vendor);
+    
     paths
 }
 
+/// A user-defined location of related files, for vendors that keep content in
+/// folders the built-in discovery doesn't know about.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedPathRule {
+    pub category: RelatedCategory,
+    /// Path pattern. `{home}`, `{name}`, and `{vendor}` are replaced with the user's
+    /// home folder, the plugin name, and its vendor; glob wildcards (`*`, `?`, `[...]`)
+    /// match versioned folders, e.g., `{home}/Documents/Native Instruments/Kontakt */Presets`
+    pub pattern: String,
+}
+
+/// Custom related-path rules applied by every scan, set at runtime
+static RELATED_PATH_RULES: RwLock<Vec<RelatedPathRule>> = RwLock::new(Vec::new());
+
+/// Adds a rule that every scan from now on uses to discover related paths.
+/// Adding the same rule twice has no effect.
+pub fn add_related_path_rule(rule: RelatedPathRule) {
+    let mut rules = RELATED_PATH_RULES.write().unwrap_or_else(|e| e.into_inner());
+    if !rules.contains(&rule) {
+        rules.push(rule);
+    }
+}
+
+/// Removes every rule added with `add_related_path_rule()`.
+pub fn clear_related_path_rules() {
+    RELATED_PATH_RULES.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// Returns the rules added with `add_related_path_rule()`.
+pub fn related_path_rules() -> Vec<RelatedPathRule> {
+    RELATED_PATH_RULES.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Adds the existing matches of the custom rules to `paths`. The user named these
+/// locations explicitly, so they get high confidence.
+fn apply_related_path_rules(paths: &mut RelatedPaths, plugin_name: &str, vendor: Option<&str>) {
+    let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")).ok();
+    
+    for rule in related_path_rules() {
+        // Substituted values are literal text, not patterns
+        let mut pattern = rule.pattern.replace("{name}", &glob::Pattern::escape(plugin_name));
+        for (placeholder, value) in [("{home}", home.as_deref()), ("{vendor}", vendor)] {
+            if pattern.contains(placeholder) {
+                match value {
+                    Some(value) => pattern = pattern.replace(placeholder, &glob::Pattern::escape(value)),
+                    // The rule can't apply without the value
+                    None => pattern.clear(),
+                }
+            }
+        }
+        if pattern.is_empty() {
+            continue;
+        }
+        
+        let matches = match glob::glob(&pattern) {
+            Ok(matches) => matches,
+            Err(e) => {
+                eprintln!("Warning: Invalid related path rule {:?}: {}", rule.pattern, e);
+                continue;
+            }
+        };
+        
+        let category_paths = match rule.category {
+            RelatedCategory::Presets => &mut paths.preset_locations,
+            RelatedCategory::Libraries => &mut paths.library_locations,
+            RelatedCategory::Support => &mut paths.support_locations,
+            RelatedCategory::Preferences => &mut paths.preference_files,
+        };
+        for path in matches.flatten() {
+            if !category_paths.iter().any(|related| related.path == path) {
+                category_paths.push(RelatedPath::new(path, Confidence::High));
+            }
+        }
+    }
+}
+
+/// Finds preference files named after both the vendor and the plugin, which the
+/// fixed candidates miss: `com.<vendor>.<plugin>.plist` (e.g., `com.fabfilter.Pro-Q.3.plist`)
+/// and files in a `Preferences/<Vendor>/` folder. Names are compared word by word,
+/// ignoring case and punctuation (see `preference_name_match()`), to avoid picking
+/// up the preferences of the vendor's other plugins. A name that only contains the
+/// plugin's among other words gets a lower confidence than an exact one.
+#[cfg(target_os = "macos")]
+fn vendor_preference_files(home: &str, vendor: &str, plugin_name: &str) -> Vec<RelatedPath> {
+    let compact = |text: &str| -> String {
+        text.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+    };
+    let vendor_token = compact(vendor);
+    let plugin_words = name_words(plugin_name);
+    // Very short names ("EQ") would match unrelated files
+    if vendor_token.is_empty() || plugin_words.concat().len() < 3 {
+        return Vec::new();
+    }
+    
+    let options = glob::MatchOptions { case_sensitive: false, ..Default::default() };
+    let preferences = format!("{}/Library/Preferences", glob::Pattern::escape(home));
+    // The vendor part of the name ("com", vendor) doesn't count towards matching the
+    // plugin; confidences are for exact and partial matches
+    let patterns = [
+        (format!("{}/com.{}.*plist", preferences, vendor_token), 2, Confidence::High, Confidence::Medium),
+        (format!("{}/{}/*", preferences, glob::Pattern::escape(vendor)), 0, Confidence::Medium, Confidence::Low),
+    ];
+    
+    let mut found = Vec::new();
+    for (pattern, vendor_words, exact, partial) in patterns {
+        let matches = match glob::glob_with(&pattern, options) {
+            Ok(matches) => matches,
+            Err(_) => continue,
+        };
+        for path in matches.flatten() {
+            let name = if path.is_file() { path.file_stem() } else { path.file_name() };
+            let words = name.map(|n| name_words(&n.to_string_lossy())).unwrap_or_default();
+            let confidence = match preference_name_match(words.get(vendor_words..).unwrap_or_default(), &plugin_words) {
+                Some(true) => exact,
+                Some(false) => partial,
+                None => continue,
+            };
+            found.push(RelatedPath::new(path, confidence));
+        }
+    }
+    found
+}
+
+/// Splits a name into lowercase words at anything that isn't a letter or digit,
+/// e.g., "Pro-Q.3" into "pro", "q", "3".
+#[cfg(target_os = "macos")]
+fn name_words(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Compares the words of a file name with a plugin's: Some(true) if they spell the
+/// same name ("ProQ3" for "Pro-Q 3"), Some(false) if the plugin's words appear whole
+/// among others ("Pro-Q 3 Settings"), and None otherwise. A number or version right
+/// after the plugin's words names another plugin, so "Saturn 2" doesn't match "Saturn".
+#[cfg(target_os = "macos")]
+fn preference_name_match(words: &[String], plugin_words: &[String]) -> Option<bool> {
+    if words.concat() == plugin_words.concat() {
+        return Some(true);
+    }
+    
+    let is_version = |word: &String| {
+        let digits = word.strip_prefix('v').unwrap_or(word);
+        !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+    };
+    words.windows(plugin_words.len())
+        .enumerate()
+        .any(|(start, window)| window == plugin_words && !words.get(start + plugin_words.len()).is_some_and(is_version))
+        .then_some(false)
+}
+
+/// Keeps the candidate paths that exist on disk.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn existing_related_paths(candidates: Vec<(String, Confidence)>) -> Vec<RelatedPath> {
+    candidates.into_iter()
+        .map(|(path, confidence)| RelatedPath::new(PathBuf::from(path), confidence))
+        .filter(|related| related.path.exists())
+        .collect()
+}
+
 /// Discovers an icon for a plugin by searching in the plugin bundle.
 /// Returns a file:// URL to the local icon if found.
-fn discover_plugin_icon(plugin_path: &PathBuf, plugin_name: &str) -> Option<String> {
+fn discover_plugin_icon(plugin_path: &Path, plugin_name: &str) -> Option<String> {
     // For bundle-based plugins (macOS .component, .vst3, .vst, Windows .vst3)
     if plugin_path.is_dir() {
         // Common icon locations within bundles
-        let icon_search_paths = vec![
+        let icon_search_paths = [
             "Contents/Resources",
             "Resources",
             "Contents",
         ];
         
         // Common icon file extensions
-        let icon_extensions = vec!["icns", "png", "ico", "jpg", "jpeg"];
+        let icon_extensions = ["icns", "png", "ico", "jpg", "jpeg"];
         
         for search_path in &icon_search_paths {
             let resource_dir = plugin_path.join(search_path);
@@ -380,8 +1645,7 @@ fn discover_plugin_icon(plugin_path: &PathBuf, plugin_name: &str) -> Option<Stri
                                     || file_name == "appicon"
                                     || file_name.starts_with("icon")
                                     || file_name.starts_with("logo") {
-                                    // Return file:// URL
-                                    return Some(format!("file://{}", entry_path.display()));
+                                    return Some(bundle_icon_url(&entry_path));
                                 }
                             }
                         }
@@ -405,7 +1669,7 @@ fn discover_plugin_icon(plugin_path: &PathBuf, plugin_name: &str) -> Option<Stri
                             let ext_str = ext.to_string_lossy().to_lowercase();
                             if icon_extensions.contains(&ext_str.as_str()) {
                                 // Return the first icon we find
-                                return Some(format!("file://{}", entry_path.display()));
+                                return Some(bundle_icon_url(&entry_path));
                             }
                         }
                     }
@@ -438,6 +1702,20 @@ fn discover_plugin_icon(plugin_path: &PathBuf, plugin_name: &str) -> Option<Stri
     None
 }
 
+/// Returns a file:// URL for an icon found in a bundle. `.icns` icons are converted
+/// to a cached PNG of their largest size, which UIs can display directly; if that
+/// fails, the icns itself is used.
+fn bundle_icon_url(icon_path: &Path) -> String {
+    let is_icns = icon_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("icns"));
+    if is_icns {
+        match cache_icns_as_png(icon_path) {
+            Ok(png_path) => return format!("file://{}", png_path.display()),
+            Err(e) => eprintln!("Warning: Failed to convert icon {:?}: {}", icon_path, e),
+        }
+    }
+    format!("file://{}", icon_path.display())
+}
+
 /// Enumerates all files associated with a plugin for uninstall or backup.
 /// Returns a complete list of paths that should be removed/backed up.
 pub fn enumerate_plugin_files(plugin: &InstalledPlugin) -> Result<Vec<PathBuf>> {
@@ -447,24 +1725,73 @@ pub fn enumerate_plugin_files(plugin: &InstalledPlugin) -> Result<Vec<PathBuf>>
     files.push(plugin.install_path.clone());
     
     // Add all discovered related paths
-    for path in &plugin.related_paths.preset_locations {
-        files.extend(enumerate_directory_recursive(path)?);
+    for related in &plugin.related_paths.preset_locations {
+        files.extend(enumerate_directory_recursive(&related.path)?);
     }
     
-    for path in &plugin.related_paths.library_locations {
-        files.extend(enumerate_directory_recursive(path)?);
+    for related in &plugin.related_paths.library_locations {
+        files.extend(enumerate_directory_recursive(&related.path)?);
     }
     
-    for path in &plugin.related_paths.support_locations {
-        files.extend(enumerate_directory_recursive(path)?);
+    for related in &plugin.related_paths.support_locations {
+        files.extend(enumerate_directory_recursive(&related.path)?);
     }
     
-    files.extend(plugin.related_paths.preference_files.clone());
+    files.extend(plugin.related_paths.preference_files.iter().map(|related| related.path.clone()));
     
     Ok(files)
 }
 
-/// Recursively enumerates all files in a directory.
+/// File name patterns left out of enumeration by default: metadata written by the
+/// OS or version control, never real plugin content. Other dotfiles are kept.
+pub const DEFAULT_SKIPPED_FILE_PATTERNS: &[&str] = &[
+    ".DS_Store",
+    "._*",
+    ".Spotlight-V100",
+    ".Trashes",
+    ".fseventsd",
+    "__MACOSX",
+    "Thumbs.db",
+    "desktop.ini",
+    ".git",
+    ".svn",
+];
+
+/// Skipped file patterns set at runtime; None uses `DEFAULT_SKIPPED_FILE_PATTERNS`
+static SKIPPED_FILE_PATTERNS: RwLock<Option<Vec<String>>> = RwLock::new(None);
+
+/// Replaces the glob patterns (matched against file and folder names) that
+/// `enumerate_plugin_files()` leaves out, and with it backup, export, and uninstall.
+/// Pass an empty list to include everything.
+pub fn set_skipped_file_patterns(patterns: Vec<String>) {
+    *SKIPPED_FILE_PATTERNS.write().unwrap_or_else(|e| e.into_inner()) = Some(patterns);
+}
+
+/// Restores `DEFAULT_SKIPPED_FILE_PATTERNS`.
+pub fn reset_skipped_file_patterns() {
+    *SKIPPED_FILE_PATTERNS.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Returns the patterns currently left out of enumeration.
+pub fn skipped_file_patterns() -> Vec<String> {
+    SKIPPED_FILE_PATTERNS.read().unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| DEFAULT_SKIPPED_FILE_PATTERNS.iter().map(|p| p.to_string()).collect())
+}
+
+/// Returns true if the path's name matches one of `skipped_file_patterns()`.
+pub fn is_skipped_file(path: &Path) -> bool {
+    let name = match path.file_name() {
+        Some(name) => name.to_string_lossy(),
+        None => return false,
+    };
+    skipped_file_patterns().iter()
+        .filter_map(|pattern| glob::Pattern::new(pattern).ok())
+        .any(|pattern| pattern.matches(&name))
+}
+
+/// Recursively enumerates all files in a directory, leaving out skipped files
+/// (see `is_skipped_file()`).
 fn enumerate_directory_recursive(dir: &PathBuf) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
     
@@ -480,10 +1807,19 @@ fn enumerate_directory_recursive(dir: &PathBuf) -> Result<Vec<PathBuf>> {
     let entries = fs::read_dir(dir)
         .context(format!("Failed to read directory: {:?}", dir))?;
     
+    let skipped: Vec<glob::Pattern> = skipped_file_patterns().iter()
+        .filter_map(|pattern| glob::Pattern::new(pattern).ok())
+        .collect();
+    
     for entry in entries {
         let entry = entry?;
         let path = entry.path();
         
+        let name = entry.file_name();
+        if skipped.iter().any(|pattern| pattern.matches(&name.to_string_lossy())) {
+            continue;
+        }
+        
         if path.is_dir() {
             files.extend(enumerate_directory_recursive(&path)?);
         } else {
@@ -495,47 +1831,182 @@ fn enumerate_directory_recursive(dir: &PathBuf) -> Result<Vec<PathBuf>> {
 }
 
 // TODO: Future functions to implement:
-// - extract_bundle_metadata() - Parse Info.plist from AU/VST3 bundles
 // - backup_plugin() - Create backup of plugin and its libraries
 // - remove_plugin() - Safely remove plugin and clean up libraries
 // - export_for_migration() - Package plugin for moving to another machine
 // - import_plugin() - Restore plugin from migration package
 
+/// Name patterns of partial files left by interrupted installers or downloads
+pub const DEFAULT_INCOMPLETE_INSTALL_PATTERNS: &[&str] = &[
+    "*.tmp",
+    "*.part",
+    "*.partial",
+    "*.download",
+    "*.crdownload",
+    "*~",
+];
+
+/// Incomplete install patterns set at runtime; None uses `DEFAULT_INCOMPLETE_INSTALL_PATTERNS`
+static INCOMPLETE_INSTALL_PATTERNS: RwLock<Option<Vec<String>>> = RwLock::new(None);
+
+/// Replaces the glob patterns (matched against file and folder names) that mark an
+/// entry of a plugin directory as an incomplete install.
+pub fn set_incomplete_install_patterns(patterns: Vec<String>) {
+    *INCOMPLETE_INSTALL_PATTERNS.write().unwrap_or_else(|e| e.into_inner()) = Some(patterns);
+}
+
+/// Restores `DEFAULT_INCOMPLETE_INSTALL_PATTERNS`.
+pub fn reset_incomplete_install_patterns() {
+    *INCOMPLETE_INSTALL_PATTERNS.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Returns the patterns currently marking incomplete installs.
+pub fn incomplete_install_patterns() -> Vec<String> {
+    INCOMPLETE_INSTALL_PATTERNS.read().unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| DEFAULT_INCOMPLETE_INSTALL_PATTERNS.iter().map(|p| p.to_string()).collect())
+}
+
+/// Returns true if the path's name matches one of `incomplete_install_patterns()`.
+pub fn is_incomplete_install(path: &Path) -> bool {
+    let name = match path.file_name() {
+        Some(name) => name.to_string_lossy(),
+        None => return false,
+    };
+    incomplete_install_patterns().iter()
+        .filter_map(|pattern| glob::Pattern::new(pattern).ok())
+        .any(|pattern| pattern.matches(&name))
+}
+
+/// Detects partial files left in plugin directories by interrupted installers
+/// (e.g., `Plugin.vst3.part`), including inside VST3 vendor/category folders.
+/// Scans skip these entries and `detect_orphaned_files()` doesn't report them,
+/// so the UI can offer to clean them up separately.
+pub fn detect_incomplete_installs() -> Result<Vec<PathBuf>> {
+    let mut incomplete = Vec::new();
+    for (dir, format) in get_plugin_directories()? {
+        if dir.is_dir() {
+            collect_incomplete_installs(&dir, &format, 0, &mut incomplete);
+        }
+    }
+    Ok(incomplete)
+}
+
+fn collect_incomplete_installs(dir: &Path, format: &PluginFormat, depth: usize, incomplete: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Warning: Failed to scan directory {:?}: {}", dir, e);
+            return;
+        }
+    };
+    
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if is_incomplete_install(&path) {
+            incomplete.push(path);
+        } else if depth < MAX_NESTED_DEPTH && is_nested_plugin_folder(&path, format) {
+            collect_incomplete_installs(&path, format, depth + 1, incomplete);
+        }
+    }
+}
+
+/// Finds plugins in the plugin directories whose extension differs from the
+/// canonical lowercase one only by case (e.g., `Plugin.VST3`), each paired with
+/// its canonical path. Scans accept either spelling, but some tools don't.
+pub(crate) fn detect_miscased_extensions() -> Result<Vec<(PathBuf, PathBuf)>> {
+    let mut miscased = Vec::new();
+    for (dir, format) in get_plugin_directories()? {
+        if dir.is_dir() {
+            collect_miscased_extensions(&dir, &format, 0, &mut miscased);
+        }
+    }
+    Ok(miscased)
+}
+
+fn collect_miscased_extensions(dir: &Path, format: &PluginFormat, depth: usize, miscased: &mut Vec<(PathBuf, PathBuf)>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Warning: Failed to scan directory {:?}: {}", dir, e);
+            return;
+        }
+    };
+    
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if let Some(canonical) = canonical_extension_path(&path, format) {
+            miscased.push((path, canonical));
+        } else if depth < MAX_NESTED_DEPTH && is_nested_plugin_folder(&path, format) {
+            collect_miscased_extensions(&path, format, depth + 1, miscased);
+        }
+    }
+}
+
+/// Returns the path of a plugin with its extension in canonical case, or None if
+/// it isn't a plugin of `format` or is already spelled canonically.
+fn canonical_extension_path(path: &Path, format: &PluginFormat) -> Option<PathBuf> {
+    let is_expected_type = if format.is_bundle() { path.is_dir() } else { path.is_file() };
+    let effective_path = strip_disabled_extension(path);
+    let ext = effective_path.extension()?.to_str()?;
+    if !is_expected_type || ext == format.extension() || !ext.eq_ignore_ascii_case(format.extension()) {
+        return None;
+    }
+    
+    let mut canonical = effective_path.with_extension(format.extension());
+    if effective_path != path {
+        canonical.as_mut_os_string().push(format!(".{}", DISABLED_FILE_EXTENSION));
+    }
+    Some(canonical)
+}
+
 /// Detects orphaned files - files in plugin directories that don't belong to any installed plugin.
-/// This helps identify leftovers from uninstalled plugins.
+/// This helps identify leftovers from uninstalled plugins. Incomplete installs are
+/// left to `detect_incomplete_installs()`.
 pub fn detect_orphaned_files() -> Result<Vec<PathBuf>> {
-    let plugin_dirs = get_plugin_directories()?;
-    let installed = scan_installed()?;
+    Ok(orphaned_files_in(&get_plugin_directories()?, &scan_installed()?))
+}
+
+/// Entries of `plugin_dirs` that belong to none of the `installed` plugins.
+fn orphaned_files_in(plugin_dirs: &[(PathBuf, PluginFormat)], installed: &[InstalledPlugin]) -> Vec<PathBuf> {
     let mut orphaned = Vec::new();
     
-    // Build a set of all known plugin paths
+    // Build a set of all known plugin paths, normalized so spelling differences
+    // (trailing slashes, "..", case) don't make a known path look orphaned
     let mut known_paths = std::collections::HashSet::new();
-    for plugin in &installed {
-        known_paths.insert(plugin.install_path.clone());
+    for plugin in installed {
+        known_paths.insert(normalize_path_for_comparison(&plugin.install_path));
+        if let Some(sidecar) = sidecar_path(&strip_disabled_extension(&plugin.install_path)) {
+            known_paths.insert(normalize_path_for_comparison(&sidecar));
+        }
         // Also add all related paths
-        for path in &plugin.related_paths.preset_locations {
-            known_paths.insert(path.clone());
+        for related in &plugin.related_paths.preset_locations {
+            known_paths.insert(normalize_path_for_comparison(&related.path));
         }
-        for path in &plugin.related_paths.library_locations {
-            known_paths.insert(path.clone());
+        for related in &plugin.related_paths.library_locations {
+            known_paths.insert(normalize_path_for_comparison(&related.path));
         }
     }
     
     // Scan each plugin directory for files not in our known set
     for (dir, _format) in plugin_dirs {
-        if !dir.exists() {
+        if !dir.is_dir() {
             continue;
         }
         
-        match fs::read_dir(&dir) {
+        match fs::read_dir(dir) {
             Ok(entries) => {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        let path = entry.path();
-                        // If this path is not in our known plugins, it's orphaned
-                        if !known_paths.contains(&path) {
-                            orphaned.push(path);
-                        }
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if is_incomplete_install(&path) {
+                        continue;
+                    }
+                    let normalized = normalize_path_for_comparison(&path);
+                    // If this path is not a known plugin, and no known plugin is nested
+                    // inside it (e.g., a VST3 vendor folder), it's orphaned
+                    if !known_paths.contains(&normalized)
+                        && !known_paths.iter().any(|known| known.starts_with(&normalized)) {
+                        orphaned.push(path);
                     }
                 }
             }
@@ -545,5 +2016,516 @@ pub fn detect_orphaned_files() -> Result<Vec<PathBuf>> {
         }
     }
     
-    Ok(orphaned)
-}
\ No newline at end of file
+    orphaned
+}
+
+/// Normalizes a path for equality checks without touching the filesystem:
+/// drops `.` components and trailing separators, resolves `..` lexically, and
+/// case-folds on platforms whose volumes are case-insensitive by default (macOS, Windows).
+pub(crate) fn normalize_path_for_comparison(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                // ".." at the root stays at the root
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    
+    if cfg!(any(target_os = "macos", target_os = "windows")) {
+        PathBuf::from(normalized.to_string_lossy().to_lowercase())
+    } else {
+        normalized
+    }
+}
+
+/// Estimates how many bytes would be freed by removing all detected orphaned files.
+/// Orphaned directories are measured recursively.
+pub fn orphaned_files_size() -> Result<u64> {
+    let orphaned = detect_orphaned_files()?;
+    Ok(orphaned.iter().map(|path| path_size(path)).sum())
+}
+
+/// Returns when the plugin was last used, as the most recent access time of its
+/// binary (the bundle's executable for bundles) and the presets in its preset folders.
+/// Scans record this in `InstalledPlugin::last_accessed` before reading anything
+/// from the plugin, since reading its metadata updates the access times; calling
+/// this after a scan may return the time of that scan.
+///
+/// This is only a heuristic: many systems mount volumes with access times disabled
+/// or updated lazily (`noatime`/`relatime` on Linux, and Windows disables last-access
+/// updates by default on large volumes), and backup or indexing tools can touch files
+/// too. Returns None if no access time is available.
+pub fn last_accessed(plugin: &InstalledPlugin) -> Option<SystemTime> {
+    binary_accessed(&plugin.install_path).max(presets_accessed(&plugin.related_paths))
+}
+
+/// Most recent access time of a plugin bundle or binary and, for bundles, its executable.
+fn binary_accessed(install_path: &Path) -> Option<SystemTime> {
+    let mut paths = vec![install_path.to_path_buf()];
+    if install_path.is_dir() {
+        paths.extend(bundle_executable(install_path));
+    }
+    
+    paths.iter()
+        .filter_map(|path| fs::metadata(path).and_then(|m| m.accessed()).ok())
+        .max()
+}
+
+/// Most recent access time of the presets directly inside a plugin's preset folders.
+/// The folders' own access times change whenever they are listed, including by
+/// `discover_related_paths()`, so only the presets are checked.
+fn presets_accessed(related_paths: &RelatedPaths) -> Option<SystemTime> {
+    related_paths.preset_locations.iter()
+        .filter_map(|related| fs::read_dir(&related.path).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter_map(|entry| entry.metadata().and_then(|m| m.accessed()).ok())
+        .max()
+}
+
+/// Everything `discover_related_paths()` found for a plugin, for diagnosing what
+/// an uninstall or backup would touch. Serializes to JSON for support reports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedPathsReport {
+    pub plugin_id: String,
+    pub plugin_name: String,
+    pub vendor: Option<String>,
+    pub format: PluginFormat,
+    pub install_path: PathBuf,
+    /// One entry per category, in `RelatedCategory::ALL` order, including empty ones
+    pub categories: Vec<RelatedCategoryReport>,
+}
+
+/// The related paths of one category in a `RelatedPathsReport`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedCategoryReport {
+    pub category: RelatedCategory,
+    pub paths: Vec<RelatedPathReport>,
+}
+
+/// One related path in a `RelatedPathsReport`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedPathReport {
+    pub path: PathBuf,
+    pub confidence: Confidence,
+    /// Whether the path still exists (it did when the plugin was scanned)
+    pub exists: bool,
+}
+
+/// Builds a `RelatedPathsReport` of the related paths discovered for `plugin`.
+pub fn related_paths_report(plugin: &InstalledPlugin) -> RelatedPathsReport {
+    let categories = RelatedCategory::ALL.into_iter()
+        .map(|category| RelatedCategoryReport {
+            category,
+            paths: plugin.related_paths.paths(category).iter()
+                .map(|related| RelatedPathReport {
+                    path: related.path.clone(),
+                    confidence: related.confidence,
+                    exists: fs::symlink_metadata(&related.path).is_ok(),
+                })
+                .collect(),
+        })
+        .collect();
+    
+    RelatedPathsReport {
+        plugin_id: plugin.plugin.id.clone(),
+        plugin_name: plugin.plugin.name.clone(),
+        vendor: plugin.plugin.author.clone(),
+        format: plugin.format,
+        install_path: plugin.install_path.clone(),
+        categories,
+    }
+}
+
+/// Disk space used by a plugin, broken down by kind of content.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskUsage {
+    /// The plugin binary/bundle itself
+    pub binary: u64,
+    pub presets: u64,
+    pub libraries: u64,
+    pub support: u64,
+    pub preferences: u64,
+    /// Sum of all of the above
+    pub total: u64,
+}
+
+/// Computes the disk space used by a plugin and its related files.
+/// Walks every related folder, so this can be slow for sample-heavy plugins.
+pub fn plugin_disk_usage(plugin: &InstalledPlugin) -> Result<DiskUsage> {
+    if fs::symlink_metadata(&plugin.install_path).is_err() {
+        anyhow::bail!("Plugin not found at {:?}", plugin.install_path);
+    }
+    
+    let sum = |paths: &[RelatedPath]| paths.iter().map(|related| path_size(&related.path)).sum::<u64>();
+    let related = &plugin.related_paths;
+    
+    let mut usage = DiskUsage {
+        binary: path_size(&plugin.install_path),
+        presets: sum(&related.preset_locations),
+        libraries: sum(&related.library_locations),
+        support: sum(&related.support_locations),
+        preferences: sum(&related.preference_files),
+        total: 0,
+    };
+    usage.total = usage.binary + usage.presets + usage.libraries + usage.support + usage.preferences;
+    
+    Ok(usage)
+}
+
+/// Returns the total size in bytes of a file or directory tree.
+/// Symlinks are not followed, and unreadable entries count as zero.
+pub fn path_size(path: &Path) -> u64 {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return 0,
+    };
+    
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    
+    match fs::read_dir(path) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| path_size(&entry.path()))
+            .sum(),
+        Err(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_directories_are_scannable() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("VST3");
+        fs::write(&file, "").unwrap();
+
+        assert!(is_scannable_dir(dir.path()));
+        assert!(!is_scannable_dir(&file));
+        assert!(!is_scannable_dir(&dir.path().join("missing")));
+    }
+
+    #[test]
+    fn slugifies_plugin_names() {
+        assert_eq!(slugify("EQ/Comp (v2)"), "eq-comp-v2");
+        assert_eq!(slugify("Über-Synth"), "uber-synth");
+        assert_eq!(slugify("Pro-Q 3"), "pro-q-3");
+        assert_eq!(slugify("合成器").len(), 8);
+    }
+
+    #[test]
+    fn legacy_ids_still_match() {
+        let mut plugin = crate::test_support::installed_plugin("Pro-Q 3 (Mono)", Path::new("/Pro-Q 3 (Mono).vst3"), PluginFormat::VST3);
+        plugin.plugin.id = plugin_id(&PluginFormat::VST3, "Pro-Q 3 (Mono)");
+
+        assert_eq!(plugin.plugin.id, "vst3.pro-q-3-mono");
+        assert_eq!(legacy_plugin_id(&plugin), "vst3.pro-q-3-(mono)");
+        assert!(matches_plugin_id(&plugin, "vst3.pro-q-3-(mono)"));
+        let config = ScanConfig { exclude_ids: vec![String::from("vst3.pro-q-3-(mono)")], ..ScanConfig::default() };
+        assert!(config.is_excluded(&plugin));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolves_only_symlinked_entries() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("Real");
+        fs::create_dir_all(real.join("Synth.lv2")).unwrap();
+        symlink("Real", dir.path().join("Linked")).unwrap();
+        symlink("../Real/Synth.lv2", real.join("Alias.lv2")).unwrap();
+
+        // A plugin in a linked folder isn't itself a link
+        let in_linked_folder = dir.path().join("Linked/Synth.lv2");
+        assert_eq!(resolve_symlink(&in_linked_folder), in_linked_folder);
+        assert_eq!(resolve_symlink(&real.join("Alias.lv2")), real.join("../Real/Synth.lv2"));
+    }
+
+    #[test]
+    fn records_access_time_from_before_the_scan() {
+        crate::test_support::isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Synth.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), "").unwrap();
+        let used = filetime::FileTime::from_unix_time(1_600_000_000, 0);
+        filetime::set_file_atime(&bundle, used).unwrap();
+
+        let plugin = recognize_plugin(bundle, &PluginFormat::LV2).unwrap();
+        assert_eq!(plugin.last_accessed, Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000)));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn matches_preference_files_by_whole_name() {
+        let home = tempfile::tempdir().unwrap();
+        let preferences = home.path().join("Library/Preferences");
+        fs::create_dir_all(preferences.join("FabFilter")).unwrap();
+        for name in ["com.fabfilter.Pro-Q.plist", "com.fabfilter.Pro-Q.3.plist", "com.fabfilter.Saturn.2.plist", "FabFilter/Pro-Q Settings.xml"] {
+            fs::write(preferences.join(name), "").unwrap();
+        }
+        let found = |plugin: &str| -> Vec<(String, Confidence)> {
+            let mut found: Vec<_> = vendor_preference_files(&home.path().to_string_lossy(), "FabFilter", plugin).into_iter()
+                .map(|related| (related.path.strip_prefix(&preferences).unwrap().to_string_lossy().to_string(), related.confidence))
+                .collect();
+            found.sort();
+            found
+        };
+
+        assert_eq!(found("Pro-Q"), [
+            (String::from("FabFilter/Pro-Q Settings.xml"), Confidence::Low),
+            (String::from("com.fabfilter.Pro-Q.plist"), Confidence::High),
+        ]);
+        assert_eq!(found("Pro-Q 3"), [(String::from("com.fabfilter.Pro-Q.3.plist"), Confidence::High)]);
+        assert!(found("Saturn").is_empty());
+    }
+
+    #[test]
+    fn global_scan_dirs_are_scanned_but_not_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let global = (dir.path().join("Global LV2"), PluginFormat::LV2);
+        add_global_scan_dir(global.0.clone(), global.1);
+        let config = ScanConfig::default();
+        let scanned = config.directories().unwrap();
+        let saved_path = dir.path().join("scan.json");
+        config.to_file(&saved_path).unwrap();
+        clear_global_scan_dirs();
+
+        assert!(scanned.contains(&global));
+        assert!(ScanConfig::from_file(&saved_path).unwrap().extra_dirs.is_empty());
+        let without_defaults = ScanConfig { include_default_dirs: false, ..ScanConfig::default() };
+        assert!(without_defaults.directories().unwrap().is_empty());
+    }
+
+    /// Scans only `dir`, expecting plugins of `format`.
+    fn scan_config_for(dir: &Path, format: PluginFormat) -> ScanConfig {
+        ScanConfig {
+            include_default_dirs: false,
+            extra_dirs: vec![(dir.to_path_buf(), format)],
+            ..ScanConfig::default()
+        }
+    }
+
+    #[test]
+    fn names_lv2_bundles_by_their_declared_name() {
+        crate::test_support::isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("fil4.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), r#"
+            @prefix lv2: <http://lv2plug.in/ns/lv2core#> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+            <http://gareus.org/oss/lv2/fil4#mono> a lv2:Plugin ;
+                rdfs:seeAlso <fil4.ttl> .
+        "#).unwrap();
+        fs::write(bundle.join("fil4.ttl"), r#"
+            @prefix lv2: <http://lv2plug.in/ns/lv2core#> .
+            @prefix doap: <http://usefulinc.com/ns/doap#> .
+            @prefix foaf: <http://xmlns.com/foaf/0.1/> .
+            <http://gareus.org/oss/lv2/fil4#mono> a lv2:Plugin , lv2:EQPlugin ;
+                doap:name "x42-eq - Parametric Equalizer Mono" ;
+                doap:maintainer [ foaf:name "Robin Gareus" ] ;
+                lv2:minorVersion 14 ;
+                lv2:microVersion 0 .
+        "#).unwrap();
+        let config = scan_config_for(dir.path(), PluginFormat::LV2);
+
+        let plugins = scan(&config, None).unwrap();
+
+        assert_eq!(plugins.len(), 1);
+        let plugin = &plugins[0];
+        assert_eq!(plugin.plugin.name, "x42-eq - Parametric Equalizer Mono");
+        assert_eq!(plugin.plugin.version, "14.0");
+        assert_eq!(plugin.plugin.author.as_deref(), Some("Robin Gareus"));
+        assert_eq!(plugin.uid.as_deref(), Some("http://gareus.org/oss/lv2/fil4#mono"));
+    }
+
+    #[test]
+    fn enumeration_leaves_out_cruft() {
+        let dir = tempfile::tempdir().unwrap();
+        let presets = dir.path().join("Presets");
+        fs::create_dir_all(presets.join("Bank")).unwrap();
+        for file in ["Init.preset", ".DS_Store", "Bank/Pad.preset", "Bank/Thumbs.db", "Bank/._Pad.preset"] {
+            fs::write(presets.join(file), "").unwrap();
+        }
+
+        let mut files = enumerate_directory_recursive(&presets).unwrap();
+        files.sort();
+
+        assert_eq!(files, [presets.join("Bank/Pad.preset"), presets.join("Init.preset")]);
+    }
+
+    #[test]
+    fn infers_each_category_tag_once() {
+        assert_eq!(inferred_tags(Some("Fx|EQ| fx|Mastering|")), ["fx", "eq", "mastering"]);
+        assert!(inferred_tags(None).is_empty());
+    }
+
+    #[test]
+    fn user_tags_survive_rescans() {
+        crate::test_support::isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("Tagged.lv2");
+        fs::create_dir_all(&bundle).unwrap();
+        fs::write(bundle.join("manifest.ttl"), r#"
+            @prefix lv2: <http://lv2plug.in/ns/lv2core#> .
+            @prefix doap: <http://usefulinc.com/ns/doap#> .
+            <urn:test:tagged> a lv2:Plugin , lv2:EQPlugin ;
+                doap:name "Tagged EQ" .
+        "#).unwrap();
+        let config = scan_config_for(dir.path(), PluginFormat::LV2);
+        let id = scan(&config, None).unwrap()[0].plugin.id.clone();
+
+        crate::state::add_tag(&id, " Favorite ").unwrap();
+        crate::state::add_tag(&id, "MIXING").unwrap();
+        let rescanned = scan(&config, None).unwrap();
+
+        assert_eq!(rescanned[0].plugin.tags, ["eq", "favorite", "mixing"]);
+        assert_eq!(crate::state::list_tags(&id).unwrap(), ["favorite", "mixing"]);
+    }
+
+    #[test]
+    fn groups_plugins_by_vendor() {
+        let plugin = |name: &str, vendor: Option<&str>| {
+            let mut plugin = crate::test_support::installed_plugin(name, Path::new(name), PluginFormat::VST3);
+            plugin.plugin.author = vendor.map(str::to_string);
+            plugin
+        };
+        let plugins = [
+            plugin("Pro-Q 3", Some("FabFilter")),
+            plugin("Pro-L 2", Some(" fabfilter ")),
+            plugin("Pro-C 2", Some("FABFILTER")),
+            plugin("Serum", Some("Xfer  Records")),
+            plugin("OTT", Some("Xfer Records")),
+            plugin("Mystery", Some("  ")),
+        ];
+
+        let groups = group_by_vendor(&plugins);
+
+        let names = |vendor: &str| groups[vendor].iter().map(|p| p.plugin.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(groups.keys().collect::<Vec<_>>(), ["FabFilter", UNKNOWN_VENDOR, "Xfer Records"]);
+        assert_eq!(names("FabFilter"), ["Pro-Q 3", "Pro-L 2", "Pro-C 2"]);
+        assert_eq!(names("Xfer Records"), ["Serum", "OTT"]);
+        assert_eq!(names(UNKNOWN_VENDOR), ["Mystery"]);
+    }
+
+    #[test]
+    fn finds_vst3_bundles_in_vendor_folders_once() {
+        crate::test_support::isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        for bundle in ["FabFilter/Pro-Q 3.vst3", "Serum.vst3"] {
+            fs::create_dir_all(dir.path().join(bundle).join("Contents/x86_64-linux")).unwrap();
+        }
+
+        let plugins = scan(&scan_config_for(dir.path(), PluginFormat::VST3), None).unwrap();
+
+        let found: Vec<_> = plugins.iter().map(|p| (p.plugin.name.as_str(), p.install_path.clone())).collect();
+        assert_eq!(found, [
+            ("Pro-Q 3", dir.path().join("FabFilter/Pro-Q 3.vst3")),
+            ("Serum", dir.path().join("Serum.vst3")),
+        ]);
+    }
+
+    #[test]
+    fn differently_spelled_known_paths_are_not_orphans() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin_dir = dir.path().join("LV2");
+        for entry in ["Synth.lv2", "Synth Presets", "Leftover.lv2"] {
+            fs::create_dir_all(plugin_dir.join(entry)).unwrap();
+        }
+        let mut plugin = crate::test_support::installed_plugin("Synth", &dir.path().join("LV2/./Synth.lv2/"), PluginFormat::LV2);
+        plugin.related_paths.preset_locations = vec![RelatedPath::new(dir.path().join("Other/../LV2/Synth Presets"), Confidence::High)];
+
+        let orphaned = orphaned_files_in(&[(plugin_dir.clone(), PluginFormat::LV2)], &[plugin]);
+
+        assert_eq!(orphaned, [plugin_dir.join("Leftover.lv2")]);
+    }
+
+    #[test]
+    fn repeated_scans_list_plugins_in_the_same_order() {
+        crate::test_support::isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        for bundle in ["VST3/Zeta.vst3", "VST3/Beta/Dup.vst3", "VST3/Alpha/Dup.vst3", "VST3/Alpha.vst3", "LV2/Alpha.lv2", "LV2/Mid.lv2"] {
+            fs::create_dir_all(dir.path().join(bundle)).unwrap();
+        }
+        let config = ScanConfig {
+            include_default_dirs: false,
+            extra_dirs: vec![(dir.path().join("LV2"), PluginFormat::LV2), (dir.path().join("VST3"), PluginFormat::VST3)],
+            ..ScanConfig::default()
+        };
+
+        let first = scan(&config, None).unwrap();
+        let second = scan(&config, None).unwrap();
+
+        let order = |plugins: &[InstalledPlugin]| plugins.iter()
+            .map(|p| (p.plugin.name.clone(), p.format, p.install_path.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(order(&first), order(&second));
+        assert_eq!(order(&first), [
+            (String::from("Alpha"), PluginFormat::VST3, dir.path().join("VST3/Alpha.vst3")),
+            (String::from("Alpha"), PluginFormat::LV2, dir.path().join("LV2/Alpha.lv2")),
+            (String::from("Dup"), PluginFormat::VST3, dir.path().join("VST3/Alpha/Dup.vst3")),
+            (String::from("Dup"), PluginFormat::VST3, dir.path().join("VST3/Beta/Dup.vst3")),
+            (String::from("Mid"), PluginFormat::LV2, dir.path().join("LV2/Mid.lv2")),
+            (String::from("Zeta"), PluginFormat::VST3, dir.path().join("VST3/Zeta.vst3")),
+        ]);
+    }
+
+    #[test]
+    fn excluded_folders_and_ids_are_left_out() {
+        crate::test_support::isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        for bundle in ["Serum.vst3", "Pro-Q 3.vst3", "Archive/Old Synth.vst3"] {
+            fs::create_dir_all(dir.path().join(bundle)).unwrap();
+        }
+        let mut config = scan_config_for(dir.path(), PluginFormat::VST3);
+        let serum = scan(&config, None).unwrap().into_iter()
+            .find(|p| p.plugin.name == "Serum")
+            .unwrap();
+
+        config.exclude_dirs = vec![dir.path().join("Archive")];
+        config.exclude_ids = vec![serum.plugin.id];
+        let names: Vec<_> = scan(&config, None).unwrap().into_iter().map(|p| p.plugin.name).collect();
+
+        assert_eq!(names, ["Pro-Q 3"]);
+    }
+
+    #[test]
+    fn lists_scan_folders_with_whether_they_exist() {
+        let home = crate::test_support::isolated_home();
+        let dir = tempfile::tempdir().unwrap();
+        let present = dir.path().join("Present");
+        fs::create_dir(&present).unwrap();
+        let missing = dir.path().join("Missing");
+        let config = ScanConfig {
+            extra_dirs: vec![(present.clone(), PluginFormat::VST3), (missing.clone(), PluginFormat::VST3)],
+            ..ScanConfig::default()
+        };
+        let user_default = if cfg!(target_os = "macos") {
+            (home.join("Library/Audio/Plug-Ins/VST3"), PluginFormat::VST3)
+        } else if cfg!(target_os = "windows") {
+            (home.join("AppData/Local").join(r"Programs\Common\VST3"), PluginFormat::VST3)
+        } else {
+            (home.join(".lv2"), PluginFormat::LV2)
+        };
+
+        let dirs = plugin_directories_with(&config).unwrap();
+
+        assert!(dirs.contains(&(present, PluginFormat::VST3, true)));
+        assert!(dirs.contains(&(missing, PluginFormat::VST3, false)));
+        assert!(dirs.iter().any(|(dir, format, _)| (dir, *format) == (&user_default.0, user_default.1)));
+        assert!(dirs.iter().all(|(dir, _, exists)| *exists == dir.is_dir()));
+    }
+}
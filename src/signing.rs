@@ -0,0 +1,193 @@
+//! Code signing and notarization status reporting (macOS).
+//!
+//! Shells out to `codesign` and `spctl` to report whether a plugin bundle is signed,
+//! which team signed it, and whether Gatekeeper accepts it as notarized.
+//!
+//! Commands are run through the `CommandRunner` trait so parsing can be exercised
+//! with canned tool output.
+
+use crate::InstalledPlugin;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Captured output of an external command.
+#[derive(Debug, Clone, Default)]
+pub struct CommandOutput {
+    /// True if the process exited with status 0
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Runs external commands. Implemented by `SystemCommandRunner` for real use.
+pub trait CommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput>;
+}
+
+/// Runs commands with `std::process::Command`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemCommandRunner;
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        let output = Command::new(program).args(args).output()?;
+        Ok(CommandOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
+
+/// Signature and notarization status of a plugin bundle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureStatus {
+    /// True if `codesign --verify` accepted the signature
+    pub signed: bool,
+    /// Apple Developer team identifier (e.g., "ABCDE12345")
+    pub team_identifier: Option<String>,
+    /// Leaf signing authority (e.g., "Developer ID Application: Vendor (ABCDE12345)")
+    pub authority: Option<String>,
+    /// Gatekeeper notarization result; None if `spctl` is unavailable
+    pub notarized: Option<bool>,
+}
+
+/// Checks the code signature and notarization status of a plugin.
+#[cfg(target_os = "macos")]
+pub fn check_signature(plugin: &InstalledPlugin) -> Result<SignatureStatus> {
+    check_signature_with(&plugin.install_path, &SystemCommandRunner)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn check_signature(_plugin: &InstalledPlugin) -> Result<SignatureStatus> {
+    anyhow::bail!("Code signature checks are only supported on macOS");
+}
+
+/// Checks the code signature of a path using the given command runner.
+pub fn check_signature_with(path: &Path, runner: &dyn CommandRunner) -> Result<SignatureStatus> {
+    let path_str = path.to_string_lossy();
+
+    let verify = runner.run("codesign", &["--verify", "--deep", "--strict", &path_str])
+        .context("Failed to run codesign (is Xcode Command Line Tools installed?)")?;
+
+    let mut status = SignatureStatus {
+        signed: verify.success,
+        team_identifier: None,
+        authority: None,
+        notarized: None,
+    };
+
+    if !status.signed {
+        status.notarized = Some(false);
+        return Ok(status);
+    }
+
+    // codesign writes its details to stderr
+    if let Ok(details) = runner.run("codesign", &["-dv", "--verbose=2", &path_str]) {
+        let (team, authority) = parse_codesign_details(&details.stderr);
+        status.team_identifier = team;
+        status.authority = authority;
+    }
+
+    status.notarized = match runner.run("spctl", &["--assess", "--type", "open", "--context", "context:primary-signature", "-v", &path_str]) {
+        Ok(assess) => Some(parse_spctl_output(&assess)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => {
+            eprintln!("Warning: Failed to run spctl for {:?}: {}", path, e);
+            None
+        }
+    };
+
+    Ok(status)
+}
+
+/// Extracts the team identifier and leaf authority from `codesign -dv` output.
+fn parse_codesign_details(output: &str) -> (Option<String>, Option<String>) {
+    let mut team = None;
+    let mut authority = None;
+
+    for line in output.lines() {
+        if let Some(value) = line.strip_prefix("TeamIdentifier=") {
+            if value != "not set" {
+                team = Some(value.trim().to_string());
+            }
+        } else if let Some(value) = line.strip_prefix("Authority=") {
+            // The first Authority line is the leaf certificate
+            if authority.is_none() {
+                authority = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    (team, authority)
+}
+
+/// Returns true if `spctl --assess` accepted the bundle as notarized.
+fn parse_spctl_output(output: &CommandOutput) -> bool {
+    // spctl reports "accepted" and "source=Notarized Developer ID" on stderr
+    let text = format!("{}\n{}", output.stdout, output.stderr);
+    output.success && text.contains("accepted") && text.contains("Notarized")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers each program with canned output; `None` means it isn't installed.
+    struct CannedRunner {
+        codesign_verify: bool,
+        spctl: Option<CommandOutput>,
+    }
+
+    impl CommandRunner for CannedRunner {
+        fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+            match (program, args.first().copied()) {
+                ("codesign", Some("--verify")) => Ok(CommandOutput { success: self.codesign_verify, ..CommandOutput::default() }),
+                ("codesign", _) => Ok(CommandOutput {
+                    success: true,
+                    stdout: String::new(),
+                    stderr: String::from(concat!(
+                        "Executable=/Library/Audio/Plug-Ins/VST3/Pro-Q 3.vst3/Contents/MacOS/Pro-Q 3\n",
+                        "Authority=Developer ID Application: FabFilter (ABCDE12345)\n",
+                        "Authority=Developer ID Certification Authority\n",
+                        "Authority=Apple Root CA\n",
+                        "TeamIdentifier=ABCDE12345\n",
+                    )),
+                }),
+                _ => self.spctl.clone().ok_or_else(|| io::Error::from(io::ErrorKind::NotFound)),
+            }
+        }
+    }
+
+    #[test]
+    fn reads_team_and_notarization_from_tool_output() {
+        let runner = CannedRunner {
+            codesign_verify: true,
+            spctl: Some(CommandOutput {
+                success: true,
+                stdout: String::new(),
+                stderr: String::from("Pro-Q 3.vst3: accepted\nsource=Notarized Developer ID\n"),
+            }),
+        };
+        let status = check_signature_with(Path::new("Pro-Q 3.vst3"), &runner).unwrap();
+
+        assert!(status.signed);
+        assert_eq!(status.team_identifier.as_deref(), Some("ABCDE12345"));
+        assert_eq!(status.authority.as_deref(), Some("Developer ID Application: FabFilter (ABCDE12345)"));
+        assert_eq!(status.notarized, Some(true));
+    }
+
+    #[test]
+    fn reports_unsigned_and_unknown_notarization() {
+        let unsigned = CannedRunner { codesign_verify: false, spctl: None };
+        let status = check_signature_with(Path::new("Synth.vst3"), &unsigned).unwrap();
+        assert!(!status.signed);
+        assert_eq!(status.notarized, Some(false));
+
+        let without_spctl = CannedRunner { codesign_verify: true, spctl: None };
+        assert_eq!(check_signature_with(Path::new("Synth.vst3"), &without_spctl).unwrap().notarized, None);
+    }
+}
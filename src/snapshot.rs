@@ -0,0 +1,225 @@
+//! Snapshots of the plugin directories' state, for a plugin-management history.
+//!
+//! A snapshot records which plugins are installed where (path, format, id, name,
+//! version, enabled, modification time), but none of their files, so it's cheap to
+//! take before and after every management session. Diffing a snapshot against the
+//! current state (or another snapshot) reports the plugins added, removed, and
+//! changed since, which is what an "undo my last session" feature needs to know.
+//! Content is restored from backups; the snapshot says what to restore.
+
+use crate::atomic::write_atomic;
+use crate::registry::scan_installed;
+use crate::{InstalledPlugin, PluginFormat};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One plugin as recorded in a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub install_path: PathBuf,
+    pub format: PluginFormat,
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub enabled: bool,
+    /// RFC 3339 modification time of the plugin file or bundle, if readable
+    #[serde(default)]
+    pub modified: Option<String>,
+}
+
+impl From<&InstalledPlugin> for SnapshotEntry {
+    fn from(plugin: &InstalledPlugin) -> Self {
+        let modified = fs::symlink_metadata(&plugin.install_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(|time| chrono::DateTime::<chrono::Local>::from(time).to_rfc3339());
+
+        SnapshotEntry {
+            install_path: plugin.install_path.clone(),
+            format: plugin.format,
+            id: plugin.plugin.id.clone(),
+            name: plugin.plugin.name.clone(),
+            version: plugin.plugin.version.clone(),
+            enabled: plugin.enabled,
+            modified,
+        }
+    }
+}
+
+/// The installed plugins at a point in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    /// RFC 3339 timestamp of when the snapshot was taken
+    pub created: String,
+    pub plugins: Vec<SnapshotEntry>,
+}
+
+impl StateSnapshot {
+    /// Builds a snapshot from already-scanned plugins.
+    pub fn from_plugins(plugins: &[InstalledPlugin]) -> Self {
+        StateSnapshot {
+            created: chrono::Local::now().to_rfc3339(),
+            plugins: plugins.iter().map(SnapshotEntry::from).collect(),
+        }
+    }
+
+    /// Writes the snapshot as pretty-printed JSON, atomically.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(path, content.as_bytes())
+            .context(format!("Failed to write snapshot: {:?}", path))?;
+        Ok(())
+    }
+
+    /// Reads a snapshot previously written with `save()`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .context(format!("Failed to read snapshot: {:?}", path))?;
+        serde_json::from_str(&content)
+            .context(format!("Failed to parse snapshot: {:?}", path))
+    }
+}
+
+/// A plugin present in both states that was updated, moved, enabled, or disabled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotChange {
+    pub before: SnapshotEntry,
+    pub after: SnapshotEntry,
+}
+
+/// Differences between two plugin directory states.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StateDiff {
+    /// Installed now, not in the snapshot
+    pub added: Vec<SnapshotEntry>,
+    /// In the snapshot, no longer installed
+    pub removed: Vec<SnapshotEntry>,
+    pub changed: Vec<SnapshotChange>,
+}
+
+impl StateDiff {
+    /// Returns true if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Scans the plugin directories and returns their current state.
+pub fn snapshot_state() -> Result<StateSnapshot> {
+    Ok(StateSnapshot::from_plugins(&scan_installed()?))
+}
+
+/// Scans the plugin directories and reports what changed since `snapshot`.
+pub fn diff_against_snapshot(snapshot: &StateSnapshot) -> Result<StateDiff> {
+    Ok(diff_snapshots(snapshot, &snapshot_state()?))
+}
+
+/// Reports what changed between two snapshots. Plugins are matched by install
+/// path and ID (members of a shell plugin share a path); a plugin whose path
+/// changed but whose ID didn't (e.g., it was disabled, which moves it) counts as
+/// changed rather than removed and added.
+pub fn diff_snapshots(before: &StateSnapshot, after: &StateSnapshot) -> StateDiff {
+    let key = |entry: &'_ SnapshotEntry| (entry.install_path.clone(), entry.id.clone());
+    let mut remaining: HashMap<(PathBuf, String), &SnapshotEntry> = after.plugins.iter()
+        .map(|entry| (key(entry), entry))
+        .collect();
+
+    let mut diff = StateDiff::default();
+    let mut removed = Vec::new();
+    for old in &before.plugins {
+        match remaining.remove(&key(old)) {
+            Some(new) if new != old => diff.changed.push(SnapshotChange { before: old.clone(), after: new.clone() }),
+            Some(_) => {}
+            None => removed.push(old),
+        }
+    }
+
+    // Keep the order of the newer snapshot for additions
+    let mut added: Vec<&SnapshotEntry> = after.plugins.iter()
+        .filter(|entry| remaining.contains_key(&key(entry)))
+        .collect();
+
+    // Pair up plugins that moved
+    for old in removed {
+        match added.iter().position(|new| new.id == old.id && new.format == old.format) {
+            Some(index) => {
+                let new = added.remove(index);
+                diff.changed.push(SnapshotChange { before: old.clone(), after: new.clone() });
+            }
+            None => diff.removed.push(old.clone()),
+        }
+    }
+    diff.added = added.into_iter().cloned().collect();
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, id: &str, version: &str) -> SnapshotEntry {
+        SnapshotEntry {
+            install_path: PathBuf::from(path),
+            format: PluginFormat::VST3,
+            id: id.to_string(),
+            name: id.to_string(),
+            version: version.to_string(),
+            enabled: true,
+            modified: None,
+        }
+    }
+
+    fn snapshot(plugins: Vec<SnapshotEntry>) -> StateSnapshot {
+        StateSnapshot { created: String::new(), plugins }
+    }
+
+    #[test]
+    fn tells_shell_members_apart() {
+        let before = snapshot(vec![
+            entry("/VST3/WaveShell.vst3", "vst3.member-a", "1.0"),
+            entry("/VST3/WaveShell.vst3", "vst3.member-b", "1.0"),
+        ]);
+        let after = snapshot(vec![
+            entry("/VST3/WaveShell.vst3", "vst3.member-a", "1.0"),
+            entry("/VST3/WaveShell.vst3", "vst3.member-c", "1.0"),
+        ]);
+
+        let diff = diff_snapshots(&before, &after);
+
+        assert_eq!(diff.removed, [entry("/VST3/WaveShell.vst3", "vst3.member-b", "1.0")]);
+        assert_eq!(diff.added, [entry("/VST3/WaveShell.vst3", "vst3.member-c", "1.0")]);
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn reports_updated_and_moved_plugins_as_changed() {
+        let before = snapshot(vec![
+            entry("/VST3/Synth.vst3", "vst3.synth", "1.0"),
+            entry("/VST3/Comp.vst3", "vst3.comp", "1.0"),
+        ]);
+        let after = snapshot(vec![
+            entry("/VST3/Synth.vst3", "vst3.synth", "2.0"),
+            entry("/VST3 (Disabled)/Comp.vst3", "vst3.comp", "1.0"),
+        ]);
+
+        let diff = diff_snapshots(&before, &after);
+
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 2);
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot.json");
+        let saved = snapshot(vec![entry("/VST3/Synth.vst3", "vst3.synth", "1.0")]);
+
+        saved.save(&path).unwrap();
+
+        assert!(diff_snapshots(&saved, &StateSnapshot::load(&path).unwrap()).is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+//! Persistent per-user plugin state.
+//!
+//! Settings the user attaches to plugins (custom display names and tags) are
+//! stored as JSON in the PluginDepot config directory, keyed by plugin ID, and
+//! merged into scan results so they survive rescans.
+
+use crate::atomic::write_atomic;
+use crate::registry::legacy_plugin_id;
+use crate::InstalledPlugin;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the state store inside the config directory
+const STATE_FILE: &str = "state.json";
+
+/// User-defined settings for plugins, keyed by plugin ID.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserState {
+    /// Custom labels shown instead of the plugin's own name
+    #[serde(default)]
+    pub display_names: BTreeMap<String, String>,
+    /// User-defined tags, normalized with `normalize_tag()`
+    #[serde(default)]
+    pub tags: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl UserState {
+    /// Reads the state store. A missing file yields empty state.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(UserState::default());
+        }
+        let content = fs::read_to_string(path)
+            .context(format!("Failed to read user state: {:?}", path))?;
+        serde_json::from_str(&content)
+            .context(format!("Failed to parse user state: {:?}", path))
+    }
+
+    /// Writes the state store atomically, creating the config directory if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .context(format!("Failed to create directory: {:?}", parent))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(path, content.as_bytes())
+            .context(format!("Failed to write user state: {:?}", path))?;
+        Ok(())
+    }
+
+    /// Applies the stored settings to scanned plugins.
+    /// A custom display name replaces `Plugin::name`; the scanned name is kept in `original_name`.
+    /// User tags are added to the plugin's inferred tags.
+    /// Settings still saved under a plugin's legacy ID (see `legacy_plugin_id()`) apply too.
+    pub fn apply(&self, plugins: &mut [InstalledPlugin]) {
+        for installed in plugins {
+            let legacy_id = legacy_plugin_id(installed);
+            if let Some(label) = self.display_names.get(&installed.plugin.id).or_else(|| self.display_names.get(&legacy_id)) {
+                let original = std::mem::replace(&mut installed.plugin.name, label.clone());
+                installed.plugin.original_name.get_or_insert(original);
+            }
+            if let Some(tags) = self.tags.get(&installed.plugin.id).or_else(|| self.tags.get(&legacy_id)) {
+                for tag in tags {
+                    if !installed.plugin.tags.contains(tag) {
+                        installed.plugin.tags.push(tag.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves settings saved under the legacy IDs of `plugins` (see
+    /// `legacy_plugin_id()`) to their current IDs, unless settings already exist
+    /// under the current ID. Returns true if anything moved.
+    pub fn migrate_legacy_ids(&mut self, plugins: &[InstalledPlugin]) -> bool {
+        let mut migrated = false;
+        for installed in plugins {
+            let id = &installed.plugin.id;
+            let legacy_id = legacy_plugin_id(installed);
+            if legacy_id == *id {
+                continue;
+            }
+            migrated |= move_key(&mut self.display_names, &legacy_id, id);
+            migrated |= move_key(&mut self.tags, &legacy_id, id);
+        }
+        migrated
+    }
+}
+
+/// Moves the value at `from` to `to` if `to` is free. Returns true if it moved.
+fn move_key<V>(map: &mut BTreeMap<String, V>, from: &str, to: &str) -> bool {
+    if map.contains_key(to) {
+        return false;
+    }
+    match map.remove(from) {
+        Some(value) => {
+            map.insert(to.to_string(), value);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the PluginDepot configuration directory.
+/// Falls back to the system temp directory if the user's environment variables are unset.
+pub fn config_dir() -> PathBuf {
+    #[cfg(target_os = "macos")]
+    {
+        if let Ok(home) = std::env::var("HOME") {
+            return PathBuf::from(format!("{}/Library/Application Support/PluginDepot", home));
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(appdata) = std::env::var("APPDATA") {
+            return PathBuf::from(format!("{}\\PluginDepot", appdata));
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        if let Ok(home) = std::env::var("HOME") {
+            return PathBuf::from(format!("{}/.config/plugindepot", home));
+        }
+    }
+
+    std::env::temp_dir().join("PluginDepot")
+}
+
+/// Path of the default state store.
+pub fn state_file_path() -> PathBuf {
+    config_dir().join(STATE_FILE)
+}
+
+/// Loads the default state store.
+pub fn load_user_state() -> Result<UserState> {
+    UserState::load(&state_file_path())
+}
+
+/// Sets a custom display name for a plugin ID in the default state store.
+/// An empty name removes the custom label.
+pub fn set_display_name(id: &str, name: &str) -> Result<()> {
+    let path = state_file_path();
+    let mut state = UserState::load(&path)?;
+
+    let name = name.trim();
+    if name.is_empty() {
+        state.display_names.remove(id);
+    } else {
+        state.display_names.insert(id.to_string(), name.to_string());
+    }
+
+    state.save(&path)
+}
+
+/// Normalizes a tag for storage and comparison: trimmed and lowercased.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Adds a user tag to a plugin ID in the default state store.
+pub fn add_tag(id: &str, tag: &str) -> Result<()> {
+    let tag = normalize_tag(tag);
+    if tag.is_empty() {
+        anyhow::bail!("Tag must not be empty");
+    }
+
+    let path = state_file_path();
+    let mut state = UserState::load(&path)?;
+    state.tags.entry(id.to_string()).or_default().insert(tag);
+    state.save(&path)
+}
+
+/// Removes a user tag from a plugin ID in the default state store.
+/// Removing a tag the plugin doesn't have is not an error.
+pub fn remove_tag(id: &str, tag: &str) -> Result<()> {
+    let path = state_file_path();
+    let mut state = UserState::load(&path)?;
+
+    if let Some(tags) = state.tags.get_mut(id) {
+        tags.remove(&normalize_tag(tag));
+        if tags.is_empty() {
+            state.tags.remove(id);
+        }
+    }
+
+    state.save(&path)
+}
+
+/// Lists the user tags of a plugin ID, sorted.
+/// Inferred tags are not stored and only appear on scanned plugins.
+pub fn list_tags(id: &str) -> Result<Vec<String>> {
+    let state = load_user_state()?;
+    Ok(state.tags.get(id).map(|tags| tags.iter().cloned().collect()).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::installed_plugin;
+    use crate::PluginFormat;
+
+    fn mono_plugin() -> InstalledPlugin {
+        let mut plugin = installed_plugin("Pro-Q 3 (Mono)", Path::new("/Pro-Q 3 (Mono).vst3"), PluginFormat::VST3);
+        plugin.plugin.id = String::from("vst3.pro-q-3-mono");
+        plugin
+    }
+
+    #[test]
+    fn applies_settings_saved_under_legacy_ids() {
+        let mut state = UserState::default();
+        state.display_names.insert(String::from("vst3.pro-q-3-(mono)"), String::from("Mono EQ"));
+        state.tags.insert(String::from("vst3.pro-q-3-(mono)"), BTreeSet::from([String::from("mixing")]));
+        let mut plugins = [mono_plugin()];
+
+        state.apply(&mut plugins);
+
+        assert_eq!(plugins[0].plugin.name, "Mono EQ");
+        assert_eq!(plugins[0].plugin.tags, ["mixing"]);
+    }
+
+    #[test]
+    fn migrates_legacy_ids() {
+        let mut state = UserState::default();
+        state.display_names.insert(String::from("vst3.pro-q-3-(mono)"), String::from("Mono EQ"));
+        let plugins = [mono_plugin()];
+
+        assert!(state.migrate_legacy_ids(&plugins));
+        assert!(!state.migrate_legacy_ids(&plugins));
+        assert_eq!(state.display_names.keys().collect::<Vec<_>>(), ["vst3.pro-q-3-mono"]);
+    }
+}
@@ -0,0 +1,56 @@
+//! Helpers shared by the unit tests.
+
+use crate::{Architecture, InstallScope, InstalledPlugin, Plugin, PluginFormat, RelatedPaths};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Points HOME and the Windows profile variables at a temporary folder for the
+/// whole test run, so tests never touch the real user's plugins, logs, or state.
+/// Every test sets the same values, so it doesn't matter which one runs first.
+pub(crate) fn isolated_home() -> &'static Path {
+    static HOME: OnceLock<PathBuf> = OnceLock::new();
+    HOME.get_or_init(|| {
+        let home = tempfile::Builder::new()
+            .prefix("plugindepot-home")
+            .tempdir()
+            .expect("temporary home")
+            .keep();
+        std::env::set_var("HOME", &home);
+        for (variable, folder) in [
+            ("USERPROFILE", ""),
+            ("APPDATA", "AppData/Roaming"),
+            ("LOCALAPPDATA", "AppData/Local"),
+            ("PROGRAMDATA", "ProgramData"),
+        ] {
+            std::env::set_var(variable, home.join(folder));
+        }
+        home
+    })
+}
+
+/// An installed plugin at `install_path` with no metadata or related paths.
+pub(crate) fn installed_plugin(name: &str, install_path: &Path, format: PluginFormat) -> InstalledPlugin {
+    InstalledPlugin {
+        plugin: Plugin {
+            id: format!("test.{}", name.to_lowercase()),
+            name: name.to_string(),
+            original_name: None,
+            version: String::from("1.0.0"),
+            description: None,
+            author: None,
+            icon_url: None,
+            tags: Vec::new(),
+        },
+        install_path: install_path.to_path_buf(),
+        resolved_path: install_path.to_path_buf(),
+        format,
+        uid: None,
+        enabled: true,
+        scope: InstallScope::User,
+        shell: None,
+        architecture: Architecture::Unknown,
+        related_paths: RelatedPaths::default(),
+        last_accessed: None,
+        license: None,
+    }
+}
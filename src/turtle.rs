@@ -0,0 +1,470 @@
+//! Minimal reader for Turtle (`.ttl`) RDF files.
+//!
+//! LV2 plugins describe themselves in Turtle: each bundle's `manifest.ttl` lists
+//! its plugins and points at the files holding their details. This reader parses
+//! the subset of Turtle those files use (prefixes, IRIs, prefixed names, literals,
+//! `;`/`,` lists, and `[ ... ]` blank nodes) into a list of triples. Collections
+//! are read but their members are not kept. Relative IRIs are kept as written.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// `rdf:type`, written `a` in Turtle
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// A node or value in a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Iri(String),
+    /// Blank node, numbered uniquely within its `Graph`
+    Blank(usize),
+    /// Literal value without its language tag or datatype
+    Literal(String),
+}
+
+impl Term {
+    /// The IRI, if this term is one
+    pub fn as_iri(&self) -> Option<&str> {
+        match self {
+            Term::Iri(iri) => Some(iri),
+            _ => None,
+        }
+    }
+
+    /// The value, if this term is a literal
+    pub fn as_literal(&self) -> Option<&str> {
+        match self {
+            Term::Literal(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// A subject–predicate–object statement. Predicates are always IRIs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Triple {
+    pub subject: Term,
+    pub predicate: String,
+    pub object: Term,
+}
+
+/// Triples read from one or more Turtle documents.
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    pub triples: Vec<Triple>,
+    next_blank: usize,
+}
+
+impl Graph {
+    /// Parses a Turtle document.
+    pub fn parse(content: &str) -> Result<Graph> {
+        let mut graph = Graph::default();
+        graph.add_document(content)?;
+        Ok(graph)
+    }
+
+    /// Parses a Turtle file and adds its triples. Blank nodes of different
+    /// documents stay distinct.
+    pub fn add_file(&mut self, path: &Path) -> Result<()> {
+        let content = fs::read_to_string(path)
+            .context(format!("Failed to read Turtle file: {:?}", path))?;
+        self.add_document(&content)
+            .context(format!("Failed to parse Turtle file: {:?}", path))
+    }
+
+    /// Parses a Turtle document and adds its triples.
+    pub fn add_document(&mut self, content: &str) -> Result<()> {
+        let tokens = tokenize(content)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            prefixes: HashMap::new(),
+            blank_labels: HashMap::new(),
+            graph: self,
+        };
+        parser.parse_document()
+    }
+
+    /// Returns the objects of every triple with this subject and predicate.
+    pub fn objects<'a>(&'a self, subject: &'a Term, predicate: &'a str) -> impl Iterator<Item = &'a Term> + 'a {
+        self.triples.iter()
+            .filter(move |t| t.subject == *subject && t.predicate == predicate)
+            .map(|t| &t.object)
+    }
+
+    /// Returns the first object with this subject and predicate.
+    pub fn object(&self, subject: &Term, predicate: &str) -> Option<&Term> {
+        self.triples.iter()
+            .find(|t| t.subject == *subject && t.predicate == predicate)
+            .map(|t| &t.object)
+    }
+
+    /// Returns every subject declared to be of type `class`, in document order.
+    /// A subject declared more than once (e.g., in an LV2 manifest and again in
+    /// the plugin's own file) is returned once.
+    pub fn subjects_of_type(&self, class: &str) -> Vec<&Term> {
+        let mut subjects: Vec<&Term> = Vec::new();
+        for triple in self.triples.iter().filter(|t| t.predicate == RDF_TYPE && t.object.as_iri() == Some(class)) {
+            if !subjects.contains(&&triple.subject) {
+                subjects.push(&triple.subject);
+            }
+        }
+        subjects
+    }
+
+    fn new_blank(&mut self) -> Term {
+        self.next_blank += 1;
+        Term::Blank(self.next_blank)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Iri(String),
+    /// Prefix and local part of a prefixed name (`lv2:Plugin`)
+    PrefixedName(String, String),
+    BlankLabel(String),
+    Literal(String),
+    /// `a`, `@prefix`, `@base`, `PREFIX`, `BASE`
+    Keyword(String),
+    Punct(char),
+}
+
+/// Characters that end a bare word (prefixed name, number, keyword)
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || "<>\"'{}[](),;#".contains(c)
+}
+
+fn tokenize(content: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = content.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '#' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '<' {
+            let end = chars[i + 1..].iter().position(|&c| c == '>')
+                .ok_or_else(|| anyhow!("Unterminated IRI"))?;
+            tokens.push(Token::Iri(chars[i + 1..i + 1 + end].iter().collect()));
+            i += end + 2;
+        } else if c == '"' || c == '\'' {
+            let (value, next) = read_string(&chars, i)?;
+            tokens.push(Token::Literal(value));
+            i = skip_literal_suffix(&chars, next);
+        } else if "[](),;".contains(c) {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        } else if c == '.' && chars.get(i + 1).is_none_or(|c| !c.is_ascii_digit()) {
+            tokens.push(Token::Punct('.'));
+            i += 1;
+        } else if is_delimiter(c) {
+            // '>', '{', and '}' (formulae aren't part of Turtle)
+            bail!("Unexpected '{}'", c);
+        } else {
+            let start = i;
+            while i < chars.len() && !is_delimiter(chars[i]) {
+                i += 1;
+            }
+            // A trailing '.' ends the statement rather than belonging to the word
+            while i > start + 1 && chars[i - 1] == '.' {
+                i -= 1;
+            }
+            tokens.push(word_token(chars[start..i].iter().collect()));
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn word_token(word: String) -> Token {
+    if let Some(label) = word.strip_prefix("_:") {
+        return Token::BlankLabel(label.to_string());
+    }
+    if word == "a" || word.starts_with('@') || word.eq_ignore_ascii_case("prefix") || word.eq_ignore_ascii_case("base") {
+        return Token::Keyword(word);
+    }
+    match word.split_once(':') {
+        Some((prefix, local)) if !word.starts_with(|c: char| c.is_ascii_digit() || c == '+' || c == '-') => {
+            Token::PrefixedName(prefix.to_string(), local.replace('\\', ""))
+        }
+        // Numbers and booleans
+        _ => Token::Literal(word),
+    }
+}
+
+/// Reads a quoted string starting at `start`; returns its value and the index after it.
+fn read_string(chars: &[char], start: usize) -> Result<(String, usize)> {
+    let quote = chars[start];
+    let long = chars.get(start + 1) == Some(&quote) && chars.get(start + 2) == Some(&quote);
+    let mut i = if long { start + 3 } else { start + 1 };
+    let mut value = String::new();
+
+    loop {
+        let c = *chars.get(i).ok_or_else(|| anyhow!("Unterminated string"))?;
+        if c == quote {
+            if !long {
+                return Ok((value, i + 1));
+            }
+            if chars.get(i + 1) == Some(&quote) && chars.get(i + 2) == Some(&quote) {
+                return Ok((value, i + 3));
+            }
+            value.push(c);
+            i += 1;
+        } else if c == '\\' {
+            let escaped = *chars.get(i + 1).ok_or_else(|| anyhow!("Unterminated string"))?;
+            i += 2;
+            match escaped {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                'r' => value.push('\r'),
+                'b' => value.push('\u{8}'),
+                'f' => value.push('\u{c}'),
+                'u' | 'U' => {
+                    let digits = if escaped == 'u' { 4 } else { 8 };
+                    let hex: String = chars.get(i..i + digits)
+                        .ok_or_else(|| anyhow!("Invalid escape in string"))?
+                        .iter()
+                        .collect();
+                    let code = u32::from_str_radix(&hex, 16)
+                        .map_err(|_| anyhow!("Invalid escape in string"))?;
+                    value.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    i += digits;
+                }
+                other => value.push(other),
+            }
+        } else if !long && c == '\n' {
+            bail!("Line break in single-line string");
+        } else {
+            value.push(c);
+            i += 1;
+        }
+    }
+}
+
+/// Skips a language tag (`@en`) or datatype (`^^xsd:int`, `^^<...>`) after a literal.
+fn skip_literal_suffix(chars: &[char], mut i: usize) -> usize {
+    if chars.get(i) == Some(&'@') {
+        i += 1;
+        while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '-') {
+            i += 1;
+        }
+    } else if chars.get(i) == Some(&'^') && chars.get(i + 1) == Some(&'^') {
+        i += 2;
+        if chars.get(i) == Some(&'<') {
+            while i < chars.len() && chars[i] != '>' {
+                i += 1;
+            }
+            i += 1;
+        } else {
+            while i < chars.len() && !is_delimiter(chars[i]) {
+                i += 1;
+            }
+            while chars[i - 1] == '.' {
+                i -= 1;
+            }
+        }
+    }
+    i
+}
+
+struct Parser<'g> {
+    tokens: Vec<Token>,
+    position: usize,
+    prefixes: HashMap<String, String>,
+    blank_labels: HashMap<String, Term>,
+    graph: &'g mut Graph,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        let token = self.tokens.get(self.position).cloned()
+            .ok_or_else(|| anyhow!("Unexpected end of document"))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, punct: char) -> Result<()> {
+        match self.next()? {
+            Token::Punct(c) if c == punct => Ok(()),
+            other => bail!("Expected '{}', found {:?}", punct, other),
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<()> {
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Keyword(keyword) if keyword != "a" => self.parse_directive(&keyword)?,
+                _ => {
+                    self.parse_triples()?;
+                    self.expect('.')?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_directive(&mut self, keyword: &str) -> Result<()> {
+        self.position += 1;
+        let sparql_style = !keyword.starts_with('@');
+
+        match keyword.trim_start_matches('@').to_lowercase().as_str() {
+            "prefix" => {
+                let prefix = match self.next()? {
+                    Token::PrefixedName(prefix, local) if local.is_empty() => prefix,
+                    other => bail!("Expected a prefix name, found {:?}", other),
+                };
+                let iri = match self.next()? {
+                    Token::Iri(iri) => iri,
+                    other => bail!("Expected an IRI, found {:?}", other),
+                };
+                self.prefixes.insert(prefix, iri);
+            }
+            // Relative IRIs are kept as written, so the base isn't needed
+            "base" => {
+                self.next()?;
+            }
+            _ => bail!("Unknown directive: {}", keyword),
+        }
+
+        if !sparql_style {
+            self.expect('.')?;
+        }
+        Ok(())
+    }
+
+    fn parse_triples(&mut self) -> Result<()> {
+        if self.peek() == Some(&Token::Punct('[')) {
+            // A blank node with properties may stand alone as a statement
+            let subject = self.parse_object()?;
+            if self.peek() != Some(&Token::Punct('.')) {
+                self.parse_predicate_objects(&subject)?;
+            }
+            return Ok(());
+        }
+
+        let subject = match self.next()? {
+            Token::Iri(iri) => Term::Iri(iri),
+            Token::PrefixedName(prefix, local) => Term::Iri(self.expand(&prefix, &local)?),
+            Token::BlankLabel(label) => self.blank_for_label(label),
+            other => bail!("Expected a subject, found {:?}", other),
+        };
+        self.parse_predicate_objects(&subject)
+    }
+
+    fn parse_predicate_objects(&mut self, subject: &Term) -> Result<()> {
+        loop {
+            let predicate = match self.next()? {
+                Token::Keyword(keyword) if keyword == "a" => RDF_TYPE.to_string(),
+                Token::Iri(iri) => iri,
+                Token::PrefixedName(prefix, local) => self.expand(&prefix, &local)?,
+                other => bail!("Expected a predicate, found {:?}", other),
+            };
+
+            loop {
+                let object = self.parse_object()?;
+                self.graph.triples.push(Triple {
+                    subject: subject.clone(),
+                    predicate: predicate.clone(),
+                    object,
+                });
+                if self.peek() != Some(&Token::Punct(',')) {
+                    break;
+                }
+                self.position += 1;
+            }
+
+            if self.peek() != Some(&Token::Punct(';')) {
+                return Ok(());
+            }
+            while self.peek() == Some(&Token::Punct(';')) {
+                self.position += 1;
+            }
+            // A trailing ';' is allowed before the end of the statement
+            if matches!(self.peek(), Some(Token::Punct('.' | ']')) | None) {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Term> {
+        match self.next()? {
+            Token::Iri(iri) => Ok(Term::Iri(iri)),
+            Token::PrefixedName(prefix, local) => Ok(Term::Iri(self.expand(&prefix, &local)?)),
+            Token::BlankLabel(label) => Ok(self.blank_for_label(label)),
+            Token::Literal(value) => Ok(Term::Literal(value)),
+            Token::Punct('[') => {
+                let node = self.graph.new_blank();
+                if self.peek() != Some(&Token::Punct(']')) {
+                    self.parse_predicate_objects(&node)?;
+                }
+                self.expect(']')?;
+                Ok(node)
+            }
+            Token::Punct('(') => {
+                while self.peek() != Some(&Token::Punct(')')) {
+                    self.parse_object()?;
+                }
+                self.position += 1;
+                Ok(self.graph.new_blank())
+            }
+            other => bail!("Expected an object, found {:?}", other),
+        }
+    }
+
+    fn expand(&self, prefix: &str, local: &str) -> Result<String> {
+        self.prefixes.get(prefix)
+            .map(|namespace| format!("{}{}", namespace, local))
+            .ok_or_else(|| anyhow!("Undefined prefix: {}:", prefix))
+    }
+
+    fn blank_for_label(&mut self, label: String) -> Term {
+        if let Some(term) = self.blank_labels.get(&label) {
+            return term.clone();
+        }
+        let term = self.graph.new_blank();
+        self.blank_labels.insert(label, term.clone());
+        term
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lv2_manifest() {
+        let graph = Graph::parse(r#"
+            @prefix lv2: <http://lv2plug.in/ns/lv2core#> .
+            @prefix doap: <http://usefulinc.com/ns/doap#> .
+            <urn:acme:synth> a lv2:Plugin ;
+                doap:name "Synth \"One\""@en , "Synth" ;
+                lv2:port [ lv2:index 0 ] .
+        "#).unwrap();
+
+        let plugin = Term::Iri(String::from("urn:acme:synth"));
+        assert_eq!(graph.subjects_of_type("http://lv2plug.in/ns/lv2core#Plugin"), [&plugin]);
+        let names: Vec<_> = graph.objects(&plugin, "http://usefulinc.com/ns/doap#name")
+            .filter_map(Term::as_literal)
+            .collect();
+        assert_eq!(names, ["Synth \"One\"", "Synth"]);
+    }
+
+    #[test]
+    fn rejects_characters_outside_turtle() {
+        for document in ["<a> <b> { } .", "<a> <b> > .", "<a> <b> }"] {
+            assert!(Graph::parse(document).is_err(), "{}", document);
+        }
+    }
+}
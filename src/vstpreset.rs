@@ -0,0 +1,224 @@
+//! Minimal reader for VST3 preset files (`.vstpreset`).
+//!
+//! A preset starts with a 48-byte header: the magic "VST3", a format version, the
+//! plugin's class ID as 32 ASCII hex digits, and the file offset of a chunk list.
+//! The chunk list ("List", entry count, then id/offset/size per entry, all
+//! little-endian) locates the component state ("Comp"), the controller state
+//! ("Cont"), and an optional XML meta-info chunk ("Info") with attributes such as
+//! the preset name.
+//!
+//! Only the header, the chunk list, and the meta-info chunk are read; the plugin's
+//! own state is opaque and never loaded. The class ID ties a preset to the plugin
+//! that saved it, so a preset browser can check it against `BundleMetadata::uid`.
+
+use crate::metadata::cached_bundle_metadata;
+use crate::InstalledPlugin;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"VST3";
+const CHUNK_LIST_MAGIC: &[u8; 4] = b"List";
+const HEADER_SIZE: usize = 48;
+
+/// Chunk holding the XML meta-info
+const INFO_CHUNK_ID: &str = "Info";
+
+/// Upper bounds against corrupt files
+const MAX_CHUNKS: u32 = 64;
+const MAX_INFO_SIZE: u64 = 1024 * 1024;
+
+/// Metadata read from a `.vstpreset` file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VstPresetInfo {
+    /// Preset format version from the header
+    pub version: u32,
+    /// Class ID of the plugin that saved the preset, 32 uppercase hex digits
+    pub class_id: String,
+    /// IDs of the chunks in the file, e.g., ["Comp", "Cont", "Info"]
+    pub chunks: Vec<String>,
+    /// Preset name from the meta-info ("Name")
+    pub name: Option<String>,
+    /// Name of the plugin that saved the preset ("PlugInName")
+    pub plugin_name: Option<String>,
+    /// Preset category ("MusicalCategory", or "PlugInCategory")
+    pub category: Option<String>,
+    /// Every meta-info attribute by ID, including the ones above
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// Reads the header, chunk list, and meta-info of a `.vstpreset` file.
+/// Fails if the file isn't a VST3 preset.
+pub fn read_vstpreset(path: &Path) -> Result<VstPresetInfo> {
+    let mut file = fs::File::open(path)
+        .context(format!("Failed to open preset: {:?}", path))?;
+
+    let mut header = [0u8; HEADER_SIZE];
+    file.read_exact(&mut header)
+        .context(format!("Preset is too short: {:?}", path))?;
+    if &header[0..4] != MAGIC {
+        anyhow::bail!("Not a VST3 preset: {:?}", path);
+    }
+
+    let class_id = String::from_utf8_lossy(&header[8..40]).trim_end_matches('\0').to_uppercase();
+    let mut info = VstPresetInfo {
+        version: u32::from_le_bytes([header[4], header[5], header[6], header[7]]),
+        class_id,
+        ..Default::default()
+    };
+
+    let list_offset = u64::from_le_bytes(header[40..48].try_into().unwrap_or_default());
+    let mut list_header = [0u8; 8];
+    file.seek(SeekFrom::Start(list_offset))?;
+    file.read_exact(&mut list_header)
+        .context(format!("Missing chunk list in preset: {:?}", path))?;
+    if &list_header[0..4] != CHUNK_LIST_MAGIC {
+        anyhow::bail!("Invalid chunk list in preset: {:?}", path);
+    }
+    let count = u32::from_le_bytes([list_header[4], list_header[5], list_header[6], list_header[7]]).min(MAX_CHUNKS);
+
+    // Each entry: 4-byte ID, 8-byte offset, 8-byte size
+    let mut entries = vec![0u8; count as usize * 20];
+    file.read_exact(&mut entries)
+        .context(format!("Truncated chunk list in preset: {:?}", path))?;
+
+    let mut info_chunk = None;
+    for entry in entries.chunks_exact(20) {
+        let id = String::from_utf8_lossy(&entry[0..4]).to_string();
+        let offset = u64::from_le_bytes(entry[4..12].try_into().unwrap_or_default());
+        let size = u64::from_le_bytes(entry[12..20].try_into().unwrap_or_default());
+        if id == INFO_CHUNK_ID {
+            info_chunk = Some((offset, size));
+        }
+        info.chunks.push(id);
+    }
+
+    if let Some((offset, size)) = info_chunk {
+        let mut xml = Vec::new();
+        file.seek(SeekFrom::Start(offset))?;
+        (&mut file).take(size.min(MAX_INFO_SIZE)).read_to_end(&mut xml)?;
+        info.attributes = parse_meta_info(&String::from_utf8_lossy(&xml));
+    }
+
+    info.name = info.attributes.get("Name").cloned();
+    info.plugin_name = info.attributes.get("PlugInName").cloned();
+    info.category = info.attributes.get("MusicalCategory")
+        .or_else(|| info.attributes.get("PlugInCategory"))
+        .cloned();
+
+    Ok(info)
+}
+
+/// Returns true if the preset was saved by `plugin`, comparing the preset's class
+/// ID with the plugin's (from its moduleinfo.json). False if the plugin's class ID
+/// is unknown.
+pub fn preset_matches_plugin(preset: &VstPresetInfo, plugin: &InstalledPlugin) -> bool {
+    cached_bundle_metadata(&plugin.install_path, &plugin.format).ok()
+        .and_then(|metadata| metadata.uid)
+        .is_some_and(|uid| normalize_class_id(&uid) == normalize_class_id(&preset.class_id))
+}
+
+/// Class IDs are written with or without dashes and in either case.
+fn normalize_class_id(id: &str) -> String {
+    id.chars().filter(char::is_ascii_hexdigit).map(|c| c.to_ascii_uppercase()).collect()
+}
+
+/// Reads the `<Attr id=".." value=".."/>` elements of a meta-info chunk.
+fn parse_meta_info(xml: &str) -> BTreeMap<String, String> {
+    xml.split("<Attr").skip(1)
+        .filter_map(|element| {
+            let tag = &element[..element.find('>').unwrap_or(element.len())];
+            Some((xml_attribute(tag, "id")?, xml_attribute(tag, "value")?))
+        })
+        .collect()
+}
+
+fn xml_attribute(tag: &str, name: &str) -> Option<String> {
+    let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let length = tag[start..].find('"')?;
+    Some(unescape_xml(&tag[start..start + length]))
+}
+
+fn unescape_xml(value: &str) -> String {
+    value.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::installed_plugin;
+    use crate::PluginFormat;
+
+    const CLASS_ID: &str = "5653545364453153796E746800000000";
+
+    /// A preset with a component state and a meta-info chunk, laid out as a host writes it
+    fn preset_bytes(class_id: &str, xml: &str) -> Vec<u8> {
+        let state = b"opaque plugin state";
+        let state_offset = HEADER_SIZE as u64;
+        let info_offset = state_offset + state.len() as u64;
+        let list_offset = info_offset + xml.len() as u64;
+
+        let mut data = MAGIC.to_vec();
+        data.extend(1u32.to_le_bytes());
+        data.extend(class_id.as_bytes());
+        data.extend(list_offset.to_le_bytes());
+        data.extend(state);
+        data.extend(xml.as_bytes());
+        data.extend(CHUNK_LIST_MAGIC);
+        data.extend(2u32.to_le_bytes());
+        for (id, offset, size) in [(b"Comp", state_offset, state.len()), (b"Info", info_offset, xml.len())] {
+            data.extend(id);
+            data.extend(offset.to_le_bytes());
+            data.extend((size as u64).to_le_bytes());
+        }
+        data
+    }
+
+    fn vst3_bundle(dir: &Path, name: &str, class_id: &str) -> InstalledPlugin {
+        let bundle = dir.join(format!("{}.vst3", name));
+        fs::create_dir_all(bundle.join("Contents/Resources")).unwrap();
+        let module_info = format!(
+            r#"{{"Name": "{}", "Classes": [{{"CID": "{}", "Category": "Audio Module Class", "Name": "{}"}}]}}"#,
+            name, class_id, name
+        );
+        fs::write(bundle.join("Contents/Resources/moduleinfo.json"), module_info).unwrap();
+        installed_plugin(name, &bundle, PluginFormat::VST3)
+    }
+
+    #[test]
+    fn reads_presets_and_matches_them_to_their_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let xml = r#"<?xml version="1.0"?><MetaInfo><Attr id="Name" value="Warm &amp; Wide" type="string"/><Attr id="PlugInName" value="Synth" type="string"/><Attr id="MusicalCategory" value="Pad" type="string"/></MetaInfo>"#;
+        let path = dir.path().join("Warm.vstpreset");
+        fs::write(&path, preset_bytes(&CLASS_ID.to_lowercase(), xml)).unwrap();
+
+        let preset = read_vstpreset(&path).unwrap();
+        assert_eq!(preset.version, 1);
+        assert_eq!(preset.class_id, CLASS_ID);
+        assert_eq!(preset.chunks, ["Comp", "Info"]);
+        assert_eq!(preset.name.as_deref(), Some("Warm & Wide"));
+        assert_eq!(preset.plugin_name.as_deref(), Some("Synth"));
+        assert_eq!(preset.category.as_deref(), Some("Pad"));
+
+        // The plugin writes its class ID in GUID form
+        let synth = vst3_bundle(dir.path(), "Synth", "56535453-6445-3153-796E-746800000000");
+        let other = vst3_bundle(dir.path(), "Other", "00000000000000000000000000000001");
+        assert!(preset_matches_plugin(&preset, &synth));
+        assert!(!preset_matches_plugin(&preset, &other));
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_presets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Fake.vstpreset");
+        fs::write(&path, [0u8; HEADER_SIZE]).unwrap();
+        assert!(read_vstpreset(&path).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! End-to-end tests of the `plugindepot` command-line tool.
+
+use assert_cmd::Command;
+use plugindepot_core::plugin::PluginFormat;
+use plugindepot_core::registry::ScanConfig;
+use plugindepot_core::state::config_dir;
+use predicates::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Points the tool at a temporary home whose scan config lists only a fixture
+/// folder of VST3 plugins, one with an unreadable moduleinfo.json. Set up once,
+/// since every test shares the process environment. Returns the fixture folder.
+fn fixture_plugins() -> &'static Path {
+    static PLUGINS: OnceLock<PathBuf> = OnceLock::new();
+    PLUGINS.get_or_init(|| {
+        let home = tempfile::tempdir().unwrap().keep();
+        // config_dir() reads these, and the tool inherits them
+        std::env::set_var("HOME", &home);
+        std::env::set_var("APPDATA", home.join("AppData/Roaming"));
+
+        let plugins = home.join("Plugins");
+        for name in ["Test Synth", "Test Verb", "Other Delay"] {
+            fs::create_dir_all(plugins.join(format!("{}.vst3", name))).unwrap();
+        }
+        let resources = plugins.join("Broken.vst3/Contents/Resources");
+        fs::create_dir_all(&resources).unwrap();
+        fs::write(resources.join("moduleinfo.json"), "{").unwrap();
+
+        let config = ScanConfig {
+            include_default_dirs: false,
+            extra_dirs: vec![(plugins.clone(), PluginFormat::VST3)],
+            ..ScanConfig::default()
+        };
+        config.to_file(&config_dir().join("config.json")).unwrap();
+        plugins
+    })
+}
+
+#[test]
+fn dry_run_uninstall_lists_every_matching_plugin() {
+    let plugins = fixture_plugins();
+
+    Command::cargo_bin("plugindepot").unwrap()
+        .args(["uninstall", "Test *", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Test Synth [VST3]"))
+        .stdout(predicate::str::contains("Test Verb [VST3]"))
+        .stdout(predicate::str::contains("Would delete"))
+        .stdout(predicate::str::contains("Other Delay").not());
+
+    for name in ["Test Synth", "Test Verb", "Other Delay"] {
+        assert!(plugins.join(format!("{}.vst3", name)).exists());
+    }
+}
+
+#[test]
+fn json_scan_output_is_only_json() {
+    fixture_plugins();
+
+    let output = Command::cargo_bin("plugindepot").unwrap()
+        .args(["scan", "--json"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Warning"))
+        .get_output()
+        .stdout
+        .clone();
+
+    let entries: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let mut names: Vec<&str> = entries.as_array().unwrap().iter()
+        .map(|entry| entry["name"].as_str().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["Broken", "Other Delay", "Test Synth", "Test Verb"]);
+}